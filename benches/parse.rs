@@ -0,0 +1,22 @@
+use std::fs;
+use criterion::{criterion_group, criterion_main, Criterion};
+use nmea::{NmeaSentence, NmeaSentenceRef};
+
+fn parse(c: &mut Criterion) {
+    let log = fs::read_to_string("test.log").expect("Could not open test.log");
+    let lines: Vec<String> = log.lines().map(|l| l.to_string()).collect();
+
+    c.bench_function("owned", |b| b.iter(|| {
+        for line in &lines {
+            std::hint::black_box(NmeaSentence::from(line));
+        }
+    }));
+    c.bench_function("borrowed", |b| b.iter(|| {
+        for line in &lines {
+            std::hint::black_box(NmeaSentence::from(NmeaSentenceRef::from(line.as_str())));
+        }
+    }));
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use nmea::signalk::SignalKConverter;
+use nmea::{FieldSource, NmeaBaseSentence, NmeaSentence, ParseOptions};
+
+// Seed the corpus with test.log, one sentence per file, so that mutations start from
+// sentences that get past the checksum:
+//     mkdir -p corpus/parse && split -l 1 ../test.log corpus/parse/line
+//     cargo fuzz run parse
+fuzz_target!(|data: &[u8]| {
+    let sentence = NmeaSentence::from(data);
+    let _ = (sentence.to_string(), sentence.talker(), sentence.message_type());
+    sentence.fields().for_each(|(_, value)| drop(value.to_string()));
+    if let Some(base) = sentence.base() {
+        for i in 0..base.field_count() + 1 {
+            let _ = (base.get::<f64>(i), base.get_hex::<u32>(i), base.get_time(i), base.get_date(i));
+        }
+    }
+    let _ = SignalKConverter::new().delta(&sentence.into_owned());
+    if let Ok(line) = std::str::from_utf8(data) {
+        let _ = ParseOptions::lenient().parse(line);
+        let _ = NmeaBaseSentence::from(&line.to_string());
+    }
+});
@@ -0,0 +1,523 @@
+use crate::*;
+
+// Longest sentence allowed, counting the trailing <CR><LF>.
+const MAX_SENTENCE_LENGTH: usize = 82;
+
+// Most sentences one ABM or BBM message may take.
+const MAX_SENTENCES: usize = 9;
+
+// The bits of an AIS message, as carried six to a character in the data field of VDM, VDO,
+// ABM and BBM.  Values are written and read most significant bit first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BinaryPayload {
+    bits: Vec<bool>,
+}
+
+impl BinaryPayload {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut payload = Self::new();
+        bytes.iter().for_each(|b| payload.push(*b as u64, 8));
+        payload
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    // Appends the low width bits of value.
+    pub fn push(&mut self, value: u64, width: u8) {
+        (0..width.min(64)).rev().for_each(|i| self.bits.push(value >> i & 1 == 1));
+    }
+
+    // Appends text in six-bit ASCII, as in the safety related messages 12 and 14.  Lower
+    // case is sent as upper case, and characters with no six-bit code as ?.
+    pub fn push_text(&mut self, text: &str) {
+        for c in text.chars().map(|c| c.to_ascii_uppercase()) {
+            let code = match c as u32 {
+                c @ 64..=95 => c - 64,
+                c @ 32..=63 => c,
+                _ => '?' as u32,
+            };
+            self.push(code as u64, 6);
+        }
+    }
+
+    pub fn get(&self, offset: usize, width: u8) -> Option<u64> {
+        let bits = self.bits.get(offset..offset.checked_add(width as usize)?)?;
+        Some(bits.iter().fold(0, |value, bit| value << 1 | *bit as u64))
+    }
+
+    // Reads chars six-bit characters, dropping the @ padding at the end.
+    pub fn text(&self, offset: usize, chars: usize) -> Option<String> {
+        let text: String = (0..chars)
+            .map(|i| self.get(offset + i * 6, 6).map(|c| if c < 32 { (c as u8 + 64) as char } else { c as u8 as char }))
+            .collect::<Option<_>>()?;
+        Some(text.trim_end_matches('@').to_string())
+    }
+
+    // The data field and the number of fill bits that pad it to a whole character.
+    pub fn armor(&self) -> (String, u8) {
+        let fill_bits = (6 - self.bits.len() % 6) % 6;
+        let data = self.bits
+            .chunks(6)
+            .map(|chunk| {
+                let value = chunk.iter().fold(0u8, |value, bit| value << 1 | *bit as u8) << (6 - chunk.len());
+                (if value < 40 { value + 48 } else { value + 56 }) as char
+            })
+            .collect();
+        (data, fill_bits as u8)
+    }
+
+    // None if data holds a character outside the six-bit alphabet.
+    pub fn dearmor(data: &str, fill_bits: u8) -> Option<Self> {
+        let mut payload = Self::new();
+        for c in data.bytes() {
+            let value = match c {
+                b'0'..=b'W' => c - 48,
+                b'`'..=b'w' => c - 56,
+                _ => return None,
+            };
+            payload.push(value as u64, 6);
+        }
+        let len = payload.bits.len().saturating_sub(fill_bits.min(5) as usize);
+        payload.bits.truncate(len);
+        Some(payload)
+    }
+}
+
+macro_rules! make_payload_accessor {
+    ($($t:ident),*) => {
+        $(
+        impl<B: FieldSource> $t<B> {
+            // The data of this sentence alone; a message split over several sentences has
+            // to be joined first.
+            pub fn payload(&self) -> Option<BinaryPayload> {
+                BinaryPayload::dearmor(&self.data()?, self.fill_bits().unwrap_or(0))
+            }
+        }
+        )*
+    };
+}
+
+make_payload_accessor!(AbmData, BbmData);
+
+// What the transponder reported in ABK.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Acknowledgement {
+    Received,
+    NotReceived,
+    CannotBroadcast,
+    Broadcast,
+    LateReception,
+}
+
+impl Acknowledgement {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Acknowledgement::Received),
+            1 => Some(Acknowledgement::NotReceived),
+            2 => Some(Acknowledgement::CannotBroadcast),
+            3 => Some(Acknowledgement::Broadcast),
+            4 => Some(Acknowledgement::LateReception),
+            _ => None,
+        }
+    }
+}
+
+// A message handed to the transponder and not yet acknowledged.  mmsi is None for a
+// broadcast.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentMessage {
+    pub mmsi: Option<u32>,
+    pub message_id: u8,
+    pub sequential_id: u8,
+}
+
+// Turns payloads into ABM and BBM sentences, each message split over as many sentences as
+// it needs, and matches the transponder's ABK replies to them.  Sequential identifiers
+// run 0 to 3 for addressed messages and 0 to 9 for broadcasts.
+pub struct AisTransmitter {
+    sender: String,
+    next_addressed: u8,
+    next_broadcast: u8,
+    pending: Vec<SentMessage>,
+}
+
+impl AisTransmitter {
+    // sender is the start of each sentence, usually !AI.
+    pub fn new(sender: &str) -> Self {
+        Self { sender: sender.to_string(), next_addressed: 0, next_broadcast: 0, pending: vec![] }
+    }
+
+    pub fn pending(&self) -> &[SentMessage] {
+        &self.pending
+    }
+
+    // None if the payload is longer than the message can carry, or needs more than nine
+    // sentences.
+    pub fn addressed(&mut self, mmsi: u32, channel: u8, message_id: u8, payload: &BinaryPayload) -> Option<Vec<AbmData>> {
+        if max_bits(message_id).is_some_and(|max| payload.len() > max) {
+            return None;
+        }
+        let sequential_id = self.next_addressed;
+        let mmsi_text = format!("{:09}", mmsi);
+        let sentence = |total: u8, number: u8, data: &str, fill_bits: u8| {
+            AbmData::builder(&self.sender)
+                .total(total)
+                .number(number)
+                .sequential_id(sequential_id)
+                .mmsi(&mmsi_text)
+                .channel(channel)
+                .message_id(message_id)
+                .data(data)
+                .fill_bits(fill_bits)
+                .build()
+        };
+        let capacity = MAX_SENTENCE_LENGTH - sentence(9, 9, "", 0).to_string().len() - 2;
+        let sentences = split(payload, capacity)?.into_iter().map(|(total, number, data, fill_bits)| sentence(total, number, &data, fill_bits)).collect();
+
+        self.next_addressed = (sequential_id + 1) % 4;
+        self.sent(SentMessage { mmsi: Some(mmsi), message_id, sequential_id });
+        Some(sentences)
+    }
+
+    pub fn broadcast(&mut self, channel: u8, message_id: u8, payload: &BinaryPayload) -> Option<Vec<BbmData>> {
+        if max_bits(message_id).is_some_and(|max| payload.len() > max) {
+            return None;
+        }
+        let sequential_id = self.next_broadcast;
+        let sentence = |total: u8, number: u8, data: &str, fill_bits: u8| {
+            BbmData::builder(&self.sender)
+                .total(total)
+                .number(number)
+                .sequential_id(sequential_id)
+                .channel(channel)
+                .message_id(message_id)
+                .data(data)
+                .fill_bits(fill_bits)
+                .build()
+        };
+        let capacity = MAX_SENTENCE_LENGTH - sentence(9, 9, "", 0).to_string().len() - 2;
+        let sentences = split(payload, capacity)?.into_iter().map(|(total, number, data, fill_bits)| sentence(total, number, &data, fill_bits)).collect();
+
+        self.next_broadcast = (sequential_id + 1) % 10;
+        self.sent(SentMessage { mmsi: None, message_id, sequential_id });
+        Some(sentences)
+    }
+
+    // The message an ABK refers to, which is no longer pending.  An addressed message may be
+    // acknowledged under its own message ID or that of the acknowledgement, e.g. 7 for 6.
+    pub fn acknowledge<B: FieldSource>(&mut self, abk: &AbkData<B>) -> Option<(SentMessage, Acknowledgement)> {
+        let acknowledgement = Acknowledgement::from_u8(abk.acknowledgement()?)?;
+        let message_id = abk.message_id()?;
+        let sequential_id = abk.sequence_number()?;
+        let mmsi = abk.mmsi().and_then(|m| m.parse::<u32>().ok());
+        let index = self.pending.iter().position(|sent| {
+            let id = sent.message_id as u16;
+            sent.sequential_id as u16 == sequential_id && sent.mmsi == mmsi
+                && (message_id == id || (sent.mmsi.is_some() && message_id == id + 1))
+        })?;
+        Some((self.pending.remove(index), acknowledgement))
+    }
+
+    // A reused sequential identifier replaces the message that had it.
+    fn sent(&mut self, message: SentMessage) {
+        self.pending.retain(|sent| sent.sequential_id != message.sequential_id || sent.mmsi.is_some() != message.mmsi.is_some());
+        self.pending.push(message);
+    }
+}
+
+// The most data bits each message can carry, from the notes to ABM and BBM.  Other
+// messages are held only to the sentence limit.
+fn max_bits(message_id: u8) -> Option<usize> {
+    match message_id {
+        6 => Some(920),
+        8 => Some(952),
+        12 => Some(936),
+        14 => Some(968),
+        25 => Some(128),
+        26 => Some(1004),
+        _ => None,
+    }
+}
+
+// Total, number, data and fill bits for each sentence.  Only the last sentence has fill
+// bits.  The total is a single digit, so a payload needing more than nine sentences gives
+// None.
+fn split(payload: &BinaryPayload, capacity: usize) -> Option<Vec<(u8, u8, String, u8)>> {
+    let (data, fill_bits) = payload.armor();
+    let chunks: Vec<&str> = if data.is_empty() {
+        vec![""]
+    } else {
+        data.as_bytes().chunks(capacity).map(|c| core::str::from_utf8(c).unwrap_or_default()).collect()
+    };
+    if chunks.len() > MAX_SENTENCES {
+        return None;
+    }
+    let total = chunks.len() as u8;
+    let sentences = (1..=total)
+        .zip(chunks)
+        .map(|(number, chunk)| (total, number, chunk.to_string(), if number == total { fill_bits } else { 0 }))
+        .collect();
+    Some(sentences)
+}
+
+// The type of ship and cargo in VSD and AIS message 5.  The u8 in a variant is the second
+// digit, the hazard category of the cargo: 1 to 4 for X, Y, Z and OS, 0 for all of them and
+// 9 when there is nothing more to say.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShipType {
+    NotAvailable,
+    WingInGround(u8),
+    Fishing,
+    Towing,
+    // Towing a tow longer than 200 m or wider than 25 m.
+    TowingLarge,
+    Dredging,
+    Diving,
+    Military,
+    Sailing,
+    PleasureCraft,
+    HighSpeedCraft(u8),
+    PilotVessel,
+    SearchAndRescue,
+    Tug,
+    PortTender,
+    AntiPollution,
+    LawEnforcement,
+    // Types 56 and 57, assigned by local authorities.
+    Local(u8),
+    Medical,
+    Noncombatant,
+    Passenger(u8),
+    Cargo(u8),
+    Tanker(u8),
+    Other(u8),
+    Reserved(u8),
+}
+
+impl From<u8> for ShipType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => ShipType::NotAvailable,
+            20..=29 => ShipType::WingInGround(value - 20),
+            30 => ShipType::Fishing,
+            31 => ShipType::Towing,
+            32 => ShipType::TowingLarge,
+            33 => ShipType::Dredging,
+            34 => ShipType::Diving,
+            35 => ShipType::Military,
+            36 => ShipType::Sailing,
+            37 => ShipType::PleasureCraft,
+            40..=49 => ShipType::HighSpeedCraft(value - 40),
+            50 => ShipType::PilotVessel,
+            51 => ShipType::SearchAndRescue,
+            52 => ShipType::Tug,
+            53 => ShipType::PortTender,
+            54 => ShipType::AntiPollution,
+            55 => ShipType::LawEnforcement,
+            56..=57 => ShipType::Local(value),
+            58 => ShipType::Medical,
+            59 => ShipType::Noncombatant,
+            60..=69 => ShipType::Passenger(value - 60),
+            70..=79 => ShipType::Cargo(value - 70),
+            80..=89 => ShipType::Tanker(value - 80),
+            90..=99 => ShipType::Other(value - 90),
+            _ => ShipType::Reserved(value),
+        }
+    }
+}
+
+impl From<ShipType> for u8 {
+    fn from(value: ShipType) -> Self {
+        match value {
+            ShipType::NotAvailable => 0,
+            ShipType::WingInGround(c) => 20 + c % 10,
+            ShipType::Fishing => 30,
+            ShipType::Towing => 31,
+            ShipType::TowingLarge => 32,
+            ShipType::Dredging => 33,
+            ShipType::Diving => 34,
+            ShipType::Military => 35,
+            ShipType::Sailing => 36,
+            ShipType::PleasureCraft => 37,
+            ShipType::HighSpeedCraft(c) => 40 + c % 10,
+            ShipType::PilotVessel => 50,
+            ShipType::SearchAndRescue => 51,
+            ShipType::Tug => 52,
+            ShipType::PortTender => 53,
+            ShipType::AntiPollution => 54,
+            ShipType::LawEnforcement => 55,
+            ShipType::Local(t) => t,
+            ShipType::Medical => 58,
+            ShipType::Noncombatant => 59,
+            ShipType::Passenger(c) => 60 + c % 10,
+            ShipType::Cargo(c) => 70 + c % 10,
+            ShipType::Tanker(c) => 80 + c % 10,
+            ShipType::Other(c) => 90 + c % 10,
+            ShipType::Reserved(t) => t,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavigationalStatus {
+    UnderWayUsingEngine,
+    AtAnchor,
+    NotUnderCommand,
+    RestrictedManoeuvrability,
+    ConstrainedByDraught,
+    Moored,
+    Aground,
+    EngagedInFishing,
+    UnderWaySailing,
+    // 9 to 13, kept for future use.
+    Reserved(u8),
+    AisSart,
+    NotDefined,
+}
+
+impl NavigationalStatus {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(NavigationalStatus::UnderWayUsingEngine),
+            1 => Some(NavigationalStatus::AtAnchor),
+            2 => Some(NavigationalStatus::NotUnderCommand),
+            3 => Some(NavigationalStatus::RestrictedManoeuvrability),
+            4 => Some(NavigationalStatus::ConstrainedByDraught),
+            5 => Some(NavigationalStatus::Moored),
+            6 => Some(NavigationalStatus::Aground),
+            7 => Some(NavigationalStatus::EngagedInFishing),
+            8 => Some(NavigationalStatus::UnderWaySailing),
+            9..=13 => Some(NavigationalStatus::Reserved(value)),
+            14 => Some(NavigationalStatus::AisSart),
+            15 => Some(NavigationalStatus::NotDefined),
+            _ => None,
+        }
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            NavigationalStatus::UnderWayUsingEngine => 0,
+            NavigationalStatus::AtAnchor => 1,
+            NavigationalStatus::NotUnderCommand => 2,
+            NavigationalStatus::RestrictedManoeuvrability => 3,
+            NavigationalStatus::ConstrainedByDraught => 4,
+            NavigationalStatus::Moored => 5,
+            NavigationalStatus::Aground => 6,
+            NavigationalStatus::EngagedInFishing => 7,
+            NavigationalStatus::UnderWaySailing => 8,
+            NavigationalStatus::Reserved(value) => *value,
+            NavigationalStatus::AisSart => 14,
+            NavigationalStatus::NotDefined => 15,
+        }
+    }
+}
+
+// The four regional application flags of VSD, whose meaning is set by the local authority.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegionalFlags(pub u8);
+
+impl RegionalFlags {
+    // Flag 0 is the least significant bit.
+    pub fn is_set(&self, flag: u8) -> bool {
+        flag < 4 && self.0 >> flag & 1 == 1
+    }
+
+    // A flag past the four there are is ignored.
+    pub fn with(self, flag: u8) -> Self {
+        if flag >= 4 {
+            return self;
+        }
+        RegionalFlags(self.0 | 1 << flag)
+    }
+}
+
+// Characters AIS can send in text: upper case letters, digits and most punctuation.
+fn is_six_bit(c: char) -> bool {
+    (' '..='_').contains(&c)
+}
+
+// AIS is stricter than NMEA: numbers must be in range, and texts short enough and in
+// six-bit ASCII.  The first problem found is returned.
+fn validate_ais<B: FieldSource>(base: &B, fields: &'static [FieldInfo], texts: &[(&'static str, usize, usize)]) -> Result<(), FieldError> {
+    validate_fields(base, fields)?;
+    if let Some(error) = out_of_range(base, fields).next() {
+        return Err(error);
+    }
+    for (name, index, max) in texts {
+        let text = base.get_text(*index).unwrap_or_default();
+        if text.chars().count() > *max {
+            return Err(FieldError::TooLong(name));
+        }
+        if !text.chars().all(is_six_bit) {
+            return Err(FieldError::InvalidCharacter(name));
+        }
+    }
+    Ok(())
+}
+
+impl<B: FieldSource> SsdData<B> {
+    // Overall length and beam in metres, from the distances to the reference point.  A
+    // distance past what AIS can carry, 511 m fore and aft or 63 m abeam, gives None.
+    pub fn length(&self) -> Option<u16> {
+        let (bow, stern) = (self.bow_distance()?, self.stern_distance()?);
+        (bow <= 511 && stern <= 511).then(|| bow + stern)
+    }
+
+    pub fn beam(&self) -> Option<u8> {
+        let (port, starboard) = (self.port_distance()?, self.starboard_distance()?);
+        (port <= 63 && starboard <= 63).then(|| port + starboard)
+    }
+
+    pub fn validate_ais(&self) -> Result<(), FieldError> {
+        validate_ais(&self.base, SsdData::FIELDS, &[("call_sign", 0, 7), ("name", 1, 20)])
+    }
+}
+
+impl<B: FieldSource> VsdData<B> {
+    pub fn ship_type(&self) -> Option<ShipType> {
+        self.ship_and_cargo_type().map(ShipType::from)
+    }
+
+    pub fn navigational_status(&self) -> Option<NavigationalStatus> {
+        NavigationalStatus::from_u8(self.status()?)
+    }
+
+    pub fn regional_flags(&self) -> Option<RegionalFlags> {
+        self.regional_application_flags().map(RegionalFlags)
+    }
+
+    pub fn validate_ais(&self) -> Result<(), FieldError> {
+        validate_ais(&self.base, VsdData::FIELDS, &[("destination", 3, 20)])
+    }
+}
+
+impl SentenceBuilder<VsdData> {
+    pub fn ship_type(self, ship_type: ShipType) -> Self {
+        self.ship_and_cargo_type(ship_type.into())
+    }
+
+    pub fn navigational_status(self, status: NavigationalStatus) -> Self {
+        self.status(status.as_u8())
+    }
+
+    pub fn regional_flags(self, flags: RegionalFlags) -> Self {
+        self.regional_application_flags(flags.0)
+    }
+}
+
+impl SentenceBuilder<AirData> {
+    // Asks a station for one message, e.g. 5 for its static and voyage data.
+    pub fn interrogate(self, mmsi: u32, message: u8) -> Self {
+        self.mmsi(&format!("{:09}", mmsi)).requested_message(message)
+    }
+}
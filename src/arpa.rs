@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetStatus {
+    // Being acquired.
+    Query,
+    Tracking,
+    Lost,
+}
+
+impl TargetStatus {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'Q' => Some(TargetStatus::Query),
+            'T' => Some(TargetStatus::Tracking),
+            'L' => Some(TargetStatus::Lost),
+            _ => None,
+        }
+    }
+}
+
+// Everything known about one target.  Distances, CPA and speeds are in the units the radar
+// sends, given by unit: K, N or S.  Positions are signed ddmm.mmmm, as from the coordinate
+// accessors.  Bearings and courses are in degrees, true.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RadarTarget {
+    pub number: u8,
+    pub distance: Option<f32>,
+    pub bearing_true: Option<f32>,
+    pub speed: Option<f32>,
+    pub course_true: Option<f32>,
+    pub unit: Option<char>,
+    pub cpa: Option<f32>,
+    // Minutes; negative once the closest point has passed.
+    pub tcpa: Option<f32>,
+    pub latitude: Option<f32>,
+    pub longitude: Option<f32>,
+    pub name: Option<String>,
+    pub label: Option<String>,
+    pub status: Option<TargetStatus>,
+    pub reference: bool,
+    pub dangerous: bool,
+    pub first_seen: Instant,
+    pub last_seen: Instant,
+}
+
+impl RadarTarget {
+    fn new(number: u8, now: Instant) -> Self {
+        Self {
+            number,
+            distance: None,
+            bearing_true: None,
+            speed: None,
+            course_true: None,
+            unit: None,
+            cpa: None,
+            tcpa: None,
+            latitude: None,
+            longitude: None,
+            name: None,
+            label: None,
+            status: None,
+            reference: false,
+            dangerous: false,
+            first_seen: now,
+            last_seen: now,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TargetEvent {
+    New(u8),
+    StatusChanged(u8, TargetStatus),
+    // The target came within both danger limits.
+    Dangerous(u8),
+    Expired(u8),
+}
+
+// Joins TTM, TLL and TLB into one table keyed by target number.  OSD and HDT give own
+// ship's heading, which turns relative bearings into true ones; a relative bearing with no
+// heading yet is dropped.  Targets not heard from for max_age are removed by expire().
+pub struct TargetTable {
+    targets: BTreeMap<u8, RadarTarget>,
+    own_heading: Option<f32>,
+    max_age: Duration,
+    danger: Option<(f32, f32)>,
+}
+
+impl Default for TargetTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TargetTable {
+    pub fn new() -> Self {
+        Self {
+            targets: BTreeMap::new(),
+            own_heading: None,
+            max_age: Duration::from_secs(60),
+            danger: None,
+        }
+    }
+
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    // A target is dangerous when its CPA is at most cpa and it will get there within tcpa
+    // minutes.
+    pub fn with_danger_limits(mut self, cpa: f32, tcpa: f32) -> Self {
+        self.danger = Some((cpa, tcpa));
+        self
+    }
+
+    pub fn get(&self, number: u8) -> Option<&RadarTarget> {
+        self.targets.get(&number)
+    }
+
+    pub fn targets(&self) -> impl Iterator<Item = &RadarTarget> {
+        self.targets.values()
+    }
+
+    pub fn own_heading(&self) -> Option<f32> {
+        self.own_heading
+    }
+
+    pub fn update<B: FieldSource>(&mut self, sentence: &NmeaSentence<B>, now: Instant) -> Vec<TargetEvent> {
+        let mut events = vec![];
+        match sentence {
+            NmeaSentence::OSD(osd) if osd.heading_true_value() == Some('A') => {
+                self.own_heading = osd.heading_true().or(self.own_heading);
+            }
+            NmeaSentence::HDT(hdt) => {
+                self.own_heading = hdt.heading_true().map(|h| h.value()).or(self.own_heading);
+            }
+            NmeaSentence::TTM(ttm) => {
+                let Some(number) = ttm.target_number() else { return events };
+                let (own_heading, danger) = (self.own_heading, self.danger);
+                let target = self.target(number, now, &mut events);
+                let status = ttm.status();
+                target.distance = ttm.target_distance().map(|d| d.value());
+                target.unit = ttm.target_distance().or(ttm.target_speed()).map(|d| d.unit());
+                target.bearing_true = ttm.target_bearing().and_then(|b| to_true(b, own_heading));
+                target.speed = ttm.target_speed().map(|s| s.value());
+                target.course_true = ttm.target_course().and_then(|c| to_true(c, own_heading));
+                target.cpa = ttm.cpa();
+                target.tcpa = ttm.tpa();
+                target.name = ttm.name().or(target.name.take());
+                target.reference = ttm.reference() == Some('R');
+                Self::set_status(target, status, &mut events);
+                if let Some((cpa, tcpa)) = danger {
+                    let dangerous = matches!((target.cpa, target.tcpa), (Some(c), Some(t)) if c <= cpa && (0.0..=tcpa).contains(&t));
+                    if dangerous && !target.dangerous {
+                        events.push(TargetEvent::Dangerous(number));
+                    }
+                    target.dangerous = dangerous;
+                }
+            }
+            NmeaSentence::TLL(tll) => {
+                let Some(number) = tll.target_number() else { return events };
+                let target = self.target(number, now, &mut events);
+                target.latitude = tll.base.get_coordinate(1, 2);
+                target.longitude = tll.base.get_coordinate(3, 4);
+                target.name = tll.name().or(target.name.take());
+                target.reference = tll.reference() == Some('R');
+                Self::set_status(target, tll.status(), &mut events);
+            }
+            NmeaSentence::TLB(tlb) => {
+                for (number, label) in tlb.targets().unwrap_or_default() {
+                    self.target(number, now, &mut events).label = Some(label);
+                }
+            }
+            _ => {}
+        }
+        events
+    }
+
+    // Removes the targets not heard from for max_age.
+    pub fn expire(&mut self, now: Instant) -> Vec<TargetEvent> {
+        let max_age = self.max_age;
+        let expired: Vec<u8> = self.targets.values()
+            .filter(|t| now.saturating_duration_since(t.last_seen) > max_age)
+            .map(|t| t.number)
+            .collect();
+        expired.iter().for_each(|number| { self.targets.remove(number); });
+        expired.into_iter().map(TargetEvent::Expired).collect()
+    }
+
+    fn target(&mut self, number: u8, now: Instant, events: &mut Vec<TargetEvent>) -> &mut RadarTarget {
+        let target = self.targets.entry(number).or_insert_with(|| {
+            events.push(TargetEvent::New(number));
+            RadarTarget::new(number, now)
+        });
+        target.last_seen = now;
+        target
+    }
+
+    fn set_status(target: &mut RadarTarget, status: Option<char>, events: &mut Vec<TargetEvent>) {
+        if let Some(status) = status.and_then(TargetStatus::from_char) {
+            if target.status != Some(status) {
+                events.push(TargetEvent::StatusChanged(target.number, status));
+            }
+            target.status = Some(status);
+        }
+    }
+}
+
+fn to_true(angle: ValueWithUnit<f32>, own_heading: Option<f32>) -> Option<f32> {
+    match angle.unit() {
+        'R' => Some((angle.value() + own_heading?) % 360.0),
+        _ => Some(angle.value()),
+    }
+}
@@ -0,0 +1,189 @@
+use crate::*;
+
+// The steering modes an HTC or HTD sentence can select.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SteeringMode {
+    Manual,
+    StandAlone,
+    Heading,
+    Track,
+    Rudder,
+}
+
+impl SteeringMode {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'M' => Some(SteeringMode::Manual),
+            'S' => Some(SteeringMode::StandAlone),
+            'H' => Some(SteeringMode::Heading),
+            'T' => Some(SteeringMode::Track),
+            'R' => Some(SteeringMode::Rudder),
+            _ => None,
+        }
+    }
+
+    pub fn as_char(&self) -> char {
+        match self {
+            SteeringMode::Manual => 'M',
+            SteeringMode::StandAlone => 'S',
+            SteeringMode::Heading => 'H',
+            SteeringMode::Track => 'T',
+            SteeringMode::Rudder => 'R',
+        }
+    }
+}
+
+// HTC and HTD share their first thirteen fields.
+macro_rules! make_steering_helpers {
+    ($($t:ident),*) => {
+        $(
+        impl<B: FieldSource> $t<B> {
+            pub fn steering_mode(&self) -> Option<SteeringMode> {
+                SteeringMode::from_char(self.selected_steering_mode()?)
+            }
+
+            // The commanded rudder angle, negative to port as in RSA.
+            pub fn commanded_rudder(&self) -> Option<f32> {
+                let angle = self.commanded_rudder_angle()?;
+                match self.commanded_rudder_direction() {
+                    Some('L') => Some(-angle),
+                    _ => Some(angle),
+                }
+            }
+        }
+        )*
+    };
+}
+
+make_steering_helpers!(HtcData, HtdData);
+
+// Setters that take the typed values, for commanding a pilot.
+#[cfg(feature = "alloc")]
+impl SentenceBuilder<HtcData> {
+    pub fn steering_mode(self, mode: SteeringMode) -> Self {
+        self.selected_steering_mode(mode.as_char())
+    }
+
+    pub fn commanded_rudder(self, angle: f32) -> Self {
+        let direction = if angle < 0.0 { 'L' } else { 'R' };
+        self.commanded_rudder_angle(angle.abs()).commanded_rudder_direction(direction)
+    }
+}
+
+// What the pilot has been told to do, from HTC, HTD and HSC, next to what the vessel is
+// doing, from RSA, HDG, HDT, HDM and the vessel heading in HTD.  Headings are in degrees;
+// the rudder is negative to port.  Fields stay None until a sentence has set them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AutopilotState {
+    pub override_active: Option<bool>,
+    pub steering_mode: Option<SteeringMode>,
+    pub commanded_rudder: Option<f32>,
+    pub rudder_limit: Option<f32>,
+    pub commanded_heading: Option<f32>,
+    pub commanded_track: Option<f32>,
+    // T or M, for the commanded heading and track.
+    pub heading_reference: Option<char>,
+    pub rudder: Option<f32>,
+    pub heading_true: Option<f32>,
+    pub heading_magnetic: Option<f32>,
+    pub rudder_limit_reached: Option<bool>,
+    pub off_heading: Option<bool>,
+    pub off_track: Option<bool>,
+}
+
+macro_rules! update_commanded {
+    ($state:expr, $data:expr) => {
+        let data = $data;
+        $state.override_active = data.override_status().map(|s| s == 'A').or($state.override_active);
+        $state.steering_mode = data.steering_mode().or($state.steering_mode);
+        $state.commanded_rudder = data.commanded_rudder().or($state.commanded_rudder);
+        $state.rudder_limit = data.commanded_rudder_limit().or($state.rudder_limit);
+        $state.commanded_heading = data.commanded_heading_to_steer().or($state.commanded_heading);
+        $state.commanded_track = data.commanded_track().or($state.commanded_track);
+        $state.heading_reference = data.heading_reference().or($state.heading_reference);
+    };
+}
+
+impl AutopilotState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Sentences that say nothing about the pilot are ignored.
+    pub fn update<B: FieldSource>(&mut self, sentence: &NmeaSentence<B>) {
+        match sentence {
+            NmeaSentence::HTC(htc) => {
+                update_commanded!(self, htc);
+            }
+            NmeaSentence::HTD(htd) => {
+                update_commanded!(self, htd);
+                let limit = |status: Option<char>| status.map(|s| s == 'V');
+                self.rudder_limit_reached = limit(htd.rudder_status()).or(self.rudder_limit_reached);
+                self.off_heading = limit(htd.off_heading_status()).or(self.off_heading);
+                self.off_track = limit(htd.off_track_status()).or(self.off_track);
+                match (htd.vessel_heading(), htd.heading_reference()) {
+                    (Some(heading), Some('T')) => self.heading_true = Some(heading),
+                    (Some(heading), Some('M')) => self.heading_magnetic = Some(heading),
+                    _ => {}
+                }
+            }
+            NmeaSentence::HSC(hsc) => {
+                if let Some(heading) = hsc.heading_true() {
+                    self.commanded_heading = Some(heading.value());
+                    self.heading_reference = Some('T');
+                } else if let Some(heading) = hsc.heading_magnetic() {
+                    self.commanded_heading = Some(heading.value());
+                    self.heading_reference = Some('M');
+                }
+            }
+            NmeaSentence::RSA(rsa) if rsa.starboard_status() == Some('A') => {
+                self.rudder = rsa.starboard_angle().or(self.rudder);
+            }
+            NmeaSentence::HDG(hdg) => {
+                // Deviation turns the sensor heading into magnetic, and variation turns
+                // that into true.  East is added and west subtracted.
+                let signed = |v: ValueWithUnit<f32>| if v.unit() == 'W' { -v.value() } else { v.value() };
+                if let Some(sensor) = hdg.sensor_heading_degrees() {
+                    let magnetic = normalize(sensor + hdg.deviation().map_or(0.0, signed));
+                    self.heading_magnetic = Some(magnetic);
+                    if let Some(variation) = hdg.variation() {
+                        self.heading_true = Some(normalize(magnetic + signed(variation)));
+                    }
+                }
+            }
+            NmeaSentence::HDT(hdt) => {
+                self.heading_true = hdt.heading_true().map(|h| h.value()).or(self.heading_true);
+            }
+            NmeaSentence::HDM(hdm) => {
+                self.heading_magnetic = hdm.heading_magnetic().map(|h| h.value()).or(self.heading_magnetic);
+            }
+            _ => {}
+        }
+    }
+
+    // The vessel heading in the same reference as the commanded heading, true if no
+    // reference has been given.
+    pub fn heading(&self) -> Option<f32> {
+        match self.heading_reference {
+            Some('M') => self.heading_magnetic,
+            _ => self.heading_true,
+        }
+    }
+
+    // How far the vessel is off the commanded heading, from -180 to 180 degrees.
+    // Positive means the pilot has to turn to starboard.
+    pub fn heading_error(&self) -> Option<f32> {
+        let error = normalize(self.commanded_heading? - self.heading()?);
+        Some(if error > 180.0 { error - 360.0 } else { error })
+    }
+
+    // How far the rudder is from where it was told to go; positive means to starboard.
+    pub fn rudder_error(&self) -> Option<f32> {
+        Some(self.commanded_rudder? - self.rudder?)
+    }
+}
+
+fn normalize(heading: f32) -> f32 {
+    let heading = heading % 360.0;
+    if heading < 0.0 { heading + 360.0 } else { heading }
+}
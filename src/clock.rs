@@ -0,0 +1,98 @@
+use std::collections::VecDeque;
+use chrono::{DateTime, Duration, Utc};
+use crate::*;
+
+// Follows the offset between the host clock and GNSS time from ZDA arrivals.  Each ZDA
+// gives one sample, the GNSS time less the host time it was received at and the latency
+// the receiver is known to send with; the offset is the mean of the last window samples.
+// A sample further than max_step from that mean starts the window again, as when the host
+// clock has been set.
+pub struct ClockSource {
+    samples: VecDeque<Duration>,
+    window: usize,
+    latency: Duration,
+    max_step: Duration,
+    last_fix: Option<DateTime<Utc>>,
+}
+
+impl Default for ClockSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClockSource {
+    pub fn new() -> Self {
+        Self {
+            samples: VecDeque::new(),
+            window: 8,
+            latency: Duration::zero(),
+            max_step: Duration::seconds(2),
+            last_fix: None,
+        }
+    }
+
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    // How long after the time it gives the receiver sends ZDA.
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    pub fn with_max_step(mut self, max_step: Duration) -> Self {
+        self.max_step = max_step;
+        self
+    }
+
+    // Returns the sample taken, if the sentence was a ZDA with a full date and time.
+    pub fn update<B: FieldSource>(&mut self, sentence: &NmeaSentence<B>, host: DateTime<Utc>) -> Option<Duration> {
+        let NmeaSentence::ZDA(zda) = sentence else { return None };
+        let gnss = zda.datetime_utc()?;
+        let sample = gnss + self.latency - host;
+        if self.offset().is_some_and(|offset| sample - offset > self.max_step || offset - sample > self.max_step) {
+            self.samples.clear();
+        }
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+        self.last_fix = Some(gnss);
+        Some(sample)
+    }
+
+    // Add to the host time to get GNSS time.
+    pub fn offset(&self) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let total: i64 = self.samples.iter().filter_map(|s| s.num_microseconds()).sum();
+        Some(Duration::microseconds(total / self.samples.len() as i64))
+    }
+
+    // The spread of the samples in the window.
+    pub fn jitter(&self) -> Option<Duration> {
+        Some(*self.samples.iter().max()? - *self.samples.iter().min()?)
+    }
+
+    pub fn samples(&self) -> usize {
+        self.samples.len()
+    }
+
+    // The GNSS time of the last ZDA.
+    pub fn last_fix(&self) -> Option<DateTime<Utc>> {
+        self.last_fix
+    }
+
+    // GNSS time at the given host time.
+    pub fn now(&self, host: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        Some(host + self.offset()?)
+    }
+
+    pub fn now_from_system(&self) -> Option<DateTime<Utc>> {
+        self.now(Utc::now())
+    }
+}
@@ -0,0 +1,575 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike};
+use crate::*;
+
+// Gaps in the clock longer than this are not filled with repeated rows.
+const MAX_RESAMPLE_GAP_SECONDS: i64 = 3600;
+
+pub trait CsvCell {
+    fn suffixes() -> &'static [&'static str];
+    fn cells(&self) -> Vec<String>;
+}
+
+macro_rules! make_csv_cell {
+    ($($t:ty),*) => {
+        $(
+            impl CsvCell for Option<$t> {
+                fn suffixes() -> &'static [&'static str] {
+                    &[""]
+                }
+
+                fn cells(&self) -> Vec<String> {
+                    match self {
+                        Some(v) => vec![v.to_string()],
+                        None => vec!["".to_string()],
+                    }
+                }
+            }
+        )*
+    };
+}
+
+make_csv_cell!(u8, u16, u32, i8, i16, i32, f32, f64, char, String, NaiveTime, NaiveDate);
+
+impl<T: ToString> CsvCell for Option<ValueWithUnit<T>> {
+    fn suffixes() -> &'static [&'static str] {
+        &["_value", "_unit"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        match self {
+            Some(v) => vec![v.value.to_string(), v.unit.to_string()],
+            None => vec!["".to_string(), "".to_string()],
+        }
+    }
+}
+
+fn column_names<S, T: CsvCell>(prefix: &str, name: &str, _accessor: fn(&S) -> T) -> Vec<String> {
+    T::suffixes()
+        .iter()
+        .map(|suffix| format!("{}_{}{}", prefix, name, suffix))
+        .collect()
+}
+
+pub trait CsvRecord {
+    fn prefix() -> &'static str;
+    fn headers() -> Vec<String>;
+    fn cells(&self) -> Vec<String>;
+}
+
+macro_rules! make_csv_record {
+    ($data:ident, $prefix:expr, [$($field:ident),* $(,)?]) => {
+        impl CsvRecord for $data {
+            fn prefix() -> &'static str {
+                $prefix
+            }
+
+            fn headers() -> Vec<String> {
+                let mut headers = Vec::new();
+                $( headers.extend(column_names($prefix, stringify!($field), <$data>::$field)); )*
+                headers
+            }
+
+            fn cells(&self) -> Vec<String> {
+                let mut cells = Vec::new();
+                $( cells.extend(self.$field().cells()); )*
+                cells
+            }
+        }
+    };
+}
+
+make_csv_record!(AamData, "aam", [arrival_status, perpendicular_status, arrival_circle_radius, waypoint_id]);
+make_csv_record!(AbkData, "abk", [mmsi, channel, message_id, sequence_number, acknowledgement]);
+make_csv_record!(AcaData, "aca", [sequence_number, ne_latitude, ne_longitude, sw_latitude, sw_longitude,
+    transition_zone_size, channel_a, channel_a_bandwidth, channel_b, channel_b_bandwidth,
+    tx_rx_mode_control, power_level, information_source, in_use_flag, time]);
+make_csv_record!(AckData, "ack", [alarm_number]);
+make_csv_record!(AcsData, "acs", [sequence_number, mmsi, time, day, month, year]);
+make_csv_record!(AirData, "air", [mmsi, requested_message, sub_section, requested_message_2, sub_section_2,
+    mmsi_station_2, requested_message_station_2, sub_section_station_2]);
+make_csv_record!(AlmData, "alm", [sentence_count_total, sentence_number, satellite_prn, gps_week_number,
+    health, eccentricity, reference_time, inclination_angle, right_ascension_rate, root_semi_major_axis,
+    perigee, ascension_node_longitude, mean_anomaly, clock_parameter_f0, clock_parameter_f1]);
+make_csv_record!(AlrData, "alr", [time, alarm_id, condition, acknowledge_state, text]);
+make_csv_record!(ApaData, "apa", [status_blink_snr_warning, status_cycle_lock_warning, xte, steer_direction,
+    arrival_status, perpendicular_status, bearing_origin_to_destination, destination_id]);
+make_csv_record!(ApbData, "apb", [status_a, status_v, xte, steer_direction, arrival_status, perpendicular_status,
+    bearing_origin_to_destination, destination_id, bearing_present_to_destination, heading_to_steer_to_waypoint]);
+make_csv_record!(BodData, "bod", [bearing_true, bearing_magnetic, destination_waypoint_id, origin_waypoint_id]);
+make_csv_record!(BwcData, "bwc", [time, latitude, longitude, bearing_true, bearing_magnetic, distance,
+    waypoint_id, mode_indicator]);
+make_csv_record!(BwrData, "bwr", [time, latitude, longitude, bearing_true, bearing_magnetic, distance,
+    waypoint_id, mode_indicator]);
+make_csv_record!(BwwData, "bww", [bearing_true, bearing_magnetic, destination_waypoint_id, origin_waypoint_id]);
+make_csv_record!(DbkData, "dbk", [depth_feet, depth_meters, depth_fathoms]);
+make_csv_record!(DbsData, "dbs", [depth_feet, depth_meters, depth_fathoms]);
+make_csv_record!(DbtData, "dbt", [depth_feet, depth_meters, depth_fathoms]);
+make_csv_record!(DcnData, "dcn", [chain_identifier, red_zone_identider, red_lop, red_master_status,
+    green_zone_identifier, green_lop, green_master_status, purple_zone_identifier, purple_lop,
+    purple_master_status, red_navigation_use, green_navigation_use, purple_navigation_use,
+    position_uncertainty, fix_data_basis]);
+make_csv_record!(DptData, "dpt", [water_depth_meters, transducer_offset, maximum_range_scale]);
+make_csv_record!(DtmData, "dtm", [datum_code, datum_subcode, latitude, longitude, altitude, datum]);
+make_csv_record!(FsiData, "fsi", [transmitting_frequency, receiving_frequency, communications_mode, power_level]);
+make_csv_record!(GbsData, "gbs", [time, expected_latitude_error, expected_longitude_error, expected_altitude_error,
+    failed_satellite_id, probability_missed_detection, estimated_bias, standard_deviation_estimated_bias]);
+make_csv_record!(GgaData, "gga", [time, latitude, longitude, quality_indicator, satellites_in_use_count, hdop,
+    antenna_altitude, geoidal_separation, age_differential_gps_data, differential_station_id]);
+make_csv_record!(GlcData, "glc", [gri_microseconds, master_toa_microseconds, master_toa_status,
+    time_difference_1_microseconds, time_difference_1_signal_status,
+    time_difference_2_microseconds, time_difference_2_signal_status,
+    time_difference_3_microseconds, time_difference_3_signal_status,
+    time_difference_4_microseconds, time_difference_4_signal_status,
+    time_difference_5_microseconds, time_difference_5_signal_status]);
+make_csv_record!(GllData, "gll", [latitude, longitude, time, status, mode]);
+make_csv_record!(GnsData, "gns", [time, latitude, longitude, mode, satellites_in_use_count, hdop, antenna_altitude,
+    geoidal_separation, age_differential_gps_data, differential_station_id, navigational_status]);
+make_csv_record!(GrsData, "grs", [time, residual_calculation, satellite_1_residual, satellite_2_residual,
+    satellite_3_residual, satellite_4_residual, satellite_5_residual, satellite_6_residual, satellite_7_residual,
+    satellite_8_residual, satellite_9_residual, satellite_10_residual, satellite_11_residual,
+    satellite_12_residual, system_id_1, system_id_2]);
+make_csv_record!(GsaData, "gsa", [selection_mode, mode, satellite_id_1, satellite_id_2, satellite_id_3,
+    satellite_id_4, satellite_id_5, satellite_id_6, satellite_id_7, satellite_id_8, satellite_id_9,
+    satellite_id_10, satellite_id_11, satellite_id_12, pdop, hdop, vdop, system_id]);
+make_csv_record!(GstData, "gst", [time, total_rms_standard_deviation, semi_major_error_standard_deviation,
+    semi_minor_errpr_standard_deviation, semi_major_orientation, latitude_error_standard_deviation,
+    longitude_error_standard_deviation, altitude_error_standard_deviation]);
+make_csv_record!(GsvData, "gsv", [total_number_of_sentences, sentence_number, total_number_of_satellites_in_view]);
+make_csv_record!(GxaData, "gxa", [time, latitude, longitude, waypoint_id, satellite_id]);
+make_csv_record!(HdgData, "hdg", [sensor_heading_degrees, deviation, variation]);
+make_csv_record!(HdmData, "hdm", [heading_magnetic]);
+make_csv_record!(HdtData, "hdt", [heading_true]);
+make_csv_record!(HfbData, "hfb", [distance_headrope_to_footrope, distance_headrope_to_bottom]);
+make_csv_record!(HscData, "hsc", [heading_true, heading_magnetic]);
+make_csv_record!(ItsData, "its", [second_spread_distance]);
+make_csv_record!(LcdData, "lcd", [gri_microseconds, master_relative_snr, master_relative_ecd,
+    time_difference_1_microseconds, time_difference_1_status,
+    time_difference_2_microseconds, time_difference_2_status,
+    time_difference_3_microseconds, time_difference_3_status,
+    time_difference_4_microseconds, time_difference_4_status,
+    time_difference_5_microseconds, time_difference_5_status]);
+make_csv_record!(MdaData, "mda", [barometric_pressure_mercury, barometric_pressure_bars, air_temperature_c,
+    water_temperature_c, relative_humidity, absolute_humidity, dew_point_c, wind_direction_true,
+    wind_direction_magnetic, wind_speed_knots, wind_speed_mps]);
+make_csv_record!(MskData, "msk", [beacon_frequency, frequency_mode, beacon_bit_rate, bitrate_mode,
+    interval_for_mss_message]);
+make_csv_record!(MssData, "mss", [signal_strength, snr, beacon_frequency, beacon_bit_rate, channel_number]);
+make_csv_record!(MtwData, "mtw", [temperature]);
+make_csv_record!(MwdData, "mwd", [wind_direction_true, wind_direction_magnetic, wind_speed_knots, wind_speed_mps]);
+make_csv_record!(MwvData, "mwv", [wind_angle, wind_speed, status]);
+make_csv_record!(OlnData, "oln", [omega1_name, omega1_first, omega1_second, omega2_name, omega2_first,
+    omega2_second, omega3_name, omega3_first, omega3_second]);
+make_csv_record!(OsdData, "osd", [heading_true, heading_true_value, course_true, course_reference, speed,
+    speed_reference, set_true, drift]);
+make_csv_record!(RlmData, "rlm", [beacon_id, time, message_code, message_body]);
+make_csv_record!(RmbData, "rmb", [status, xte, direction_to_steer, origin_waypoint_id, destination_waypoint_id,
+    destination_latitude, destination_longitude, destination_range, destination_bearing, vmg_knots,
+    arrival_status, mode]);
+make_csv_record!(RmcData, "rmc", [time, status, latitude, longitude, sog_knots, track_made_good_true, date,
+    variation, mode, nav_status]);
+make_csv_record!(RotData, "rot", [rate, valid]);
+make_csv_record!(RpmData, "rpm", [source, source_number, rpms, propeller_pitch, status]);
+make_csv_record!(RsaData, "rsa", [starboard_angle, starboard_status, port_angle, port_status]);
+make_csv_record!(RsdData, "rsd", [origin_1_range, origin_1_bearing, vrm_1, bearing_line_1, origin_2_range,
+    origin_2_bearing, vrm_3, bearing_line_2, cursor_range, cursor_bearing, range_scale, display_rotation]);
+make_csv_record!(RteData, "rte", [total_sentence_count, sentence_number, sentence_mode, route_name]);
+make_csv_record!(Sf1Data, "sf1", [total_sentence_count, sentence_number]);
+make_csv_record!(StnData, "stn", [talker_id]);
+make_csv_record!(TdsData, "tds", [distance_between_doors]);
+make_csv_record!(TfiData, "tfi", [sensor_1, sensor_2, sensor_3]);
+make_csv_record!(TllData, "tll", [target_number, latitude, longitude, name, time, status, reference]);
+make_csv_record!(TpcData, "tpc", [horizontal_distance_from_centerline, horizontal_distance_from_transducer, depth]);
+make_csv_record!(TprData, "tpr", [horizontal_range, bearing_to_target, separator, depth]);
+make_csv_record!(TptData, "tpt", [horizontal_range, bearing_true, separator, depth]);
+make_csv_record!(TrfData, "trf", [time, date, latitude, longitude, elevation_angle, iteration_count,
+    dopper_count, update_distance, satellite_id, data_validity]);
+make_csv_record!(TtmData, "ttm", [target_number, target_distance, target_bearing, target_speed, target_course,
+    cpa, tpa, name, status, reference, time, report_type]);
+make_csv_record!(VbwData, "vbw", [longitudinal_water_speed, transverse_water_speed, status_water_speed,
+    longitudinal_ground_speed, transverse_ground_speed, status_ground_speed, stern_traverse_water_speed_knots,
+    status_stern_traverse_water_speed, stern_traverse_ground_speed_knots, status_stern_traverse_ground_speed]);
+make_csv_record!(VdrData, "vdr", [direction_true, direction_magnetic, current_speed]);
+make_csv_record!(VhwData, "vhw", [heading_true, heading_magnetic, stw_knots, stw_kph]);
+make_csv_record!(VlwData, "vlw", [water_distance_total, water_distance_since_reset, ground_distance_total,
+    ground_distance_since_reset]);
+make_csv_record!(VpwData, "vpw", [speed_knots, speed_mps]);
+make_csv_record!(VtgData, "vtg", [cog_true, cog_magnetic, sog_knots, sog_kph, mode]);
+make_csv_record!(VwrData, "vwr", [wind_direction, speed_knots, speed_mps, speed_kph]);
+make_csv_record!(WcvData, "wcv", [velocity_knot, waypoint_id, mode]);
+make_csv_record!(WncData, "wnc", [distance_nm, distance_km, destination_waypoint_id, origin_waypoint_id]);
+make_csv_record!(WplData, "wpl", [latitude, longitude, waypoint_id]);
+make_csv_record!(XteData, "xte", [status, loran_status, xte, direction_to_steer, mode]);
+make_csv_record!(XtrData, "xtr", [xte, direction_to_steer]);
+make_csv_record!(ZdaData, "zda", [time, day, month, year, local_zone_description, local_zone_minutes]);
+make_csv_record!(ZfoData, "zfo", [time, elapsed_time, origin_waypoint_id]);
+make_csv_record!(ZtgData, "ztg", [time, time_remaining, destination_waypoint_id]);
+
+// One decoded line: the table it belongs to, the talker, its headers and its rows of cells.
+struct CsvLine {
+    prefix: &'static str,
+    talker: String,
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+fn make_line<T: CsvRecord + NmeaBaseTrait>(data: &T) -> CsvLine {
+    CsvLine {
+        prefix: T::prefix(),
+        talker: data.talker().to_string(),
+        headers: T::headers(),
+        rows: vec![data.cells()],
+    }
+}
+
+fn make_xdr_line(data: &XdrData) -> CsvLine {
+    let rows = data.measurements().unwrap_or_default()
+        .into_iter()
+        .map(|m| vec![
+            m.transducer_type.to_string(),
+            m.data.value.to_string(),
+            m.data.unit.to_string(),
+            m.name,
+        ])
+        .collect();
+    CsvLine {
+        prefix: "xdr",
+        talker: data.talker().to_string(),
+        headers: ["xdr_transducer_type", "xdr_value", "xdr_unit", "xdr_name"]
+            .iter().map(|h| h.to_string()).collect(),
+        rows,
+    }
+}
+
+fn csv_line(sentence: &NmeaSentence) -> Option<CsvLine> {
+    match sentence {
+        NmeaSentence::AAM(d) => Some(make_line(d)),
+        NmeaSentence::ABK(d) => Some(make_line(d)),
+        NmeaSentence::ACA(d) => Some(make_line(d)),
+        NmeaSentence::ACK(d) => Some(make_line(d)),
+        NmeaSentence::ACS(d) => Some(make_line(d)),
+        NmeaSentence::AIR(d) => Some(make_line(d)),
+        NmeaSentence::ALM(d) => Some(make_line(d)),
+        NmeaSentence::ALR(d) => Some(make_line(d)),
+        NmeaSentence::APA(d) => Some(make_line(d)),
+        NmeaSentence::APB(d) => Some(make_line(d)),
+        NmeaSentence::BOD(d) => Some(make_line(d)),
+        NmeaSentence::BWC(d) => Some(make_line(d)),
+        NmeaSentence::BWR(d) => Some(make_line(d)),
+        NmeaSentence::BWW(d) => Some(make_line(d)),
+        NmeaSentence::DBK(d) => Some(make_line(d)),
+        NmeaSentence::DBS(d) => Some(make_line(d)),
+        NmeaSentence::DBT(d) => Some(make_line(d)),
+        NmeaSentence::DCN(d) => Some(make_line(d)),
+        NmeaSentence::DPT(d) => Some(make_line(d)),
+        NmeaSentence::DTM(d) => Some(make_line(d)),
+        NmeaSentence::FSI(d) => Some(make_line(d)),
+        NmeaSentence::GBS(d) => Some(make_line(d)),
+        NmeaSentence::GGA(d) => Some(make_line(d)),
+        NmeaSentence::GLC(d) => Some(make_line(d)),
+        NmeaSentence::GLL(d) => Some(make_line(d)),
+        NmeaSentence::GNS(d) => Some(make_line(d)),
+        NmeaSentence::GRS(d) => Some(make_line(d)),
+        NmeaSentence::GSA(d) => Some(make_line(d)),
+        NmeaSentence::GST(d) => Some(make_line(d)),
+        NmeaSentence::GSV(d) => Some(make_line(d)),
+        NmeaSentence::GXA(d) => Some(make_line(d)),
+        NmeaSentence::HDG(d) => Some(make_line(d)),
+        NmeaSentence::HDM(d) => Some(make_line(d)),
+        NmeaSentence::HDT(d) => Some(make_line(d)),
+        NmeaSentence::HFB(d) => Some(make_line(d)),
+        NmeaSentence::HSC(d) => Some(make_line(d)),
+        NmeaSentence::ITS(d) => Some(make_line(d)),
+        NmeaSentence::LCD(d) => Some(make_line(d)),
+        NmeaSentence::MDA(d) => Some(make_line(d)),
+        NmeaSentence::MSK(d) => Some(make_line(d)),
+        NmeaSentence::MSS(d) => Some(make_line(d)),
+        NmeaSentence::MTW(d) => Some(make_line(d)),
+        NmeaSentence::MWD(d) => Some(make_line(d)),
+        NmeaSentence::MWV(d) => Some(make_line(d)),
+        NmeaSentence::OLN(d) => Some(make_line(d)),
+        NmeaSentence::OSD(d) => Some(make_line(d)),
+        NmeaSentence::RLM(d) => Some(make_line(d)),
+        NmeaSentence::RMB(d) => Some(make_line(d)),
+        NmeaSentence::RMC(d) => Some(make_line(d)),
+        NmeaSentence::ROT(d) => Some(make_line(d)),
+        NmeaSentence::RPM(d) => Some(make_line(d)),
+        NmeaSentence::RSA(d) => Some(make_line(d)),
+        NmeaSentence::RSD(d) => Some(make_line(d)),
+        NmeaSentence::RTE(d) => Some(make_line(d)),
+        NmeaSentence::SF1(d) => Some(make_line(d)),
+        NmeaSentence::STN(d) => Some(make_line(d)),
+        NmeaSentence::TDS(d) => Some(make_line(d)),
+        NmeaSentence::TFI(d) => Some(make_line(d)),
+        NmeaSentence::TLL(d) => Some(make_line(d)),
+        NmeaSentence::TPC(d) => Some(make_line(d)),
+        NmeaSentence::TPR(d) => Some(make_line(d)),
+        NmeaSentence::TPT(d) => Some(make_line(d)),
+        NmeaSentence::TRF(d) => Some(make_line(d)),
+        NmeaSentence::TTM(d) => Some(make_line(d)),
+        NmeaSentence::VBW(d) => Some(make_line(d)),
+        NmeaSentence::VDR(d) => Some(make_line(d)),
+        NmeaSentence::VHW(d) => Some(make_line(d)),
+        NmeaSentence::VLW(d) => Some(make_line(d)),
+        NmeaSentence::VPW(d) => Some(make_line(d)),
+        NmeaSentence::VTG(d) => Some(make_line(d)),
+        NmeaSentence::VWR(d) => Some(make_line(d)),
+        NmeaSentence::WCV(d) => Some(make_line(d)),
+        NmeaSentence::WNC(d) => Some(make_line(d)),
+        NmeaSentence::WPL(d) => Some(make_line(d)),
+        NmeaSentence::XDR(d) => Some(make_xdr_line(d)),
+        NmeaSentence::XTE(d) => Some(make_line(d)),
+        NmeaSentence::XTR(d) => Some(make_line(d)),
+        NmeaSentence::ZDA(d) => Some(make_line(d)),
+        NmeaSentence::ZFO(d) => Some(make_line(d)),
+        NmeaSentence::ZTG(d) => Some(make_line(d)),
+        _ => None,
+    }
+}
+
+fn sentence_time(sentence: &NmeaSentence) -> Option<NaiveTime> {
+    match sentence {
+        NmeaSentence::GGA(d) => d.time(),
+        NmeaSentence::GLL(d) => d.time(),
+        NmeaSentence::GNS(d) => d.time(),
+        NmeaSentence::RMC(d) => d.time(),
+        NmeaSentence::ZDA(d) => d.time(),
+        _ => None,
+    }
+}
+
+fn sentence_date(sentence: &NmeaSentence) -> Option<NaiveDate> {
+    match sentence {
+        NmeaSentence::RMC(d) => d.date(),
+        NmeaSentence::ZDA(d) => match (d.year(), d.month(), d.day()) {
+            (Some(year), Some(month), Some(day)) => NaiveDate::from_ymd_opt(year, month, day),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CsvTable {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    pub fn headers(&self) -> &Vec<String> {
+        &self.headers
+    }
+
+    pub fn rows(&self) -> &Vec<Vec<String>> {
+        &self.rows
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W, delimiter: char) -> io::Result<()> {
+        write_record(writer, &self.headers, delimiter)?;
+        for row in &self.rows {
+            write_record(writer, row, delimiter)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_record<W: Write>(writer: &mut W, record: &[String], delimiter: char) -> io::Result<()> {
+    let cells = record.iter()
+        .map(|cell| {
+            if cell.contains(delimiter) || cell.contains('"') || cell.contains('\n') || cell.contains('\r') {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.clone()
+            }
+        })
+        .collect::<Vec<String>>();
+    writeln!(writer, "{}", cells.join(&delimiter.to_string()))
+}
+
+pub struct CsvExporter {
+    delimiter: char,
+}
+
+impl Default for CsvExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CsvExporter {
+    pub fn new() -> Self {
+        Self { delimiter: ',' }
+    }
+
+    pub fn tsv() -> Self {
+        Self { delimiter: '\t' }
+    }
+
+    pub fn with_delimiter(delimiter: char) -> Self {
+        Self { delimiter }
+    }
+
+    pub fn delimiter(&self) -> char {
+        self.delimiter
+    }
+
+    pub fn extension(&self) -> &'static str {
+        if self.delimiter == '\t' { "tsv" } else { "csv" }
+    }
+
+    // One table per sentence type, keyed by the lowercase message type.  Every table starts
+    // with the line number in the log and the talker, followed by the sentence's own columns.
+    pub fn tables_by_type<R: BufRead>(&self, reader: R) -> io::Result<BTreeMap<String, CsvTable>> {
+        let mut tables: BTreeMap<String, CsvTable> = BTreeMap::new();
+        for (n, line) in reader.lines().enumerate() {
+            let line = line?;
+            let sentence = NmeaSentence::from(&line);
+            if let Some(csv_line) = csv_line(&sentence) {
+                let table = tables.entry(csv_line.prefix.to_string()).or_insert_with(|| {
+                    let mut headers = vec!["line".to_string(), "talker".to_string()];
+                    headers.extend(csv_line.headers.iter().cloned());
+                    CsvTable { headers, rows: vec![] }
+                });
+                for cells in csv_line.rows {
+                    let mut row = vec![(n + 1).to_string(), csv_line.talker.clone()];
+                    row.extend(cells);
+                    table.rows.push(row);
+                }
+            }
+        }
+        Ok(tables)
+    }
+
+    pub fn write_by_type<R: BufRead>(&self, reader: R, directory: &Path) -> io::Result<()> {
+        fs::create_dir_all(directory)?;
+        for (name, table) in self.tables_by_type(reader)? {
+            let path = directory.join(format!("{}.{}", name, self.extension()));
+            let mut file = io::BufWriter::new(fs::File::create(path)?);
+            table.write(&mut file, self.delimiter)?;
+        }
+        Ok(())
+    }
+
+    // A wide table with one row per second of GNSS time and one column per channel, holding
+    // the latest value seen for that channel.  XDR measurements become one channel per
+    // transducer name.  Lines seen before the first time fix are carried into the first row.
+    pub fn resampled<R: BufRead>(&self, reader: R) -> io::Result<CsvTable> {
+        let mut resampler = Resampler::default();
+        for line in reader.lines() {
+            let line = line?;
+            resampler.push(&NmeaSentence::from(&line));
+        }
+        Ok(resampler.finish())
+    }
+
+    pub fn write_resampled<R: BufRead, W: Write>(&self, reader: R, writer: &mut W) -> io::Result<()> {
+        self.resampled(reader)?.write(writer, self.delimiter)
+    }
+}
+
+#[derive(Default)]
+struct Resampler {
+    columns: Vec<String>,
+    column_index: HashMap<String, usize>,
+    latest: Vec<String>,
+    rows: Vec<Vec<String>>,
+    date: Option<NaiveDate>,
+    current: Option<i64>,
+}
+
+impl Resampler {
+    fn set(&mut self, column: &str, value: String) {
+        let index = match self.column_index.get(column) {
+            Some(i) => *i,
+            None => {
+                self.columns.push(column.to_string());
+                self.column_index.insert(column.to_string(), self.columns.len() - 1);
+                self.latest.push("".to_string());
+                self.columns.len() - 1
+            }
+        };
+        self.latest[index] = value;
+    }
+
+    fn label(&self, second: i64) -> String {
+        let days = second.div_euclid(86400);
+        let seconds = second.rem_euclid(86400) as u32;
+        let time = NaiveTime::from_num_seconds_from_midnight_opt(seconds, 0).unwrap_or_default();
+        if self.date.is_some() {
+            match NaiveDate::from_num_days_from_ce_opt(days as i32) {
+                Some(date) => format!("{}T{}", date, time),
+                None => time.to_string(),
+            }
+        } else {
+            time.to_string()
+        }
+    }
+
+    fn emit(&mut self, second: i64) {
+        let mut row = vec![self.label(second)];
+        row.extend(self.latest.iter().cloned());
+        self.rows.push(row);
+    }
+
+    fn tick(&mut self, time: NaiveTime) {
+        let days = self.date.map(|d| d.num_days_from_ce() as i64).unwrap_or(0);
+        let second = days * 86400 + time.num_seconds_from_midnight() as i64;
+        match self.current {
+            Some(current) if second > current => {
+                self.emit(current);
+                if second - current <= MAX_RESAMPLE_GAP_SECONDS {
+                    for s in current + 1..second {
+                        self.emit(s);
+                    }
+                }
+                self.current = Some(second);
+            }
+            Some(current) if second < current => {
+                self.emit(current);
+                self.current = Some(second);
+            }
+            Some(_) => {}
+            None => self.current = Some(second),
+        }
+    }
+
+    fn push(&mut self, sentence: &NmeaSentence) {
+        if let Some(date) = sentence_date(sentence) {
+            self.date = Some(date);
+        }
+        if let Some(time) = sentence_time(sentence) {
+            self.tick(time);
+        }
+        if let NmeaSentence::XDR(d) = sentence {
+            for m in d.measurements().unwrap_or_default() {
+                let name = m.name.to_lowercase();
+                self.set(&format!("xdr_{}_value", name), m.data.value.to_string());
+                self.set(&format!("xdr_{}_unit", name), m.data.unit.to_string());
+            }
+        } else if let Some(csv_line) = csv_line(sentence) {
+            for cells in csv_line.rows {
+                for (header, cell) in csv_line.headers.iter().zip(cells) {
+                    if !cell.is_empty() {
+                        self.set(header, cell);
+                    }
+                }
+            }
+        }
+    }
+
+    fn finish(mut self) -> CsvTable {
+        if let Some(current) = self.current {
+            self.emit(current);
+        }
+        let width = self.columns.len() + 1;
+        for row in self.rows.iter_mut() {
+            row.resize(width, "".to_string());
+        }
+        let mut headers = vec!["timestamp".to_string()];
+        headers.extend(self.columns);
+        CsvTable { headers, rows: self.rows }
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+use crate::*;
+
+// One layer of a current profile.  Directions are where the current flows towards, in
+// degrees true when the sentence gave a true heading to turn a relative direction round.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrentLayer {
+    pub layer: u8,
+    // Metres.
+    pub depth: Option<f32>,
+    pub direction: Option<f32>,
+    // Knots.
+    pub speed: Option<f32>,
+    // B = bottom track, W = water track, P = positioning system.
+    pub speed_reference: Option<char>,
+}
+
+impl<B: FieldSource> CurData<B> {
+    pub fn direction_true(&self) -> Option<f32> {
+        let direction = self.direction()?;
+        match (self.direction_reference(), self.heading_reference()) {
+            (Some('R'), Some('T')) => Some((direction + self.heading()?) % 360.0),
+            (Some('R'), _) => None,
+            _ => Some(direction),
+        }
+    }
+}
+
+// The layers of one data set, in order of depth.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CurrentProfile {
+    pub data_set: u8,
+    pub layers: Vec<CurrentLayer>,
+}
+
+impl CurrentProfile {
+    // The shallowest layer with a known depth.
+    pub fn surface(&self) -> Option<&CurrentLayer> {
+        self.layers.iter().find(|l| l.depth.is_some())
+    }
+
+    // The layer whose depth is nearest to depth.
+    pub fn at_depth(&self, depth: f32) -> Option<&CurrentLayer> {
+        self.layers
+            .iter()
+            .filter_map(|l| Some((l, (l.depth? - depth).abs())))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(l, _)| l)
+    }
+}
+
+// Groups CUR layers into a profile per data set.  A layer that comes again replaces the
+// one before; invalid sentences are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct CurrentProfiles {
+    profiles: BTreeMap<u8, CurrentProfile>,
+}
+
+impl CurrentProfiles {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push<B: FieldSource>(&mut self, cur: &CurData<B>) -> Option<&CurrentProfile> {
+        if cur.validity() != Some('A') {
+            return None;
+        }
+        let data_set = cur.data_set().unwrap_or(0);
+        let layer = CurrentLayer {
+            layer: cur.layer()?,
+            depth: cur.depth(),
+            direction: cur.direction_true(),
+            speed: cur.speed(),
+            speed_reference: cur.speed_reference(),
+        };
+        let profile = self.profiles.entry(data_set).or_insert_with(|| CurrentProfile { data_set, layers: vec![] });
+        profile.layers.retain(|l| l.layer != layer.layer);
+        profile.layers.push(layer);
+        profile.layers.sort_by(|a, b| a.depth.unwrap_or(f32::MAX).total_cmp(&b.depth.unwrap_or(f32::MAX)));
+        Some(profile)
+    }
+
+    pub fn get(&self, data_set: u8) -> Option<&CurrentProfile> {
+        self.profiles.get(&data_set)
+    }
+
+    pub fn profiles(&self) -> impl Iterator<Item = &CurrentProfile> {
+        self.profiles.values()
+    }
+}
+
+// Set, in degrees true, and drift, in knots, of the current.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Current {
+    pub set: f32,
+    pub drift: f32,
+}
+
+// Works out the surface current as the difference between how the boat moves over the
+// ground, from RMC or VTG, and through the water, from VHW, with HDT for the heading when
+// VHW has none.  Leeway is taken to be nil.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SetAndDrift {
+    pub course_over_ground: Option<f32>,
+    pub speed_over_ground: Option<f32>,
+    pub heading: Option<f32>,
+    pub speed_through_water: Option<f32>,
+}
+
+impl SetAndDrift {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update<B: FieldSource>(&mut self, sentence: &NmeaSentence<B>) {
+        match sentence {
+            NmeaSentence::RMC(rmc) if rmc.status() == Some('A') => {
+                self.course_over_ground = rmc.track_made_good_true().or(self.course_over_ground);
+                self.speed_over_ground = rmc.sog_knots().or(self.speed_over_ground);
+            }
+            NmeaSentence::VTG(vtg) if vtg.mode() != Some('N') => {
+                self.course_over_ground = vtg.cog_true().map(|c| c.value()).or(self.course_over_ground);
+                self.speed_over_ground = vtg.sog_knots().map(|s| s.value()).or(self.speed_over_ground);
+            }
+            NmeaSentence::VHW(vhw) => {
+                self.heading = vhw.heading_true().map(|h| h.value()).or(self.heading);
+                self.speed_through_water = vhw.stw_knots().map(|s| s.value()).or(self.speed_through_water);
+            }
+            NmeaSentence::HDT(hdt) => {
+                self.heading = hdt.heading_true().map(|h| h.value()).or(self.heading);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn current(&self) -> Option<Current> {
+        let (cog, sog) = (self.course_over_ground?.to_radians(), self.speed_over_ground?);
+        let (heading, stw) = (self.heading?.to_radians(), self.speed_through_water?);
+        let east = sog * cog.sin() - stw * heading.sin();
+        let north = sog * cog.cos() - stw * heading.cos();
+        let set = east.atan2(north).to_degrees();
+        Some(Current { set: if set < 0.0 { set + 360.0 } else { set }, drift: east.hypot(north) })
+    }
+
+    pub fn to_vdr(&self, sender: &str) -> Option<VdrData> {
+        let current = self.current()?;
+        Some(VdrData::builder(sender).direction_true(round(current.set), 'T').current_speed(round(current.drift), 'N').build())
+    }
+
+    // The current as the one layer of a profile, measured by the positioning system.
+    pub fn to_cur(&self, sender: &str, data_set: u8) -> Option<CurData> {
+        let current = self.current()?;
+        Some(
+            CurData::builder(sender)
+                .validity('A')
+                .data_set(data_set)
+                .layer(1)
+                .direction(round(current.set))
+                .direction_reference('T')
+                .speed(round(current.drift))
+                .heading(round(self.heading?))
+                .heading_reference('T')
+                .speed_reference('P')
+                .build(),
+        )
+    }
+}
+
+// To the tenth, as set and drift are sent.
+fn round(value: f32) -> f32 {
+    (value * 10.0).round() / 10.0
+}
@@ -0,0 +1,286 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use crate::units::{kelvin, pascal, KELVIN_OFFSET};
+use crate::*;
+
+// Readings further apart than this are not joined up when adding up running time and fuel,
+// so a gap in the log does not count as hours run.
+const MAX_GAP: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TelegraphOrder {
+    Stop,
+    AheadDeadSlow,
+    AheadSlow,
+    AheadHalf,
+    AheadFull,
+    NavigationFull,
+    AsternDeadSlow,
+    AsternSlow,
+    AsternHalf,
+    AsternFull,
+    CrashAstern,
+}
+
+impl TelegraphOrder {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(TelegraphOrder::Stop),
+            1 => Some(TelegraphOrder::AheadDeadSlow),
+            2 => Some(TelegraphOrder::AheadSlow),
+            3 => Some(TelegraphOrder::AheadHalf),
+            4 => Some(TelegraphOrder::AheadFull),
+            5 => Some(TelegraphOrder::NavigationFull),
+            11 => Some(TelegraphOrder::AsternDeadSlow),
+            12 => Some(TelegraphOrder::AsternSlow),
+            13 => Some(TelegraphOrder::AsternHalf),
+            14 => Some(TelegraphOrder::AsternFull),
+            15 => Some(TelegraphOrder::CrashAstern),
+            _ => None,
+        }
+    }
+}
+
+impl<B: FieldSource> EtlData<B> {
+    pub fn order(&self) -> Option<TelegraphOrder> {
+        TelegraphOrder::from_u8(self.telegraph_position()?)
+    }
+}
+
+// Everything known about one engine.  Temperatures are in degrees Celsius, pressures in
+// pascals, voltage in volts and fuel rate in litres per hour.  Fields stay None until a
+// sentence has set them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Engine {
+    pub number: u8,
+    pub rpm: Option<f32>,
+    pub shaft_rpm: Option<f32>,
+    // Percent of maximum; negative means astern.
+    pub pitch: Option<f32>,
+    pub telegraph_order: Option<TelegraphOrder>,
+    pub telegraph_answer: Option<TelegraphOrder>,
+    pub coolant_temperature: Option<f64>,
+    pub oil_temperature: Option<f64>,
+    pub oil_pressure: Option<f64>,
+    pub alternator_voltage: Option<f64>,
+    pub fuel_rate: Option<f64>,
+    pub overspeed: bool,
+    pub overheat: bool,
+    running_time: Duration,
+    revolutions: f64,
+    fuel_used: f64,
+    last_rpm: Option<Instant>,
+    last_fuel_rate: Option<Instant>,
+}
+
+impl Engine {
+    // Time spent turning since the monitor started, in hours.
+    pub fn hours(&self) -> f64 {
+        self.running_time.as_secs_f64() / 3600.0
+    }
+
+    pub fn running_time(&self) -> Duration {
+        self.running_time
+    }
+
+    // The average while running, weighted by time.
+    pub fn average_rpm(&self) -> Option<f64> {
+        let minutes = self.running_time.as_secs_f64() / 60.0;
+        (minutes > 0.0).then(|| self.revolutions / minutes)
+    }
+
+    // Litres, from the fuel rate.
+    pub fn fuel_used(&self) -> f64 {
+        self.fuel_used
+    }
+
+    // Litres per hour of running time.
+    pub fn average_fuel_rate(&self) -> Option<f64> {
+        let hours = self.hours();
+        (hours > 0.0).then(|| self.fuel_used / hours)
+    }
+
+    fn set_rpm(&mut self, rpm: f32, now: Instant) {
+        if let (Some(previous), Some(last)) = (self.rpm, self.last_rpm) {
+            let elapsed = now.saturating_duration_since(last);
+            if previous != 0.0 && elapsed <= MAX_GAP {
+                self.running_time += elapsed;
+                self.revolutions += previous.abs() as f64 * elapsed.as_secs_f64() / 60.0;
+            }
+        }
+        self.rpm = Some(rpm);
+        self.last_rpm = Some(now);
+    }
+
+    fn set_fuel_rate(&mut self, rate: f64, now: Instant) {
+        if let (Some(previous), Some(last)) = (self.fuel_rate, self.last_fuel_rate) {
+            let elapsed = now.saturating_duration_since(last);
+            if elapsed <= MAX_GAP {
+                self.fuel_used += previous * elapsed.as_secs_f64() / 3600.0;
+            }
+        }
+        self.fuel_rate = Some(rate);
+        self.last_fuel_rate = Some(now);
+    }
+}
+
+enum Reading {
+    CoolantTemperature,
+    OilTemperature,
+    OilPressure,
+    AlternatorVoltage,
+    FuelRate,
+    Rpm,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineEvent {
+    Overspeed(u8),
+    Overheat(u8),
+    // The engine is back within all its limits.
+    Normal(u8),
+}
+
+// Follows every engine on board from RPM, ETL and XDR.  XDR readings are matched on their
+// type and a name such as ENGINE#1, the number after the # being the engine:
+//
+//   C ENGINE or ENGCOOL   coolant temperature
+//   C ENGOIL or ENGINEOIL oil temperature
+//   P ENGINE or ENGOIL    oil pressure
+//   U ENGINE or ALT       alternator voltage
+//   R ENGINE or FUEL      fuel rate, litres per second
+//   T ENGINE              engine speed
+pub struct EngineMonitor {
+    engines: BTreeMap<u8, Engine>,
+    max_rpm: Option<f32>,
+    max_coolant_temperature: Option<f64>,
+    max_oil_temperature: Option<f64>,
+}
+
+impl Default for EngineMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EngineMonitor {
+    pub fn new() -> Self {
+        Self {
+            engines: BTreeMap::new(),
+            max_rpm: None,
+            max_coolant_temperature: None,
+            max_oil_temperature: None,
+        }
+    }
+
+    pub fn with_max_rpm(mut self, rpm: f32) -> Self {
+        self.max_rpm = Some(rpm);
+        self
+    }
+
+    // Degrees Celsius.
+    pub fn with_max_coolant_temperature(mut self, temperature: f64) -> Self {
+        self.max_coolant_temperature = Some(temperature);
+        self
+    }
+
+    pub fn with_max_oil_temperature(mut self, temperature: f64) -> Self {
+        self.max_oil_temperature = Some(temperature);
+        self
+    }
+
+    pub fn get(&self, number: u8) -> Option<&Engine> {
+        self.engines.get(&number)
+    }
+
+    pub fn engines(&self) -> impl Iterator<Item = &Engine> {
+        self.engines.values()
+    }
+
+    pub fn update<B: FieldSource>(&mut self, sentence: &NmeaSentence<B>, now: Instant) -> Vec<EngineEvent> {
+        let mut touched = vec![];
+        match sentence {
+            NmeaSentence::RPM(rpm) if rpm.status() == Some('A') => {
+                let (Some(source), Some(number)) = (rpm.source(), rpm.source_number()) else { return vec![] };
+                let engine = self.engine(number);
+                if let Some(speed) = rpm.rpms() {
+                    match source {
+                        'E' => engine.set_rpm(speed, now),
+                        _ => engine.shaft_rpm = Some(speed),
+                    }
+                }
+                engine.pitch = rpm.propeller_pitch().or(engine.pitch);
+                touched.push(number);
+            }
+            NmeaSentence::ETL(etl) => {
+                let engine = self.engine(etl.engine_number().unwrap_or(0));
+                match etl.message_type() {
+                    Some('O') => engine.telegraph_order = etl.order().or(engine.telegraph_order),
+                    Some('A') => engine.telegraph_answer = etl.order().or(engine.telegraph_answer),
+                    _ => {}
+                }
+            }
+            NmeaSentence::XDR(xdr) => {
+                for m in xdr.measurements().unwrap_or_default() {
+                    let name = m.name.to_uppercase();
+                    let (base, number) = match name.split_once('#') {
+                        Some((base, number)) => match number.parse::<u8>() {
+                            Ok(number) => (base, number),
+                            Err(_) => continue,
+                        },
+                        None => (name.as_str(), 0),
+                    };
+                    let reading = match (m.transducer_type, base) {
+                        ('C', "ENGINE") | ('C', "ENGCOOL") => Reading::CoolantTemperature,
+                        ('C', "ENGOIL") | ('C', "ENGINEOIL") => Reading::OilTemperature,
+                        ('P', "ENGINE") | ('P', "ENGOIL") | ('P', "ENGINEOIL") => Reading::OilPressure,
+                        ('U', "ENGINE") | ('U', "ALT") | ('U', "ALTERNATOR") => Reading::AlternatorVoltage,
+                        ('R', "ENGINE") | ('R', "FUEL") | ('R', "FUELRATE") => Reading::FuelRate,
+                        ('T', "ENGINE") => Reading::Rpm,
+                        _ => continue,
+                    };
+                    let celsius = kelvin(&m.data).map(|k| k - KELVIN_OFFSET);
+                    let engine = self.engine(number);
+                    match reading {
+                        Reading::CoolantTemperature => engine.coolant_temperature = celsius,
+                        Reading::OilTemperature => engine.oil_temperature = celsius,
+                        Reading::OilPressure => engine.oil_pressure = pascal(&m.data),
+                        Reading::AlternatorVoltage => engine.alternator_voltage = Some(m.data.value as f64),
+                        Reading::FuelRate => engine.set_fuel_rate(m.data.value as f64 * 3600.0, now),
+                        Reading::Rpm => engine.set_rpm(m.data.value, now),
+                    }
+                    touched.push(number);
+                }
+            }
+            _ => {}
+        }
+        touched.dedup();
+        let mut events = vec![];
+        touched.into_iter().for_each(|number| self.check(number, &mut events));
+        events
+    }
+
+    fn engine(&mut self, number: u8) -> &mut Engine {
+        self.engines.entry(number).or_insert_with(|| Engine { number, ..Engine::default() })
+    }
+
+    // Raises an event when an engine goes over a limit, or comes back under all of them.
+    fn check(&mut self, number: u8, events: &mut Vec<EngineEvent>) {
+        let (max_rpm, max_coolant, max_oil) = (self.max_rpm, self.max_coolant_temperature, self.max_oil_temperature);
+        let Some(engine) = self.engines.get_mut(&number) else { return };
+        let above = |value: Option<f64>, limit: Option<f64>| matches!((value, limit), (Some(v), Some(l)) if v > l);
+        let overspeed = above(engine.rpm.map(|r| r.abs() as f64), max_rpm.map(|r| r as f64));
+        let overheat = above(engine.coolant_temperature, max_coolant) || above(engine.oil_temperature, max_oil);
+        if overspeed && !engine.overspeed {
+            events.push(EngineEvent::Overspeed(number));
+        }
+        if overheat && !engine.overheat {
+            events.push(EngineEvent::Overheat(number));
+        }
+        if !overspeed && !overheat && (engine.overspeed || engine.overheat) {
+            events.push(EngineEvent::Normal(number));
+        }
+        engine.overspeed = overspeed;
+        engine.overheat = overheat;
+    }
+}
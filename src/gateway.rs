@@ -0,0 +1,241 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, BufRead};
+use chrono::NaiveTime;
+use crate::*;
+use crate::n2k::{N2kMessage, BROADCAST};
+
+// PGNs sent with the fast-packet protocol.  Everything in the proprietary range
+// 130816-131071 is also treated as fast-packet.
+const FAST_PACKET_PGNS: &[u32] = &[
+    126208, 126464, 126720, 126983, 126984, 126985, 126986, 126987, 126988, 126996, 126998,
+    127233, 127237, 127489, 127493, 127496, 127497, 127498, 127503, 127504, 127506, 127507,
+    127509, 127510, 127511, 127512, 127513, 127514, 128275, 128520, 129029, 129038, 129039,
+    129040, 129041, 129044, 129045, 129284, 129285, 129301, 129302, 129538, 129540, 129541,
+    129542, 129545, 129547, 129549, 129551, 129556, 129792, 129793, 129794, 129795, 129796,
+    129797, 129798, 129799, 129800, 129801, 129802, 129803, 129804, 129805, 129806, 129807,
+    129808, 129809, 129810, 130052, 130053, 130054, 130060, 130061, 130064, 130065, 130066,
+    130067, 130068, 130069, 130070, 130071, 130072, 130073, 130074, 130320, 130321, 130322,
+    130323, 130324, 130330, 130560, 130567, 130577, 130578,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanFrame {
+    pub timestamp: Option<NaiveTime>,
+    // 'R' for frames received by the gateway, 'T' for frames it transmitted.
+    pub direction: char,
+    pub id: u32,
+    pub data: Vec<u8>,
+}
+
+impl CanFrame {
+    pub fn priority(&self) -> u8 {
+        ((self.id >> 26) & 0x07) as u8
+    }
+
+    pub fn source(&self) -> u8 {
+        (self.id & 0xFF) as u8
+    }
+
+    fn pdu_format(&self) -> u8 {
+        ((self.id >> 16) & 0xFF) as u8
+    }
+
+    // PDU1 messages (PF < 240) are addressed; the PS byte is the destination, not part of the PGN.
+    pub fn pgn(&self) -> u32 {
+        let pgn = (self.id >> 8) & 0x3FFFF;
+        if self.pdu_format() < 240 { pgn & 0x3FF00 } else { pgn }
+    }
+
+    pub fn destination(&self) -> u8 {
+        if self.pdu_format() < 240 { ((self.id >> 8) & 0xFF) as u8 } else { BROADCAST }
+    }
+}
+
+fn parse_hex_bytes<'a>(parts: impl Iterator<Item = &'a str>) -> Option<Vec<u8>> {
+    parts.map(|b| u8::from_str_radix(b, 16).ok()).collect()
+}
+
+// Yacht Devices RAW: "17:33:21.107 R 19F51323 01 02 03 04 05 06 07 08"
+pub fn parse_yd_raw(line: &str) -> Option<CanFrame> {
+    let mut parts = line.split_whitespace();
+    let timestamp = NaiveTime::parse_from_str(parts.next()?, "%H:%M:%S%.f").ok()?;
+    let direction = match parts.next()? {
+        "R" => 'R',
+        "T" => 'T',
+        _ => return None,
+    };
+    let id = u32::from_str_radix(parts.next()?, 16).ok().filter(|id| *id < 1 << 29)?;
+    let data = parse_hex_bytes(parts)?;
+    if data.len() > 8 {
+        return None;
+    }
+    Some(CanFrame { timestamp: Some(timestamp), direction, id, data })
+}
+
+// Actisense N2K ASCII: "A173321.107 23FF7 1F513 012F3070002F30709F".  The fields are the
+// timestamp, then source, destination and priority, then the PGN, then the complete payload.
+pub fn parse_actisense(line: &str) -> Option<N2kMessage> {
+    let mut parts = line.split_whitespace();
+    let time = parts.next()?.strip_prefix('A')?;
+    let timestamp = NaiveTime::parse_from_str(time, "%H%M%S%.f").ok()?;
+    let address = parts.next()?;
+    if address.len() != 5 || !address.is_ascii() {
+        return None;
+    }
+    let source = u8::from_str_radix(&address[0..2], 16).ok()?;
+    let destination = u8::from_str_radix(&address[2..4], 16).ok()?;
+    let priority = u8::from_str_radix(&address[4..5], 16).ok().filter(|p| *p < 8)?;
+    let pgn = u32::from_str_radix(parts.next()?, 16).ok().filter(|pgn| *pgn < 1 << 18)?;
+    let payload = parts.next().unwrap_or("");
+    if !payload.len().is_multiple_of(2) || !payload.is_ascii() || parts.next().is_some() {
+        return None;
+    }
+    let data = parse_hex_bytes((0..payload.len()).step_by(2).map(|i| &payload[i..i + 2]))?;
+    Some(N2kMessage { pgn, priority, source, destination, timestamp: Some(timestamp), data })
+}
+
+struct PartialMessage {
+    length: usize,
+    next_frame: u8,
+    data: Vec<u8>,
+}
+
+// Reassembles fast-packet frames into complete messages.  Single-frame PGNs pass straight
+// through.  Frames that arrive out of order abandon the message they belong to.
+pub struct FastPacketAssembler {
+    fast_packet_pgns: HashSet<u32>,
+    partial: HashMap<(u8, u32, u8), PartialMessage>,
+}
+
+impl Default for FastPacketAssembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FastPacketAssembler {
+    pub fn new() -> Self {
+        Self {
+            fast_packet_pgns: FAST_PACKET_PGNS.iter().copied().collect(),
+            partial: HashMap::new(),
+        }
+    }
+
+    // Registers an extra PGN as fast-packet, e.g. a manufacturer's proprietary message.
+    pub fn with_fast_packet(mut self, pgn: u32) -> Self {
+        self.fast_packet_pgns.insert(pgn);
+        self
+    }
+
+    pub fn is_fast_packet(&self, pgn: u32) -> bool {
+        self.fast_packet_pgns.contains(&pgn) || (130816..=131071).contains(&pgn)
+    }
+
+    pub fn push(&mut self, frame: &CanFrame) -> Option<N2kMessage> {
+        let pgn = frame.pgn();
+        let message = |data: Vec<u8>| N2kMessage {
+            pgn,
+            priority: frame.priority(),
+            source: frame.source(),
+            destination: frame.destination(),
+            timestamp: frame.timestamp,
+            data,
+        };
+        if !self.is_fast_packet(pgn) {
+            return Some(message(frame.data.clone()));
+        }
+        let first = *frame.data.first()?;
+        let sequence = first >> 5;
+        let counter = first & 0x1F;
+        let key = (frame.source(), pgn, sequence);
+        if counter == 0 {
+            let length = *frame.data.get(1)? as usize;
+            let mut data = frame.data.get(2..).unwrap_or_default().to_vec();
+            if data.len() >= length {
+                data.truncate(length);
+                self.partial.remove(&key);
+                return Some(message(data));
+            }
+            self.partial.insert(key, PartialMessage { length, next_frame: 1, data });
+            return None;
+        }
+        let partial = self.partial.get_mut(&key)?;
+        if partial.next_frame != counter {
+            self.partial.remove(&key);
+            return None;
+        }
+        partial.data.extend_from_slice(frame.data.get(1..).unwrap_or_default());
+        partial.next_frame += 1;
+        if partial.data.len() >= partial.length {
+            let mut partial = self.partial.remove(&key)?;
+            partial.data.truncate(partial.length);
+            return Some(message(partial.data));
+        }
+        None
+    }
+}
+
+#[derive(Debug)]
+pub enum GatewayMessage {
+    Nmea0183(NmeaSentence),
+    N2k(N2kMessage),
+}
+
+// Accepts the lines a gateway sends on one port, whatever the mix of NMEA 0183, YD RAW and
+// Actisense N2K ASCII, and hands back complete messages.
+#[derive(Default)]
+pub struct GatewayReader {
+    assembler: FastPacketAssembler,
+}
+
+impl GatewayReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_assembler(assembler: FastPacketAssembler) -> Self {
+        Self { assembler }
+    }
+
+    // None for lines that are not recognised, and for fast-packet frames that do not
+    // complete a message.
+    pub fn push_line(&mut self, line: &str) -> Option<GatewayMessage> {
+        let line = line.trim();
+        match line.chars().next()? {
+            '$' | '!' => Some(GatewayMessage::Nmea0183(NmeaSentence::from(&line.to_string()))),
+            'A' => parse_actisense(line).map(GatewayMessage::N2k),
+            c if c.is_ascii_digit() => {
+                let frame = parse_yd_raw(line)?;
+                self.assembler.push(&frame).map(GatewayMessage::N2k)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn messages<R: BufRead>(self, reader: R) -> GatewayMessages<R> {
+        GatewayMessages { reader: self, lines: reader.lines() }
+    }
+}
+
+pub struct GatewayMessages<R> {
+    reader: GatewayReader,
+    lines: io::Lines<R>,
+}
+
+impl<R: BufRead> Iterator for GatewayMessages<R> {
+    type Item = io::Result<GatewayMessage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for line in self.lines.by_ref() {
+            match line {
+                Ok(line) => {
+                    if let Some(message) = self.reader.push_line(&line) {
+                        return Some(Ok(message));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,172 @@
+use crate::*;
+
+// The constellations, in the order GNS gives their mode characters.
+const SYSTEMS: [GnssSystem; 6] = [
+    GnssSystem::Gps,
+    GnssSystem::Glonass,
+    GnssSystem::Galileo,
+    GnssSystem::BeiDou,
+    GnssSystem::Qzss,
+    GnssSystem::NavIc,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GnssSystem {
+    Gps,
+    Glonass,
+    Galileo,
+    BeiDou,
+    Qzss,
+    NavIc,
+}
+
+impl GnssSystem {
+    // The system ID of NMEA 4.10 and later.
+    pub fn from_system_id(id: u8) -> Option<Self> {
+        SYSTEMS.get(usize::from(id).checked_sub(1)?).copied()
+    }
+
+    pub fn system_id(self) -> u8 {
+        self as u8 + 1
+    }
+
+    // GN, for more than one system, gives None.
+    pub fn from_talker(talker: &str) -> Option<Self> {
+        match talker {
+            "GP" => Some(GnssSystem::Gps),
+            "GL" => Some(GnssSystem::Glonass),
+            "GA" => Some(GnssSystem::Galileo),
+            "GB" | "BD" => Some(GnssSystem::BeiDou),
+            "GQ" => Some(GnssSystem::Qzss),
+            "GI" => Some(GnssSystem::NavIc),
+            _ => None,
+        }
+    }
+
+    pub fn talker(self) -> &'static str {
+        match self {
+            GnssSystem::Gps => "GP",
+            GnssSystem::Glonass => "GL",
+            GnssSystem::Galileo => "GA",
+            GnssSystem::BeiDou => "GB",
+            GnssSystem::Qzss => "GQ",
+            GnssSystem::NavIc => "GI",
+        }
+    }
+
+    // The satellite numbering GN sentences used before system IDs: 1 to 32 GPS, 33 to 64
+    // SBAS, counted with GPS, and 65 to 96 GLONASS.
+    pub fn from_satellite_id(id: u16) -> Option<Self> {
+        match id {
+            1..=64 => Some(GnssSystem::Gps),
+            65..=96 => Some(GnssSystem::Glonass),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionMode {
+    Autonomous,
+    Differential,
+    Estimated,
+    FloatRtk,
+    Manual,
+    NoFix,
+    Precise,
+    RtkInteger,
+    Simulator,
+}
+
+impl PositionMode {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'A' => Some(PositionMode::Autonomous),
+            'D' => Some(PositionMode::Differential),
+            'E' => Some(PositionMode::Estimated),
+            'F' => Some(PositionMode::FloatRtk),
+            'M' => Some(PositionMode::Manual),
+            'N' => Some(PositionMode::NoFix),
+            'P' => Some(PositionMode::Precise),
+            'R' => Some(PositionMode::RtkInteger),
+            'S' => Some(PositionMode::Simulator),
+            _ => None,
+        }
+    }
+
+    pub fn is_fix(self) -> bool {
+        !matches!(self, PositionMode::NoFix | PositionMode::Manual | PositionMode::Simulator)
+    }
+}
+
+// The navigational status of NMEA 4.10, on GNS and RMC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrityStatus {
+    Safe,
+    Caution,
+    Unsafe,
+    NotValid,
+}
+
+impl IntegrityStatus {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'S' => Some(IntegrityStatus::Safe),
+            'C' => Some(IntegrityStatus::Caution),
+            'U' => Some(IntegrityStatus::Unsafe),
+            'V' => Some(IntegrityStatus::NotValid),
+            _ => None,
+        }
+    }
+}
+
+impl<B: FieldSource> GnsData<B> {
+    // The mode of each constellation the receiver reported on.  NMEA 2.3 sends two
+    // characters, later versions up to six.
+    pub fn modes(&self) -> impl Iterator<Item = (GnssSystem, Option<PositionMode>)> + '_ {
+        let modes = self.base.field(5).unwrap_or("");
+        SYSTEMS.into_iter().zip(modes.chars()).map(|(system, c)| (system, PositionMode::from_char(c)))
+    }
+
+    pub fn mode_of(&self, system: GnssSystem) -> Option<PositionMode> {
+        self.modes().find(|(s, _)| *s == system)?.1
+    }
+
+    pub fn integrity(&self) -> Option<IntegrityStatus> {
+        IntegrityStatus::from_char(self.navigational_status()?)
+    }
+}
+
+impl<B: FieldSource> RmcData<B> {
+    pub fn integrity(&self) -> Option<IntegrityStatus> {
+        IntegrityStatus::from_char(self.nav_status()?)
+    }
+}
+
+impl<B: FieldSource> GsaData<B> {
+    // From the system ID when there is one, then the talker, then the first satellite
+    // for GN sentences without a system ID.
+    pub fn system(&self) -> Option<GnssSystem> {
+        if let Some(id) = self.system_id() {
+            return GnssSystem::from_system_id(id);
+        }
+        GnssSystem::from_talker(self.base.sender_str().trim_start_matches(['$', '!']))
+            .or_else(|| GnssSystem::from_satellite_id(self.base.get::<u16>(2)?))
+    }
+
+    pub fn satellite_ids(&self) -> impl Iterator<Item = u8> + '_ {
+        (2..14).filter_map(|i| self.base.get::<u8>(i))
+    }
+}
+
+impl<B: FieldSource> GsvData<B> {
+    // The signal ID NMEA 4.10 adds after the satellites, in hexadecimal.
+    pub fn signal_id(&self) -> Option<u8> {
+        let count = self.base.field_count();
+        (count > 3 && (count - 3) % 4 == 1).then(|| self.base.get_hex::<u8>(count - 1))?
+    }
+
+    pub fn system(&self) -> Option<GnssSystem> {
+        GnssSystem::from_talker(self.base.sender_str().trim_start_matches(['$', '!']))
+    }
+}
@@ -1,22 +1,60 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 extern crate core;
-
-use std::io::{BufRead, BufReader};
-use std::str::FromStr;
-use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, collections::BTreeMap, format, string::{String, ToString}, vec, vec::Vec};
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use num_traits::Num;
-use crate::NmeaSentence::{AAM, ABK, ABM, ACA, ACK, ACS, ADS, AIR, AKD, ALA, ALM, ALR, APA, APB, ASD, BBM, BEC, BOD, BWC, BWR, BWW, CEK, COP, CUR, DBK, DBS, DBT, DCN, DCR, DDC, DOR, DPT, DSC, DSE, DSI, DSR, DTM, ETL, EVE, FIR, FSI, GBS, GGA, GLC, GLL, GMP, GNS, GRS, GSA, GST, GSV, GTD, GXA, HDG, HDM, HDT, HFB, HMR, HMS, HSC, HTC, HTD, ITS, LCD, LR1, LR2, LR3, LRF, MDA, MLA, MSK, MSS, MTW, MWD, MWV, OLN, OSD, R00, RLM, RMA, RMB, RMC, ROT, RPM, RSA, RSD, RTE, SF1, SSD, STN, TDS, TFI, TLB, TLL, TPC, TPR, TPT, TRF, TTM, TUT, TXT, VBW, VDM, VDO, VDR, VHW, VLW, VPW, VSD, VTG, VWR, WCV, WDC, WDR, WNC, WPL, XDR, XTE, XTR, ZDA, ZDL, ZFO, ZTG};
+#[cfg(feature = "alloc")]
+pub mod ais;
+#[cfg(feature = "std")]
+pub mod arpa;
+pub mod autopilot;
+#[cfg(feature = "std")]
+pub mod clock;
+#[cfg(feature = "std")]
+pub mod csv;
+#[cfg(feature = "std")]
+pub mod current;
+#[cfg(feature = "std")]
+pub mod engine;
+pub mod gnss;
+#[cfg(feature = "std")]
+pub mod gateway;
+#[cfg(feature = "std")]
+pub mod n2k;
+#[cfg(feature = "std")]
+pub mod seatalk;
+#[cfg(feature = "std")]
+pub mod signalk;
+#[cfg(feature = "alloc")]
+pub mod systems;
+pub mod version;
+#[cfg(feature = "alloc")]
+mod serialization;
+#[cfg(feature = "std")]
+mod units;
+
+use crate::NmeaSentence::{AAM, ABK, ABM, ACA, ACK, ACS, ADS, AIR, AKD, ALA, ALM, ALR, APA, APB, ASD, BBM, BEC, BOD, BWC, BWR, BWW, CEK, COP, CUR, DBK, DBS, DBT, DCN, DCR, DDC, DOR, DPT, DSC, DSE, DSI, DSR, DTM, ETL, EVE, FIR, FSI, GBS, GGA, GLC, GLL, GMP, GNS, GRS, GSA, GST, GSV, GTD, GXA, HDG, HDM, HDT, HFB, HMR, HMS, HSC, HTC, HTD, ITS, LCD, LR1, LR2, LR3, LRF, MDA, MLA, MSK, MSS, MTW, MWD, MWV, OLN, OSD, R00, RLM, RMA, RMB, RMC, ROT, RPM, RSA, RSD, RTE, SF1, SSD, STN, TDS, TFI, TLB, TLL, TPC, TPR, TPT, TRF, TTM, TUT, TXT, VBW, VDM, VDO, VDR, VHW, VLW, VPW, VSD, VTG, VWR, WAT, WCV, WDC, WDR, WNC, WPL, XDR, XTE, XTR, ZDA, ZDL, ZFO, ZTG};
 
 macro_rules! make_data {
     ($i:ident) => {
         #[derive(Debug)]
-        pub struct $i {
-            base : NmeaBaseSentence,
+        pub struct $i<B = DefaultBase> {
+            base : B,
         }
+        #[cfg(feature = "alloc")]
         impl NmeaBaseTrait for $i {
             fn get_base(&self) -> &NmeaBaseSentence {
                 &self.base
             }
         }
+        #[cfg(feature = "alloc")]
         impl From<&String> for $i {
             fn from(value : &String) -> Self {
                 Self {
@@ -24,16 +62,38 @@ macro_rules! make_data {
                 }
             }
         }
+        impl<'a> From<NmeaSentenceRef<'a>> for $i<NmeaSentenceRef<'a>> {
+            fn from(value : NmeaSentenceRef<'a>) -> Self {
+                Self {
+                    base : value,
+                }
+            }
+        }
+        #[cfg(feature = "alloc")]
+        impl $i<NmeaSentenceRef<'_>> {
+            pub fn into_owned(self) -> $i {
+                $i {
+                    base : self.base.into_owned(),
+                }
+            }
+        }
+        impl<B: FieldSource> Display for $i<B> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{}", self.base.original())
+            }
+        }
     };
 }
 
 macro_rules! make_number_field {
-    ($a:ident, $b:ident, $c:expr) => {
+    ($(#[$attr:meta])* $a:ident, $b:ident, $c:expr) => {
+        $(#[$attr])*
         pub fn $a(&self) -> Option<$b> {
             self.base.get::<$b>($c)
         }
     };
-    ($a:ident, $b:ident, $c:expr, $d:expr) => {
+    ($(#[$attr:meta])* $a:ident, $b:ident, $c:expr, $d:expr) => {
+        $(#[$attr])*
         pub fn $a(&self) -> Option<ValueWithUnit<$b>> {
             self.base.get_pair::<$b>($c, $d)
         }
@@ -41,7 +101,8 @@ macro_rules! make_number_field {
 }
 
 macro_rules! make_char_field {
-    ($a:ident, $b:expr) => {
+    ($(#[$attr:meta])* $a:ident, $b:expr) => {
+        $(#[$attr])*
         pub fn $a(&self) -> Option<char> {
             self.base.get::<char>($b)
         }
@@ -49,15 +110,24 @@ macro_rules! make_char_field {
 }
 
 macro_rules! make_string_field {
-    ($a:ident, $b:expr) => {
+    ($(#[$attr:meta])* $a:ident, $b:expr) => {
+        #[cfg(feature = "alloc")]
+        $(#[$attr])*
         pub fn $a(&self) -> Option<String> {
-            self.base.get::<String>($b)
+            self.base.get_text($b)
+        }
+        #[cfg(not(feature = "alloc"))]
+        $(#[$attr])*
+        pub fn $a(&self) -> Option<&str> {
+            self.base.field($b).filter(|f| !f.is_empty())
         }
     }
 }
 
 macro_rules! make_time_field {
-    ($a:ident, $b:expr) => {
+    ($(#[$attr:meta])* $a:ident, $b:expr) => {
+        #[cfg(feature = "chrono")]
+        $(#[$attr])*
         pub fn $a(&self) -> Option<NaiveTime> {
             self.base.get_time($b)
         }
@@ -65,7 +135,9 @@ macro_rules! make_time_field {
 }
 
 macro_rules! make_date_field {
-    ($a:ident, $b:expr) => {
+    ($(#[$attr:meta])* $a:ident, $b:expr) => {
+        #[cfg(feature = "chrono")]
+        $(#[$attr])*
         pub fn $a(&self) -> Option<NaiveDate> {
             self.base.get_date($b)
         }
@@ -73,7 +145,8 @@ macro_rules! make_date_field {
 }
 
 macro_rules! make_coordinate_field {
-    ($a:ident, $b:expr, $c:expr) => {
+    ($(#[$attr:meta])* $a:ident, $b:expr, $c:expr) => {
+        $(#[$attr])*
         pub fn $a(&self) -> Option<f32> {
             self.base.get_coordinate($b, $c)
         }
@@ -81,14 +154,363 @@ macro_rules! make_coordinate_field {
 }
 
 macro_rules! make_hex_field {
-    ($a:ident, $b:ident, $c:expr) => {
+    ($(#[$attr:meta])* $a:ident, $b:ident, $c:expr) => {
+        $(#[$attr])*
         pub fn $a(&self) -> Option<$b> {
             self.base.get_hex::<$b>($c)
         }
     }
 }
 
-trait NmeaBaseTrait {
+macro_rules! make_group_field {
+    ($(#[$attr:meta])* $a:ident, $b:ty, $c:expr) => {
+        #[cfg(feature = "alloc")]
+        $(#[$attr])*
+        pub fn $a(&self) -> Option<Vec<$b>> {
+            self.base.get_group::<$b>($c)
+        }
+    }
+}
+
+// Lists the fields of a sentence.  Each field is one of the make_..._field! macros, with
+// its description as a doc comment.  [required] marks a field that must not be empty, and
+// [alias] one that reads the same field as another accessor.  [range(lo, hi)] gives the
+// values a number is allowed to take, for lenient parsing to check.  [manual] leaves the
+// accessor to be written by hand, for a field whose place depends on the layout; the type
+// then gives a layout_value() that serde reads it through, and the version module the
+// field table of each older layout for schema().  Besides the accessors, this
+// gives the sentence its FIELDS table, validation, a builder and a serde representation
+// holding its fields by name.
+macro_rules! make_fields {
+    ($t:ident { $( $(#[doc = $doc:literal])* $([$($flag:tt)*])? $m:ident ! ( $f:ident $(, $arg:tt)* ) ; )* }) => {
+        impl<B: FieldSource> $t<B> {
+            $( accessor!([$($($flag)*)?] $m, $(#[doc = $doc])* $f $(, $arg)*); )*
+
+            // The NMEA version whose layout the sentence has, for sentences that changed.
+            pub fn version(&self) -> Option<version::NmeaVersion> {
+                version::detect(MessageType::from(self.base.message_type_str()), &self.base)
+            }
+
+            pub fn validate(&self) -> Result<(), FieldError> {
+                validate_fields(&self.base, self.schema())
+            }
+
+            pub fn fields(&self) -> impl Iterator<Item = (&'static str, Value<'_>)> {
+                field_values(&self.base, self.schema())
+            }
+
+            // FIELDS, or the fields of the older layout the sentence has where they sit elsewhere.
+            pub fn schema(&self) -> &'static [FieldInfo] {
+                version::layout_fields(MessageType::from(self.base.message_type_str()), &self.base, <$t>::FIELDS)
+            }
+        }
+
+        impl $t {
+            pub const FIELDS: &'static [FieldInfo] = &[
+                $( field_info!($m, $f, concat!($($doc),*), has_flag!(required; $($($flag)*)?), has_flag!(alias; $($($flag)*)?), field_range!($($($flag)*)?) $(, $arg)*), )*
+            ];
+
+            pub const FIELD_COUNT: usize = field_count(Self::FIELDS);
+
+            #[cfg(feature = "alloc")]
+            pub fn builder(sender: &str) -> SentenceBuilder<Self> {
+                SentenceBuilder::new(sender)
+            }
+        }
+
+        const _: () = assert!(!overlapping_fields(<$t>::FIELDS), concat!("Two fields of ", stringify!($t), " share an index"));
+
+        #[cfg(feature = "alloc")]
+        impl SentenceBuilder<$t> {
+            $( builder_field!($m, $f $(, $arg)*); )*
+
+            pub fn build(mut self) -> $t {
+                // Trailing empty fields are still written out.
+                if <$t>::FIELD_COUNT > 0 {
+                    serialization::reserve(&mut self.fields, <$t>::FIELD_COUNT - 1);
+                }
+                $t { base: serialization::build(&self.sender, stringify!($t), &self.fields) }
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        const _: () = {
+            use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+            #[derive(Default, Serialize, Deserialize)]
+            #[serde(default)]
+            struct Repr {
+                sender: String,
+                $( $f: field_type!($m $(, $arg)*), )*
+            }
+
+            impl<B: FieldSource> Serialize for $t<B> {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    Repr { sender: self.base.sender_str().to_string(), $( $f: serde_value!([$($($flag)*)?] self, $m, $f $(, $arg)*), )* }.serialize(serializer)
+                }
+            }
+
+            impl<'de, B: From<NmeaBaseSentence>> Deserialize<'de> for $t<B> {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let repr = Repr::deserialize(deserializer)?;
+                    let mut fields = Vec::new();
+                    $( write_field!($m, &mut fields, &repr.$f, $f $(, $arg)*); )*
+                    Ok(Self { base: B::from(serialization::build(&repr.sender, stringify!($t), &fields)) })
+                }
+            }
+        };
+    };
+}
+
+// Sentences without accessors yet have no field table and serialize their raw fields.
+macro_rules! make_raw_fields {
+    ($($t:ident),*) => {
+        $(
+        impl $t {
+            pub const FIELDS: &'static [FieldInfo] = &[];
+        }
+
+        impl<B: FieldSource> $t<B> {
+            pub fn version(&self) -> Option<version::NmeaVersion> {
+                version::detect(MessageType::from(self.base.message_type_str()), &self.base)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        const _: () = {
+            use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+            #[derive(Serialize, Deserialize)]
+            struct Repr {
+                sender: String,
+                fields: Vec<String>,
+            }
+
+            impl<B: FieldSource> Serialize for $t<B> {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    let fields = (0..self.base.field_count()).filter_map(|i| self.base.field(i)).map(|f| f.to_string()).collect();
+                    Repr { sender: self.base.sender_str().to_string(), fields }.serialize(serializer)
+                }
+            }
+
+            impl<'de, B: From<NmeaBaseSentence>> Deserialize<'de> for $t<B> {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let repr = Repr::deserialize(deserializer)?;
+                    Ok(Self { base: B::from(serialization::build(&repr.sender, stringify!($t), &repr.fields)) })
+                }
+            }
+        };
+        )*
+    };
+}
+
+macro_rules! accessor {
+    ([manual $($rest:tt)*] $($field:tt)*) => {};
+    ([$other:tt $($rest:tt)*] $($field:tt)*) => { accessor!([$($rest)*] $($field)*); };
+    ([] $m:ident, $($field:tt)*) => { $m!($($field)*); };
+}
+
+macro_rules! has_flag {
+    ($flag:ident;) => { false };
+    (required; required $($rest:tt)*) => { true };
+    (alias; alias $($rest:tt)*) => { true };
+    ($flag:ident; $other:tt $($rest:tt)*) => { has_flag!($flag; $($rest)*) };
+}
+
+macro_rules! field_range {
+    () => { None };
+    (range ($lo:expr, $hi:expr) $($rest:tt)*) => { Some(($lo as f64, $hi as f64)) };
+    ($other:tt $($rest:tt)*) => { field_range!($($rest)*) };
+}
+
+macro_rules! field_info {
+    (@ $f:ident, $doc:expr, $required:expr, $alias:expr, $range:expr, $kind:ident, $type_name:expr, $index:expr, $unit_index:expr) => {
+        FieldInfo {
+            name: stringify!($f),
+            kind: FieldKind::$kind,
+            type_name: $type_name,
+            index: $index,
+            unit_index: $unit_index,
+            required: $required,
+            alias: $alias,
+            range: $range,
+            description: $doc.trim_ascii_start(),
+        }
+    };
+    (make_number_field, $f:ident, $doc:expr, $r:expr, $a:expr, $g:expr, $b:tt, $c:tt) => {
+        field_info!(@ $f, $doc, $r, $a, $g, Number, stringify!($b), $c, None)
+    };
+    (make_number_field, $f:ident, $doc:expr, $r:expr, $a:expr, $g:expr, $b:tt, $c:tt, $d:tt) => {
+        field_info!(@ $f, $doc, $r, $a, $g, Number, stringify!($b), $c, Some($d))
+    };
+    (make_char_field, $f:ident, $doc:expr, $r:expr, $a:expr, $g:expr, $b:tt) => {
+        field_info!(@ $f, $doc, $r, $a, $g, Char, "char", $b, None)
+    };
+    (make_string_field, $f:ident, $doc:expr, $r:expr, $a:expr, $g:expr, $b:tt) => {
+        field_info!(@ $f, $doc, $r, $a, $g, Text, "String", $b, None)
+    };
+    (make_time_field, $f:ident, $doc:expr, $r:expr, $a:expr, $g:expr, $b:tt) => {
+        field_info!(@ $f, $doc, $r, $a, $g, Time, "NaiveTime", $b, None)
+    };
+    (make_date_field, $f:ident, $doc:expr, $r:expr, $a:expr, $g:expr, $b:tt) => {
+        field_info!(@ $f, $doc, $r, $a, $g, Date, "NaiveDate", $b, None)
+    };
+    (make_coordinate_field, $f:ident, $doc:expr, $r:expr, $a:expr, $g:expr, $b:tt, $c:tt) => {
+        field_info!(@ $f, $doc, $r, $a, $g, Coordinate, "f32", $b, Some($c))
+    };
+    (make_hex_field, $f:ident, $doc:expr, $r:expr, $a:expr, $g:expr, $b:tt, $c:tt) => {
+        field_info!(@ $f, $doc, $r, $a, $g, Hex, stringify!($b), $c, None)
+    };
+    (make_group_field, $f:ident, $doc:expr, $r:expr, $a:expr, $g:expr, $b:tt, $c:tt) => {
+        field_info!(@ $f, $doc, $r, $a, $g, Group, stringify!($b), $c, None)
+    };
+}
+
+// The builder setters take the same types the accessors return.
+#[cfg(feature = "alloc")]
+macro_rules! builder_field {
+    (make_number_field, $f:ident, $b:tt, $c:tt) => {
+        pub fn $f(mut self, value: $b) -> Self {
+            write_field!(make_number_field, &mut self.fields, &Some(value), $f, $b, $c);
+            self
+        }
+    };
+    (make_number_field, $f:ident, $b:tt, $c:tt, $d:tt) => {
+        pub fn $f(mut self, value: $b, unit: char) -> Self {
+            write_field!(make_number_field, &mut self.fields, &Some(ValueWithUnit { value, unit }), $f, $b, $c, $d);
+            self
+        }
+    };
+    (make_char_field, $f:ident, $b:tt) => {
+        pub fn $f(mut self, value: char) -> Self {
+            write_field!(make_char_field, &mut self.fields, &Some(value), $f, $b);
+            self
+        }
+    };
+    (make_string_field, $f:ident, $b:tt) => {
+        pub fn $f(mut self, value: &str) -> Self {
+            write_field!(make_string_field, &mut self.fields, &Some(value.to_string()), $f, $b);
+            self
+        }
+    };
+    (make_time_field, $f:ident, $b:tt) => {
+        #[cfg(feature = "chrono")]
+        pub fn $f(mut self, value: NaiveTime) -> Self {
+            write_field!(make_time_field, &mut self.fields, &Some(value), $f, $b);
+            self
+        }
+    };
+    (make_date_field, $f:ident, $b:tt) => {
+        #[cfg(feature = "chrono")]
+        pub fn $f(mut self, value: NaiveDate) -> Self {
+            write_field!(make_date_field, &mut self.fields, &Some(value), $f, $b);
+            self
+        }
+    };
+    (make_coordinate_field, $f:ident, $b:tt, $c:tt) => {
+        pub fn $f(mut self, value: f32) -> Self {
+            write_field!(make_coordinate_field, &mut self.fields, &Some(value), $f, $b, $c);
+            self
+        }
+    };
+    (make_hex_field, $f:ident, $b:tt, $c:tt) => {
+        pub fn $f(mut self, value: $b) -> Self {
+            write_field!(make_hex_field, &mut self.fields, &Some(value), $f, $b, $c);
+            self
+        }
+    };
+    (make_group_field, $f:ident, $b:tt, $c:tt) => {
+        pub fn $f(mut self, values: &[$b]) -> Self {
+            for (i, group) in values.iter().enumerate() {
+                group.write(&mut self.fields, $c + i * <$b as FieldGroup>::WIDTH);
+            }
+            self
+        }
+    };
+}
+
+// Decimal fields go through f64 rather than the f32 of their accessors, which would change
+// the digits of a position such as 4844.8683.
+#[cfg(feature = "serde")]
+macro_rules! field_type {
+    (make_number_field, f32, $c:tt) => { Option<f64> };
+    (make_number_field, f32, $c:tt, $d:tt) => { Option<ValueWithUnit<f64>> };
+    (make_number_field, $b:tt, $c:tt) => { Option<$b> };
+    (make_number_field, $b:tt, $c:tt, $d:tt) => { Option<ValueWithUnit<$b>> };
+    (make_char_field, $b:tt) => { Option<char> };
+    (make_string_field, $b:tt) => { Option<String> };
+    (make_time_field, $b:tt) => { Option<NaiveTime> };
+    (make_date_field, $b:tt) => { Option<NaiveDate> };
+    (make_coordinate_field, $b:tt, $c:tt) => { Option<f64> };
+    (make_hex_field, $b:tt, $c:tt) => { Option<$b> };
+    (make_group_field, $b:tt, $c:tt) => { Option<Vec<$b>> };
+}
+
+// The value of a field as field_type! holds it.
+#[cfg(feature = "serde")]
+macro_rules! serde_value {
+    ([manual $($rest:tt)*] $s:expr, $m:ident, $f:ident $(, $arg:tt)*) => { $s.layout_value(stringify!($f)) };
+    ([$other:tt $($rest:tt)*] $($field:tt)*) => { serde_value!([$($rest)*] $($field)*) };
+    ([] $s:expr, make_number_field, $f:ident, f32, $c:tt) => { $s.base.get::<f64>($c) };
+    ([] $s:expr, make_number_field, $f:ident, f32, $c:tt, $d:tt) => { $s.base.get_pair::<f64>($c, $d) };
+    ([] $s:expr, make_coordinate_field, $f:ident, $b:tt, $c:tt) => {
+        $s.base.get::<f64>($b).zip($s.base.get::<char>($c)).map(|(v, h)| if h == 'S' || h == 'W' { -v } else { v })
+    };
+    ([] $s:expr, $m:ident, $f:ident $(, $arg:tt)*) => { $s.$f() };
+}
+
+#[cfg(feature = "alloc")]
+macro_rules! write_field {
+    (make_number_field, $fields:expr, $v:expr, $a:ident, $b:tt, $c:tt) => {
+        serialization::reserve($fields, $c);
+        if let Some(v) = $v { serialization::set($fields, $c, v.to_string()); }
+    };
+    (make_number_field, $fields:expr, $v:expr, $a:ident, $b:tt, $c:tt, $d:tt) => {
+        serialization::reserve($fields, usize::max($c, $d));
+        if let Some(v) = $v {
+            serialization::set($fields, $c, v.value.to_string());
+            serialization::set($fields, $d, v.unit.to_string());
+        }
+    };
+    (make_char_field, $fields:expr, $v:expr, $a:ident, $b:tt) => {
+        serialization::reserve($fields, $b);
+        if let Some(v) = $v { serialization::set($fields, $b, v.to_string()); }
+    };
+    (make_string_field, $fields:expr, $v:expr, $a:ident, $b:tt) => {
+        serialization::reserve($fields, $b);
+        if let Some(v) = $v { serialization::set($fields, $b, serialization::escape(v)); }
+    };
+    (make_time_field, $fields:expr, $v:expr, $a:ident, $b:tt) => {
+        serialization::reserve($fields, $b);
+        if let Some(v) = $v { serialization::set($fields, $b, serialization::time(v)); }
+    };
+    (make_date_field, $fields:expr, $v:expr, $a:ident, $b:tt) => {
+        serialization::reserve($fields, $b);
+        if let Some(v) = $v { serialization::set($fields, $b, v.format("%d%m%y").to_string()); }
+    };
+    (make_coordinate_field, $fields:expr, $v:expr, $a:ident, $b:tt, $c:tt) => {
+        serialization::reserve($fields, usize::max($b, $c));
+        if let Some(v) = $v {
+            let (value, hemisphere) = serialization::coordinate(*v, stringify!($a));
+            serialization::set($fields, $b, value);
+            serialization::set($fields, $c, hemisphere);
+        }
+    };
+    (make_hex_field, $fields:expr, $v:expr, $a:ident, $b:tt, $c:tt) => {
+        serialization::reserve($fields, $c);
+        if let Some(v) = $v { serialization::set($fields, $c, format!("{:X}", *v as u64)); }
+    };
+    (make_group_field, $fields:expr, $v:expr, $a:ident, $b:tt, $c:tt) => {
+        for (i, group) in $v.iter().flatten().enumerate() {
+            group.write($fields, $c + i * <$b as FieldGroup>::WIDTH);
+        }
+    };
+}
+
+// Common accessors for anything holding a parsed sentence: the typed sentences,
+// NmeaBaseSentence and NmeaSentence.
+#[cfg(feature = "alloc")]
+pub trait NmeaBaseTrait {
     fn get_base(&self) -> &NmeaBaseSentence;
 
     fn sender(&self) -> &String {
@@ -106,14 +528,314 @@ trait NmeaBaseTrait {
     fn checksum(&self) -> u8 {
         self.get_base()._checksum
     }
+
+    // The sender without its leading $ or !.
+    fn talker(&self) -> &str {
+        self.sender().trim_start_matches(['$', '!'])
+    }
+
+    // The line the sentence was parsed from.
+    fn raw(&self) -> &str {
+        &self.get_base()._original
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Number,
+    Hex,
+    Char,
+    Text,
+    Time,
+    Date,
+    Coordinate,
+    Group,
+}
+
+// Describes one field of a sentence, as listed in make_fields!.  unit_index is the field
+// holding the unit, or the hemisphere for coordinates.  Groups start at index and repeat
+// to the end of the sentence.  range is the span of valid values for a number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldInfo {
+    pub name: &'static str,
+    pub kind: FieldKind,
+    pub type_name: &'static str,
+    pub index: usize,
+    pub unit_index: Option<usize>,
+    pub required: bool,
+    pub alias: bool,
+    pub range: Option<(f64, f64)>,
+    pub description: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldError {
+    TooFewFields { expected: usize, found: usize },
+    TooManyFields { expected: usize, found: usize },
+    MissingField(&'static str),
+    OutOfRange(&'static str),
+    TooLong(&'static str),
+    InvalidCharacter(&'static str),
+}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FieldError::TooFewFields { expected, found } => write!(f, "expected {} fields, found {}", expected, found),
+            FieldError::TooManyFields { expected, found } => write!(f, "expected {} fields, found {}", expected, found),
+            FieldError::MissingField(name) => write!(f, "required field {} is empty", name),
+            FieldError::OutOfRange(name) => write!(f, "field {} is out of range", name),
+            FieldError::TooLong(name) => write!(f, "field {} is too long", name),
+            FieldError::InvalidCharacter(name) => write!(f, "field {} holds a character that cannot be sent", name),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FieldError {}
+
+// The number of fields in a complete sentence, not counting repeating groups.
+const fn field_count(fields: &[FieldInfo]) -> usize {
+    let mut count = 0;
+    let mut i = 0;
+    while i < fields.len() {
+        let field = &fields[i];
+        if !matches!(field.kind, FieldKind::Group) {
+            if field.index >= count {
+                count = field.index + 1;
+            }
+            if let Some(unit_index) = field.unit_index {
+                if unit_index >= count {
+                    count = unit_index + 1;
+                }
+            }
+        }
+        i += 1;
+    }
+    count
+}
+
+// Two fields may only share an index if one is an alias, if they share a unit field, or
+// if one is a group repeating fields that are also read one by one.
+const fn overlapping_fields(fields: &[FieldInfo]) -> bool {
+    let mut i = 0;
+    while i < fields.len() {
+        let mut j = i + 1;
+        while j < fields.len() {
+            let (a, b) = (&fields[i], &fields[j]);
+            let exempt = a.alias || b.alias || matches!(a.kind, FieldKind::Group) || matches!(b.kind, FieldKind::Group);
+            if !exempt && (a.index == b.index
+                || matches!(a.unit_index, Some(u) if u == b.index)
+                || matches!(b.unit_index, Some(u) if u == a.index)) {
+                return true;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    false
+}
+
+// Sentences from older equipment often stop early, so only the fields up to the last
+// required one have to be there.  More fields than listed means the sentence is not what
+// we think it is, unless it ends in a repeating group.  Some gateways add a trailing empty
+// field, which is not counted.
+fn validate_fields<B: FieldSource + ?Sized>(base: &B, fields: &[FieldInfo]) -> Result<(), FieldError> {
+    let found = base.field_count();
+    let expected = field_count(fields);
+    let grouped = fields.iter().any(|f| f.kind == FieldKind::Group);
+    let used = (0..found).rev().find(|&i| base.field(i).is_some_and(|v| !v.is_empty())).map_or(0, |i| i + 1);
+    if used > expected && !grouped {
+        return Err(FieldError::TooManyFields { expected, found });
+    }
+    if let Some(last) = fields.iter().filter(|f| f.required).map(|f| f.unit_index.unwrap_or(0).max(f.index)).max() {
+        if found <= last {
+            return Err(FieldError::TooFewFields { expected: last + 1, found });
+        }
+    }
+    for field in fields.iter().filter(|f| f.required) {
+        let present = |i: usize| base.field(i).is_some_and(|v| !v.is_empty());
+        if !present(field.index) || field.unit_index.is_some_and(|u| !present(u)) {
+            return Err(FieldError::MissingField(field.name));
+        }
+    }
+    Ok(())
+}
+
+// Fields that hold a number outside their range.  Empty fields and text that is not a
+// number are left to the accessors.
+fn out_of_range<'a, B: FieldSource + ?Sized>(base: &'a B, fields: &'static [FieldInfo]) -> impl Iterator<Item = FieldError> + 'a {
+    fields.iter().filter(|f| !f.alias).filter_map(move |field| {
+        let (lo, hi) = field.range?;
+        let value = base.field(field.index)?.parse::<f64>().ok()?;
+        (value < lo || value > hi).then_some(FieldError::OutOfRange(field.name))
+    })
+}
+
+// Builds a sentence one field at a time.  The setters are named after the accessors;
+// build() fills in the checksum.
+#[cfg(feature = "alloc")]
+pub struct SentenceBuilder<T> {
+    sender: String,
+    fields: Vec<String>,
+    sentence: core::marker::PhantomData<T>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T> SentenceBuilder<T> {
+    fn new(sender: &str) -> Self {
+        Self {
+            sender: sender.to_string(),
+            fields: Vec::new(),
+            sentence: core::marker::PhantomData,
+        }
+    }
+}
+
+// A field value for code that handles every sentence the same way, such as a dashboard.
+// Numbers are read as f64 straight from the text, so 2.1 stays 2.1.  A field that does not
+// parse as its declared kind is passed on as Text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value<'a> {
+    Number(f64),
+    Char(char),
+    // The field as it was sent, with any ^HH escapes left in; the string accessors decode them.
+    Text(&'a str),
+    #[cfg(feature = "chrono")]
+    Time(NaiveTime),
+    #[cfg(feature = "chrono")]
+    Date(NaiveDate),
+    Coordinate(f64),
+    WithUnit(f64, char),
+    Missing,
+}
+
+impl Display for Value<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Value::Number(v) | Value::Coordinate(v) => write!(f, "{}", v),
+            Value::Char(c) => write!(f, "{}", c),
+            Value::Text(t) => write!(f, "{}", t),
+            #[cfg(feature = "chrono")]
+            Value::Time(t) => write!(f, "{}", t),
+            #[cfg(feature = "chrono")]
+            Value::Date(d) => write!(f, "{}", d),
+            Value::WithUnit(v, unit) => write!(f, "{} {}", v, unit),
+            Value::Missing => Ok(()),
+        }
+    }
+}
+
+// Aliases are left out, since they repeat another field.
+fn field_values<'a, B: FieldSource + ?Sized>(base: &'a B, fields: &'static [FieldInfo]) -> impl Iterator<Item = (&'static str, Value<'a>)> {
+    fields.iter().filter(|f| !f.alias).map(move |f| (f.name, field_value(base, f)))
+}
+
+fn field_value<'a, B: FieldSource + ?Sized>(base: &'a B, field: &FieldInfo) -> Value<'a> {
+    // A group is everything from its first field to the checksum.
+    if field.kind == FieldKind::Group {
+        let original = base.original();
+        let start = original.match_indices(',').nth(field.index).map(|(i, _)| i + 1);
+        let end = original.rfind('*').unwrap_or(original.len());
+        return match start {
+            Some(start) if start < end => Value::Text(&original[start..end]),
+            _ => Value::Missing,
+        };
+    }
+    let text = match base.field(field.index) {
+        Some(text) if !text.is_empty() => text,
+        _ => return Value::Missing,
+    };
+    let unit = field.unit_index.and_then(|i| base.get::<char>(i));
+    let value = match field.kind {
+        FieldKind::Number => base.get::<f64>(field.index).map(|v| match unit {
+            Some(unit) => Value::WithUnit(v, unit),
+            None => Value::Number(v),
+        }),
+        FieldKind::Hex => base.get_hex::<u64>(field.index).map(|v| Value::Number(v as f64)),
+        FieldKind::Char => base.get::<char>(field.index).map(Value::Char),
+        #[cfg(feature = "chrono")]
+        FieldKind::Time => base.get_time(field.index).map(Value::Time),
+        #[cfg(feature = "chrono")]
+        FieldKind::Date => base.get_date(field.index).map(Value::Date),
+        FieldKind::Coordinate => base.get::<f64>(field.index).map(|v| match unit {
+            Some('S') | Some('W') => Value::Coordinate(-v),
+            _ => Value::Coordinate(v),
+        }),
+        _ => None,
+    };
+    value.unwrap_or(Value::Text(text))
+}
+
+// A run of fields that repeats to the end of the sentence, such as the satellites in GSV.
+pub trait FieldGroup: Sized {
+    const WIDTH: usize;
+
+    fn read<S: FieldSource + ?Sized>(base: &S, index: usize) -> Option<Self>;
+
+    #[cfg(feature = "alloc")]
+    fn write(&self, fields: &mut Vec<String>, index: usize);
+}
+
+impl FieldGroup for f32 {
+    const WIDTH: usize = 1;
+
+    fn read<S: FieldSource + ?Sized>(base: &S, index: usize) -> Option<Self> {
+        base.get::<f32>(index)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn write(&self, fields: &mut Vec<String>, index: usize) {
+        serialization::set(fields, index, self.to_string());
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FieldGroup for String {
+    const WIDTH: usize = 1;
+
+    fn read<S: FieldSource + ?Sized>(base: &S, index: usize) -> Option<Self> {
+        base.get::<String>(index)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn write(&self, fields: &mut Vec<String>, index: usize) {
+        serialization::set(fields, index, self.clone());
+    }
+}
+
+impl<A: FromStr + Display, B: FromStr + Display> FieldGroup for (A, B) {
+    const WIDTH: usize = 2;
+
+    fn read<S: FieldSource + ?Sized>(base: &S, index: usize) -> Option<Self> {
+        Some((base.get::<A>(index)?, base.get::<B>(index + 1)?))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn write(&self, fields: &mut Vec<String>, index: usize) {
+        serialization::set(fields, index, self.0.to_string());
+        serialization::set(fields, index + 1, self.1.to_string());
+    }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ValueWithUnit<T> {
     value: T,
     unit: char,
 }
 
+impl<T: Copy> ValueWithUnit<T> {
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    pub fn unit(&self) -> char {
+        self.unit
+    }
+}
+
+#[cfg(feature = "alloc")]
 #[derive(Debug)]
 pub struct NmeaBaseSentence {
     _sender: String,
@@ -123,12 +845,39 @@ pub struct NmeaBaseSentence {
     _original: String,
 }
 
+// Without an allocator the typed views default to sentences borrowed from static buffers.
+#[cfg(feature = "alloc")]
+type DefaultBase = NmeaBaseSentence;
+#[cfg(not(feature = "alloc"))]
+type DefaultBase = NmeaSentenceRef<'static>;
+
+#[cfg(feature = "alloc")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ErrorData {
     pub error : String,
     pub message : String,
 }
 
+// Without an allocator the offending sentence is not copied; the caller still has it.
+#[cfg(not(feature = "alloc"))]
+#[derive(Debug)]
+pub struct ErrorData {
+    pub error : &'static str,
+}
+
+impl ErrorData {
+    #[cfg(feature = "alloc")]
+    fn new(error: &str, line: &[u8]) -> Self {
+        Self { error: error.to_string(), message: String::from_utf8_lossy(line).into_owned() }
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn new(error: &'static str, _line: &[u8]) -> Self {
+        Self { error }
+    }
+}
+
 make_data!(AamData);
 make_data!(AbkData);
 make_data!(AcaData);
@@ -236,6 +985,7 @@ make_data!(VpwData);
 make_data!(VsdData);
 make_data!(VtgData);
 make_data!(VwrData);
+make_data!(WatData);
 make_data!(WcvData);
 make_data!(WdcData);
 make_data!(WdrData);
@@ -253,58 +1003,40 @@ make_data!(BbmData);
 make_data!(VdmData);
 make_data!(VdoData);
 
-impl NmeaBaseSentence {
-    pub fn new(value : String) -> Self {
-        Self {
-            _sender: "".to_string(),
-            _message_type: "".to_string(),
-            _fields: vec![],
-            _checksum: 0,
-            _original : value,
-        }
-    }
+make_raw_fields!(
+    AdsData, AkdData, AlaData, AsdData, BecData, CekData, CopData, DcrData, DdcData,
+    DscData, DseData, DsiData, DsrData, EveData, GmpData, LrfData, Lr1Data, Lr2Data, Lr3Data, MlaData, RmaData,
+    TutData, WdcData, WdrData, VdmData, VdoData
+);
 
-    pub fn default() -> Self {
-        Self {
-            _sender: "".to_string(),
-            _message_type: "".to_string(),
-            _fields: vec![],
-            _checksum: 0,
-            _original: "".to_string(),
-        }
-    }
+// Where the typed accessors read their fields from.  NmeaBaseSentence owns its fields;
+// NmeaSentenceRef borrows them from the caller's buffer.
+pub trait FieldSource {
+    fn original(&self) -> &str;
 
-    pub fn sender(&self) -> String {
-        self._sender.clone()
-    }
+    fn sender_str(&self) -> &str;
 
-    pub fn message_type(&self) -> String {
-        self._message_type.clone()
-    }
+    fn message_type_str(&self) -> &str;
 
-    pub fn checksum(&self) -> u8 {
-        self._checksum
+    fn field(&self, index: usize) -> Option<&str>;
+
+    fn field_count(&self) -> usize;
+
+    fn get<T: FromStr>(&self, index: usize) -> Option<T> {
+        self.field(index).filter(|f| !f.is_empty())?.parse::<T>().ok()
     }
 
-    pub fn get<T: FromStr>(&self, index: usize) -> Option<T> {
-        if index < self.nfields() && !self._fields[index].is_empty() {
-            match self._fields[index].parse::<T>() {
-                Ok(v) => Some(v),
-                _ => None
-            }
-        } else { None }
+    fn get_hex<T: Num>(&self, index: usize) -> Option<T> {
+        T::from_str_radix(self.field(index).filter(|f| !f.is_empty())?, 16).ok()
     }
 
-    pub fn get_hex<T: Num>(&self, index: usize) -> Option<T> {
-        if index < self.nfields() && !self._fields[index].is_empty() {
-            match T::from_str_radix(&self._fields[index], 16) {
-                Ok(v) => Some(v),
-                _ => None
-            }
-        } else { None }
+    // Text with its ^HH escapes decoded, e.g. ^2C for a comma.
+    #[cfg(feature = "alloc")]
+    fn get_text(&self, index: usize) -> Option<String> {
+        self.field(index).filter(|f| !f.is_empty()).map(serialization::unescape)
     }
 
-    pub fn get_pair<T: FromStr>(&self, index1: usize, index2 : usize) -> Option<ValueWithUnit<T>> {
+    fn get_pair<T: FromStr>(&self, index1: usize, index2 : usize) -> Option<ValueWithUnit<T>> {
         if let Some(v) = self.get::<T>(index1) {
             if let Some(c) = self.get::<char>(index2) {
                 return Some(ValueWithUnit {
@@ -316,16 +1048,20 @@ impl NmeaBaseSentence {
         None
     }
 
-    pub fn get_time(&self, index: usize) -> Option<NaiveTime> {
-        if index >= self._fields.len() || self._fields[index].is_empty() {
-            return None
-        };
-        let field = &self._fields[index];
-        if let Ok(hours) = field[0..2].parse::<u32>() {
-            if let Ok(minutes) = field[2..4].parse::<u32>() {
-                if let Ok(seconds) = field[5..].parse::<f32>() {
-                    let millis = ((seconds-seconds.floor()) * 1000.0).floor() as u32;
-                    let seconds = seconds.floor() as u32;
+    #[cfg(feature = "chrono")]
+    fn get_time(&self, index: usize) -> Option<NaiveTime> {
+        let field = self.field(index).filter(|f| !f.is_empty())?;
+        if let Ok(hours) = field.get(0..2)?.parse::<u32>() {
+            if let Ok(minutes) = field.get(2..4)?.parse::<u32>() {
+                // Milliseconds are read as digits rather than through a float, which would
+                // turn .37 into .369.
+                let (seconds, fraction) = field.get(4..)?.split_once('.').unwrap_or((&field[4..], ""));
+                let fraction = fraction.get(..fraction.len().min(3))?;
+                let millis = match fraction {
+                    "" => Ok(0),
+                    _ => fraction.parse::<u32>().map(|m| m * 10u32.pow(3 - fraction.len() as u32)),
+                };
+                if let (Ok(seconds), Ok(millis)) = (seconds.parse::<u32>(), millis) {
                     return NaiveTime::from_hms_milli_opt(hours, minutes, seconds, millis);
                 }
             }
@@ -333,7 +1069,7 @@ impl NmeaBaseSentence {
         None
     }
 
-    pub fn get_coordinate(&self, index_n: usize, index_n_s_e_w: usize) -> Option<f32> {
+    fn get_coordinate(&self, index_n: usize, index_n_s_e_w: usize) -> Option<f32> {
         if let Some(coordinate) = self.get::<f32>(index_n) {
             if let Some(hemisphere) = self.get::<char>(index_n_s_e_w) {
                 return if hemisphere == 'S' || hemisphere == 'W' {
@@ -346,21 +1082,118 @@ impl NmeaBaseSentence {
         None
     }
 
-    pub fn get_date(&self, index: usize) -> Option<NaiveDate> {
-        if let Some(t) = self.get::<String>(index)  {
-            let days_s = &t[0..2];
-            let month_s = &t[2..4];
-            let year_s = &t[4..];
+    #[cfg(feature = "chrono")]
+    fn get_date(&self, index: usize) -> Option<NaiveDate> {
+        if let Some(t) = self.field(index).filter(|f| !f.is_empty()) {
+            let days_s = t.get(0..2)?;
+            let month_s = t.get(2..4)?;
+            let year_s = t.get(4..)?;
             let days = days_s.parse::<u32>().unwrap_or(0);
             let months = month_s.parse::<u32>().unwrap_or(0);
-            let years = year_s.parse::<i32>().unwrap_or(0) + 2000;
+            let years = year_s.parse::<i32>().unwrap_or(0).checked_add(2000)?;
             NaiveDate::from_ymd_opt(years, months, days)
         } else {
             None
         }
     }
-}
 
+    #[cfg(feature = "alloc")]
+    fn get_group<T: FieldGroup>(&self, index: usize) -> Option<Vec<T>> {
+        let group = (index..self.field_count())
+            .step_by(T::WIDTH)
+            .filter_map(|i| T::read(self, i))
+            .collect::<Vec<T>>();
+        if group.is_empty() { None } else { Some(group) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl NmeaBaseSentence {
+    pub fn new(value : String) -> Self {
+        Self {
+            _sender: "".to_string(),
+            _message_type: "".to_string(),
+            _fields: vec![],
+            _checksum: 0,
+            _original : value,
+        }
+    }
+
+    // Builds a sentence from its parts, calculating the checksum.  The sender includes the
+    // introductory "$" or "!", e.g. NmeaBaseSentence::build("$II", "HDG", &fields).
+    pub fn build(sender: &str, message_type: &str, fields: &[String]) -> Self {
+        let mut chars = sender.chars();
+        let start = chars.next().unwrap_or('$');
+        let body = format!("{}{},{}", chars.as_str(), message_type, fields.join(","));
+        let checksum = body.bytes().fold(0, |acc, x| acc ^ x);
+        let sentence = format!("{}{}*{:02X}", start, body, checksum);
+        NmeaBaseSentence::from(&sentence)
+    }
+
+    pub fn sender(&self) -> String {
+        self._sender.clone()
+    }
+
+    pub fn message_type(&self) -> String {
+        self._message_type.clone()
+    }
+
+    pub fn checksum(&self) -> u8 {
+        self._checksum
+    }
+
+    pub fn get<T: FromStr>(&self, index: usize) -> Option<T> {
+        FieldSource::get(self, index)
+    }
+
+    pub fn get_hex<T: Num>(&self, index: usize) -> Option<T> {
+        FieldSource::get_hex(self, index)
+    }
+
+    pub fn get_text(&self, index: usize) -> Option<String> {
+        FieldSource::get_text(self, index)
+    }
+
+    pub fn get_pair<T: FromStr>(&self, index1: usize, index2 : usize) -> Option<ValueWithUnit<T>> {
+        FieldSource::get_pair(self, index1, index2)
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn get_time(&self, index: usize) -> Option<NaiveTime> {
+        FieldSource::get_time(self, index)
+    }
+
+    pub fn get_coordinate(&self, index_n: usize, index_n_s_e_w: usize) -> Option<f32> {
+        FieldSource::get_coordinate(self, index_n, index_n_s_e_w)
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn get_date(&self, index: usize) -> Option<NaiveDate> {
+        FieldSource::get_date(self, index)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Default for NmeaBaseSentence {
+    fn default() -> Self {
+        Self {
+            _sender: "".to_string(),
+            _message_type: "".to_string(),
+            _fields: vec![],
+            _checksum: 0,
+            _original: "".to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Display for NmeaBaseSentence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self._original)
+    }
+}
+
+#[cfg(feature = "alloc")]
 impl Clone for NmeaBaseSentence {
     fn clone(&self) -> Self {
         Self {
@@ -373,105 +1206,338 @@ impl Clone for NmeaBaseSentence {
     }
 }
 
+// A base sentence serializes as the sentence text.
+#[cfg(feature = "serde")]
+impl serde::Serialize for NmeaBaseSentence {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self._original)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for NmeaBaseSentence {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let sentence = String::deserialize(deserializer)?;
+        Ok(NmeaBaseSentence::from(&sentence))
+    }
+}
+
+#[cfg(feature = "alloc")]
 impl From<&String> for NmeaBaseSentence {
     fn from(value: &String) -> Self {
-        // Integrity checks...
-        //    Is it at least 9 characters long? $SSMMM*HH
-        //    or the third-to-last character is not an asterisk
-        let value = value.clone();
-        let message_length = value.len();
-        if message_length < 9 || value[message_length-3..message_length-2] != *"*" {
-            return NmeaBaseSentence::new(value);
-        }
-        let checksum_string = &value[message_length-2..];
-        let calculated_checksum : u8;
-        if let Ok(checksum) = u8::from_str_radix(&checksum_string, 16) {
-            calculated_checksum=
-                value[1..message_length-3]
-                    .bytes().fold(0, |acc, x| acc ^ x);
-            if checksum != calculated_checksum {
-                return NmeaBaseSentence::new(value);
+        NmeaSentenceRef::from(value.as_str()).into_owned()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FieldSource for NmeaBaseSentence {
+    fn original(&self) -> &str {
+        &self._original
+    }
+
+    fn sender_str(&self) -> &str {
+        &self._sender
+    }
+
+    fn message_type_str(&self) -> &str {
+        &self._message_type
+    }
+
+    fn field(&self, index: usize) -> Option<&str> {
+        self._fields.get(index).map(|f| f.as_str())
+    }
+
+    fn field_count(&self) -> usize {
+        self._fields.len()
+    }
+}
+
+// The most fields a sentence can have: a standard sentence is at most 82 characters.
+pub const MAX_FIELDS: usize = 82;
+
+// A sentence parsed in place.  The fields are byte ranges into the caller's buffer, so
+// parsing allocates nothing; into_owned() copies it into an NmeaBaseSentence when needed.
+// Sentences with more than MAX_FIELDS fields are treated as invalid.
+#[derive(Debug, Clone)]
+pub struct NmeaSentenceRef<'a> {
+    original: &'a str,
+    sender_length: usize,
+    prolog_length: usize,
+    checksum: u8,
+    fields: [(u16, u16); MAX_FIELDS],
+    nfields: usize,
+}
+
+impl<'a> NmeaSentenceRef<'a> {
+    fn invalid(original: &'a str) -> Self {
+        Self {
+            original,
+            sender_length: 0,
+            prolog_length: 0,
+            checksum: 0,
+            fields: [(0, 0); MAX_FIELDS],
+            nfields: 0,
+        }
+    }
+
+    pub fn sender(&self) -> &'a str {
+        &self.original[..self.sender_length]
+    }
+
+    pub fn message_type(&self) -> &'a str {
+        &self.original[self.sender_length..self.prolog_length]
+    }
+
+    pub fn checksum(&self) -> u8 {
+        self.checksum
+    }
+
+    pub fn nfields(&self) -> usize {
+        self.nfields
+    }
+
+    pub fn get_field(&self, index: usize) -> Option<&'a str> {
+        if index < self.nfields {
+            let (start, end) = self.fields[index];
+            Some(&self.original[start as usize..end as usize])
+        } else { None }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn into_owned(self) -> NmeaBaseSentence {
+        if self.prolog_length == 0 {
+            return NmeaBaseSentence::new(self.original.to_string());
+        }
+        NmeaBaseSentence {
+            _sender: self.sender().to_string(),
+            _message_type: self.message_type().to_string(),
+            _fields: (0..self.nfields).filter_map(|i| self.get_field(i)).map(|f| f.to_string()).collect(),
+            _checksum: self.checksum,
+            _original: self.original.to_string(),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for NmeaSentenceRef<'a> {
+    fn from(value: &'a str) -> Self {
+        Self::parse(value.as_bytes()).unwrap_or_else(|_| Self::invalid(value))
+    }
+}
+
+enum Checksum {
+    Valid(u8),
+    Missing(u8),
+    Mismatch { expected: u8, found: u8 },
+}
+
+fn hex_digit(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        _ => None,
+    }
+}
+
+impl<'a> NmeaSentenceRef<'a> {
+    // Parses raw bytes, such as a line read from a serial port.  Nothing here indexes past
+    // the end of the input, and anything but ASCII is an error, so the fields always fall
+    // on character boundaries and no input can panic.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, &'static str> {
+        match Self::read(bytes)? {
+            (sentence, Checksum::Valid(_)) => Ok(sentence),
+            (_, Checksum::Missing(_)) => Err("Missing checksum"),
+            (_, Checksum::Mismatch { .. }) => Err("Checksum mismatch"),
+        }
+    }
+
+    // Splits the sentence into its fields and works out its checksum, leaving it to the
+    // caller to decide what a bad checksum means.  The first byte, $ or !, is not checked.
+    fn read(bytes: &'a [u8]) -> Result<(Self, Checksum), &'static str> {
+        if !bytes.is_ascii() {
+            return Err("Non-ASCII character");
+        }
+        if bytes.len() > u16::MAX as usize {
+            return Err("Sentence too long");
+        }
+        let original = core::str::from_utf8(bytes).map_err(|_| "Non-ASCII character")?;
+        let calculate = |body: &[u8]| body.iter().fold(0, |acc, x| acc ^ x);
+
+        // A sentence with no asterisk at all has no checksum; one anywhere but third from
+        // the end is malformed.
+        let (body, checksum) = match bytes {
+            [_, body @ .., b'*', high, low] => {
+                let found = hex_digit(*high).zip(hex_digit(*low)).map(|(h, l)| h << 4 | l).ok_or("Malformed checksum")?;
+                let expected = calculate(body);
+                let checksum = match found == expected {
+                    true => Checksum::Valid(found),
+                    false => Checksum::Mismatch { expected, found },
+                };
+                (&bytes[..bytes.len() - 3], checksum)
             }
-        } else {
-            return NmeaBaseSentence::new(value);
-        }
-
-        // All OK - time to get down to business...
-        if let Some((prolog, epilog)) = value.split_once(',') {
-            let prolog_length = prolog.len();
-            let sender_length = prolog_length - 3;
-            let sender = prolog[0..sender_length].to_string();
-            let message = prolog[sender_length..].to_string();
-            let mut fields = epilog.split(',')
-                .map(|x| x.to_string())
-                .collect::<Vec<String>>();
-            let last = fields.pop().unwrap();
-            let (body, _): (&str, &str) = last.split_once('*').unwrap_or(("", ""));
-            fields.push(body.to_string());
-            Self {
-                _sender: sender,
-                _message_type: message,
-                _fields: fields,
-                _checksum: calculated_checksum,
-                _original: value.clone(),
+            _ if bytes.contains(&b'*') => return Err("Malformed sentence"),
+            [_, body @ ..] => (bytes, Checksum::Missing(calculate(body))),
+            [] => return Err("Malformed sentence"),
+        };
+
+        // The prolog is the start character, the talker and a three-letter message type.
+        let prolog_length = body.iter().position(|b| *b == b',').ok_or("Malformed sentence")?;
+        if prolog_length < 4 {
+            return Err("Malformed sentence");
+        }
+        let mut sentence = Self::invalid(original);
+        let mut start = prolog_length + 1;
+        for field in body[start..].split(|b| *b == b',') {
+            if sentence.nfields == MAX_FIELDS {
+                return Err("Too many fields");
             }
-        } else {
-            Self::new(value)
+            sentence.fields[sentence.nfields] = (start as u16, (start + field.len()) as u16);
+            sentence.nfields += 1;
+            start += field.len() + 1;
         }
+        sentence.sender_length = prolog_length - 3;
+        sentence.prolog_length = prolog_length;
+        sentence.checksum = match checksum {
+            Checksum::Valid(found) | Checksum::Mismatch { found, .. } => found,
+            Checksum::Missing(calculated) => calculated,
+        };
+        Ok((sentence, checksum))
     }
 }
 
+impl Display for NmeaSentenceRef<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.original)
+    }
+}
+
+impl FieldSource for NmeaSentenceRef<'_> {
+    fn original(&self) -> &str {
+        self.original
+    }
+
+    fn sender_str(&self) -> &str {
+        self.sender()
+    }
+
+    fn message_type_str(&self) -> &str {
+        self.message_type()
+    }
+
+    fn field(&self, index: usize) -> Option<&str> {
+        self.get_field(index)
+    }
+
+    fn field_count(&self) -> usize {
+        self.nfields
+    }
+}
+
+#[cfg(feature = "alloc")]
 impl NmeaBaseTrait for NmeaBaseSentence {
     fn get_base(&self) -> &NmeaBaseSentence {
         self
     }
 }
 
-impl AamData {
-    make_char_field!(arrival_status, 0);
-    make_char_field!(perpendicular_status, 1);
+make_fields!(AamData {
+    /// Arrival circle entered: A = yes, V = no.
+    [required] make_char_field!(arrival_status, 0);
+    /// Perpendicular passed at the waypoint: A = yes, V = no.
+    [required] make_char_field!(perpendicular_status, 1);
+    /// Arrival circle radius; the unit is N for nautical miles.
     make_number_field!(arrival_circle_radius, f32, 2, 3);
+    /// Waypoint ID.
     make_string_field!(waypoint_id, 4);
-}
+});
 
-impl AbkData {
+make_fields!(AbkData {
+    /// MMSI of the addressed AIS unit.
     make_string_field!(mmsi, 0);
-    make_number_field!(channel, u16, 1);
-    make_number_field!(message_id, u16, 2);
+    /// AIS channel of reception: A or B.
+    make_char_field!(channel, 1);
+    /// ITU message ID being acknowledged.
+    [required] make_number_field!(message_id, u16, 2);
+    /// Message sequence number.
     make_number_field!(sequence_number, u16, 3);
-    make_number_field!(acknowledgement, u8, 4);
-}
-
-impl AcaData {
-    make_number_field!(sequence_number, u8, 0);
-    make_coordinate_field!(ne_latitude, 1, 2);
-    make_coordinate_field!(ne_longitude, 3, 4);
-    make_coordinate_field!(sw_latitude, 5, 6);
-    make_coordinate_field!(sw_longitude, 7, 8);
+    /// Type of acknowledgement: 0 = received, 1 = not received, 2 = cannot be broadcast, 3 = accepted for broadcast, 4 = late reception.
+    [required] make_number_field!(acknowledgement, u8, 4);
+});
+
+make_fields!(AbmData {
+    /// Total number of sentences in the message, 1 to 9.
+    [required] make_number_field!(total, u8, 0);
+    /// Number of this sentence, 1 to 9.
+    [required] make_number_field!(number, u8, 1);
+    /// Sequential message identifier, 0 to 3; the sentences of one message share it.
+    [required] make_number_field!(sequential_id, u8, 2);
+    /// MMSI of the destination AIS unit.
+    [required] make_string_field!(mmsi, 3);
+    /// AIS channel for broadcast: 0 = no preference, 1 = A, 2 = B, 3 = both.
+    make_number_field!(channel, u8, 4);
+    /// ITU-R M.1371 message ID, e.g. 6 = binary or 12 = safety related.
+    [required] make_number_field!(message_id, u8, 5);
+    /// Six-bit armored data.
+    make_string_field!(data, 6);
+    /// Number of fill bits added to the data, 0 to 5.
+    [range(0, 5)] make_number_field!(fill_bits, u8, 7);
+});
+
+make_fields!(AcaData {
+    /// Sequence number, 0 to 9; 0 is used when the sentence is not part of a set.
+    [range(0, 9)] make_number_field!(sequence_number, u8, 0);
+    /// Latitude of the north-east corner of the region.
+    [range(0, 9000)] make_coordinate_field!(ne_latitude, 1, 2);
+    /// Longitude of the north-east corner of the region.
+    [range(0, 18000)] make_coordinate_field!(ne_longitude, 3, 4);
+    /// Latitude of the south-west corner of the region.
+    [range(0, 9000)] make_coordinate_field!(sw_latitude, 5, 6);
+    /// Longitude of the south-west corner of the region.
+    [range(0, 18000)] make_coordinate_field!(sw_longitude, 7, 8);
+    /// Transition zone size in nautical miles.
     make_number_field!(transition_zone_size, f32, 9);
+    /// Channel A number.
     make_number_field!(channel_a, u16, 10);
-    make_number_field!(channel_a_bandwidth, f32, 11);
+    /// Channel A bandwidth: 0 = default, 1 = 12.5 kHz.
+    [range(0, 1)] make_number_field!(channel_a_bandwidth, f32, 11);
+    /// Channel B number.
     make_number_field!(channel_b, u16, 12);
-    make_number_field!(channel_b_bandwidth, f32, 13);
+    /// Channel B bandwidth: 0 = default, 1 = 12.5 kHz.
+    [range(0, 1)] make_number_field!(channel_b_bandwidth, f32, 13);
+    /// Transmit/receive mode control.
     make_number_field!(tx_rx_mode_control, u8, 14);
-    make_number_field!(power_level, u8, 15);
+    /// Power level: 0 = high, 1 = low.
+    [range(0, 1)] make_number_field!(power_level, u8, 15);
+    /// Information source, e.g. A for an ITU-R M.1371 message.
     make_char_field!(information_source, 16);
-    make_number_field!(in_use_flag, u8, 17);
+    /// In-use flag: 0 = not in use, 1 = in use.
+    [range(0, 1)] make_number_field!(in_use_flag, u8, 17);
+    /// Time the in-use flag changed to 1.
     make_time_field!(time, 18);
-}
+});
 
-impl AckData {
-    make_number_field!(alarm_number, u8, 0);
-}
+make_fields!(AckData {
+    /// Number of the alarm being acknowledged.
+    [required] make_number_field!(alarm_number, u8, 0);
+});
 
-impl AcsData {
-    make_number_field!(sequence_number, u8, 0);
+make_fields!(AcsData {
+    /// Sequence number, 0 to 9.
+    [range(0, 9)] make_number_field!(sequence_number, u8, 0);
+    /// MMSI of the station that originated the channel management information.
     make_string_field!(mmsi, 1);
+    /// UTC time the information was received.
     make_time_field!(time, 2);
-    make_number_field!(day, u8, 3);
-    make_number_field!(month, u8, 4);
+    /// UTC day, 01 to 31.
+    [range(1, 31)] make_number_field!(day, u8, 3);
+    /// UTC month, 01 to 12.
+    [range(1, 12)] make_number_field!(month, u8, 4);
+    /// UTC year.
     make_number_field!(year, u16, 5);
+});
 
+#[cfg(feature = "chrono")]
+impl<B: FieldSource> AcsData<B> {
     pub fn timestamp(&self) -> Option<DateTime<Utc>> {
         if let Some(t) = self.base.get_time(2) {
             if let Some(day) = self.base.get::<u32>(3) {
@@ -479,7 +1545,7 @@ impl AcsData {
                     if let Some(year) = self.base.get::<i32>(5) {
                         if let Some(d) = NaiveDate::from_ymd_opt(year, month, day) {
                             let n = NaiveDateTime::new(d, t);
-                            return Some(DateTime::<Utc>::from_utc(n, Utc));
+                            return Some(DateTime::<Utc>::from_naive_utc_and_offset(n, Utc));
                         }
                     }
                 }
@@ -489,273 +1555,572 @@ impl AcsData {
     }
 }
 
-impl AirData {
+make_fields!(AirData {
+    /// MMSI of the first interrogated station.
     make_string_field!(mmsi, 0);
+    /// First message requested from the first station.
     make_number_field!(requested_message, u8, 1);
+    /// Message sub-section of the first request.
     make_number_field!(sub_section, u8, 2);
+    /// Second message requested from the first station.
     make_number_field!(requested_message_2, u8, 3);
+    /// Message sub-section of the second request.
     make_number_field!(sub_section_2, u8, 4);
+    /// MMSI of the second interrogated station.
     make_string_field!(mmsi_station_2, 5);
+    /// Message requested from the second station.
     make_number_field!(requested_message_station_2, u8, 6);
+    /// Message sub-section of the request to the second station.
     make_number_field!(sub_section_station_2, u8, 7);
-}
-
-impl AlmData {
-    make_number_field!(sentence_count_total, u16, 0);
-    make_number_field!(sentence_number, u16, 1);
-    make_number_field!(satellite_prn, u8, 2);
+});
+
+make_fields!(AlmData {
+    /// Total number of sentences in this almanac set.
+    [required] make_number_field!(sentence_count_total, u16, 0);
+    /// Number of this sentence.
+    [required] make_number_field!(sentence_number, u16, 1);
+    /// Satellite PRN number, 01 to 32.
+    [required] make_number_field!(satellite_prn, u8, 2);
+    /// GPS week number.
     make_number_field!(gps_week_number, u16, 3);
+    /// SV health, bits 17-24 of each almanac page.
     make_hex_field!(health, u8, 4);
+    /// Eccentricity.
     make_hex_field!(eccentricity, f32, 5);
+    /// Almanac reference time.
     make_hex_field!(reference_time, u8, 6);
+    /// Inclination angle.
     make_hex_field!(inclination_angle, f32, 7);
+    /// Rate of right ascension.
     make_hex_field!(right_ascension_rate, f32, 8);
+    /// Square root of the semi-major axis.
     make_hex_field!(root_semi_major_axis, f32, 9);
+    /// Argument of perigee.
     make_hex_field!(perigee, f32, 10);
+    /// Longitude of the ascension node.
     make_hex_field!(ascension_node_longitude, f32, 11);
+    /// Mean anomaly.
     make_hex_field!(mean_anomaly, f32, 12);
+    /// Clock parameter F0.
     make_hex_field!(clock_parameter_f0, f32, 13);
+    /// Clock parameter F1.
     make_hex_field!(clock_parameter_f1, f32, 14);
-}
+});
 
-impl AlrData {
+make_fields!(AlrData {
+    /// Time the alarm condition last changed.
     make_time_field!(time, 0);
-    make_number_field!(alarm_id, u16, 1);
-    make_char_field!(condition, 2);
-    make_char_field!(acknowledge_state, 3);
+    /// Unique alarm number at the alarm source.
+    [required] make_number_field!(alarm_id, u16, 1);
+    /// Alarm condition: A = threshold exceeded, V = not exceeded.
+    [required] make_char_field!(condition, 2);
+    /// Alarm acknowledge state: A = acknowledged, V = unacknowledged.
+    [required] make_char_field!(acknowledge_state, 3);
+    /// Alarm description text.
     make_string_field!(text, 4);
-}
-
-impl ApaData {
-    make_char_field!(status_blink_snr_warning, 0);
-    make_char_field!(status_cycle_lock_warning, 1);
+});
+
+make_fields!(ApaData {
+    /// Status: V = LORAN-C blink or SNR warning, A = valid.
+    [required] make_char_field!(status_blink_snr_warning, 0);
+    /// Status: V = LORAN-C cycle lock warning, A = valid.
+    [required] make_char_field!(status_cycle_lock_warning, 1);
+    /// Magnitude of the cross-track error; the unit is N for nautical miles or K for kilometres.
     make_number_field!(xte, f32, 2, 4);
+    /// Direction to steer: L or R.
     make_char_field!(steer_direction, 3);
+    /// Arrival circle entered: A = yes, V = no.
     make_char_field!(arrival_status, 5);
+    /// Perpendicular passed at the waypoint: A = yes, V = no.
     make_char_field!(perpendicular_status, 6);
-    make_number_field!(bearing_origin_to_destination, f32, 7, 8);
+    /// Bearing from origin to destination; the unit is M for magnetic or T for true.
+    [range(0, 360)] make_number_field!(bearing_origin_to_destination, f32, 7, 8);
+    /// Destination waypoint ID.
     make_string_field!(destination_id, 9);
-}
-
-impl ApbData {
-    make_char_field!(status_a, 0);
-    make_char_field!(status_v, 1);
+});
+
+make_fields!(ApbData {
+    /// Status: V = LORAN-C blink or SNR warning, A = valid.
+    [required] make_char_field!(status_a, 0);
+    /// Status: V = LORAN-C cycle lock warning, A = valid.
+    [required] make_char_field!(status_v, 1);
+    /// Magnitude of the cross-track error; the unit is N for nautical miles or K for kilometres.
     make_number_field!(xte, f32, 2, 4);
+    /// Direction to steer: L or R.
     make_char_field!(steer_direction, 3);
+    /// Arrival circle entered: A = yes, V = no.
     make_char_field!(arrival_status, 5);
+    /// Perpendicular passed at the waypoint: A = yes, V = no.
     make_char_field!(perpendicular_status, 6);
-    make_number_field!(bearing_origin_to_destination, f32, 7, 8);
+    /// Bearing from origin to destination; the unit is M for magnetic or T for true.
+    [range(0, 360)] make_number_field!(bearing_origin_to_destination, f32, 7, 8);
+    /// Destination waypoint ID.
     make_string_field!(destination_id, 9);
-    make_number_field!(bearing_present_to_destination, f32, 10, 11);
-    make_number_field!(heading_to_steer_to_waypoint, f32, 12, 13);
-}
-
-impl BodData {
-    make_number_field!(bearing_true, f32, 0, 1);
-    make_number_field!(bearing_magnetic, f32, 2, 3);
+    /// Bearing from the present position to the destination; the unit is M or T.
+    [range(0, 360)] make_number_field!(bearing_present_to_destination, f32, 10, 11);
+    /// Heading to steer to the destination; the unit is M or T.
+    [range(0, 360)] make_number_field!(heading_to_steer_to_waypoint, f32, 12, 13);
+    /// Mode indicator: A = autonomous, D = differential, E = estimated, M = manual, S = simulator, N = not valid.
+    make_char_field!(mode, 14);
+});
+
+make_fields!(BbmData {
+    /// Total number of sentences in the message, 1 to 9.
+    [required] make_number_field!(total, u8, 0);
+    /// Number of this sentence, 1 to 9.
+    [required] make_number_field!(number, u8, 1);
+    /// Sequential message identifier, 0 to 9; the sentences of one message share it.
+    [required] make_number_field!(sequential_id, u8, 2);
+    /// AIS channel for broadcast: 0 = no preference, 1 = A, 2 = B, 3 = both.
+    make_number_field!(channel, u8, 3);
+    /// ITU-R M.1371 message ID, e.g. 8 = binary or 14 = safety related.
+    [required] make_number_field!(message_id, u8, 4);
+    /// Six-bit armored data.
+    make_string_field!(data, 5);
+    /// Number of fill bits added to the data, 0 to 5.
+    [range(0, 5)] make_number_field!(fill_bits, u8, 6);
+});
+
+make_fields!(BodData {
+    /// Bearing from origin to destination, true; the unit is T.
+    [range(0, 360)] make_number_field!(bearing_true, f32, 0, 1);
+    /// Bearing from origin to destination, magnetic; the unit is M.
+    [range(0, 360)] make_number_field!(bearing_magnetic, f32, 2, 3);
+    /// Destination waypoint ID.
     make_string_field!(destination_waypoint_id, 4);
+    /// Origin waypoint ID.
     make_string_field!(origin_waypoint_id, 5);
-}
+});
 
-impl BwcData {
+make_fields!(BwcData {
+    /// UTC time of the observation.
     make_time_field!(time, 0);
-    make_number_field!(latitude, f32, 1, 2);
-    make_number_field!(longitude, f32, 3, 4);
-    make_number_field!(bearing_true, f32, 5, 6);
-    make_number_field!(bearing_magnetic, f32, 7, 8);
+    /// Latitude of the waypoint in ddmm.mmmm; the unit is the hemisphere, N or S.
+    [range(0, 9000)] make_number_field!(latitude, f32, 1, 2);
+    /// Longitude of the waypoint in dddmm.mmmm; the unit is the hemisphere, E or W.
+    [range(0, 18000)] make_number_field!(longitude, f32, 3, 4);
+    /// Bearing to the waypoint, true; the unit is T.
+    [range(0, 360)] make_number_field!(bearing_true, f32, 5, 6);
+    /// Bearing to the waypoint, magnetic; the unit is M.
+    [range(0, 360)] make_number_field!(bearing_magnetic, f32, 7, 8);
+    /// Distance to the waypoint; the unit is N for nautical miles.
     make_number_field!(distance, f32, 9, 10);
+    /// Waypoint ID.
     make_string_field!(waypoint_id, 11);
+    /// Mode indicator: A = autonomous, D = differential, E = estimated, M = manual, S = simulator, N = not valid.
     make_char_field!(mode_indicator, 12);
-}
+});
 
-impl BwrData {
+make_fields!(BwrData {
+    /// UTC time of the observation.
     make_time_field!(time, 0);
-    make_number_field!(latitude, f32, 1, 2);
-    make_number_field!(longitude, f32, 3, 4);
-    make_number_field!(bearing_true, f32, 5, 6);
-    make_number_field!(bearing_magnetic, f32, 7, 8);
+    /// Latitude of the waypoint in ddmm.mmmm; the unit is the hemisphere, N or S.
+    [range(0, 9000)] make_number_field!(latitude, f32, 1, 2);
+    /// Longitude of the waypoint in dddmm.mmmm; the unit is the hemisphere, E or W.
+    [range(0, 18000)] make_number_field!(longitude, f32, 3, 4);
+    /// Rhumb line bearing to the waypoint, true; the unit is T.
+    [range(0, 360)] make_number_field!(bearing_true, f32, 5, 6);
+    /// Rhumb line bearing to the waypoint, magnetic; the unit is M.
+    [range(0, 360)] make_number_field!(bearing_magnetic, f32, 7, 8);
+    /// Rhumb line distance to the waypoint; the unit is N for nautical miles.
     make_number_field!(distance, f32, 9, 10);
+    /// Waypoint ID.
     make_string_field!(waypoint_id, 11);
+    /// Mode indicator: A = autonomous, D = differential, E = estimated, M = manual, S = simulator, N = not valid.
     make_char_field!(mode_indicator, 12);
-}
-
-impl BwwData {
-    make_number_field!(bearing_true, f32, 0, 1);
-    make_number_field!(bearing_magnetic, f32, 2, 3);
+});
+
+make_fields!(BwwData {
+    /// Bearing from the TO waypoint to the FROM waypoint, true; the unit is T.
+    [range(0, 360)] make_number_field!(bearing_true, f32, 0, 1);
+    /// Bearing from the TO waypoint to the FROM waypoint, magnetic; the unit is M.
+    [range(0, 360)] make_number_field!(bearing_magnetic, f32, 2, 3);
+    /// TO waypoint ID.
     make_string_field!(destination_waypoint_id, 4);
+    /// FROM waypoint ID.
     make_string_field!(origin_waypoint_id, 5);
-}
-
-impl DbkData {
+});
+
+make_fields!(CurData {
+    /// Validity: A = valid, V = not valid.
+    [required] make_char_field!(validity, 0);
+    /// Data set number, 0 to 9; the layers of one profile share it.
+    [range(0, 9)] make_number_field!(data_set, u8, 1);
+    /// Layer number.
+    make_number_field!(layer, u8, 2);
+    /// Depth of the layer in metres.
+    make_number_field!(depth, f32, 3);
+    /// Direction the current flows towards, in degrees.
+    [range(0, 360)] make_number_field!(direction, f32, 4);
+    /// Direction reference: T = true, R = relative to the heading.
+    make_char_field!(direction_reference, 5);
+    /// Speed of the current in knots.
+    make_number_field!(speed, f32, 6);
+    /// Depth of the reference layer in metres.
+    make_number_field!(reference_layer_depth, f32, 7);
+    /// Heading, in degrees.
+    [range(0, 360)] make_number_field!(heading, f32, 8);
+    /// Heading reference: T = true, M = magnetic.
+    make_char_field!(heading_reference, 9);
+    /// Speed reference: B = bottom track, W = water track, P = positioning system.
+    make_char_field!(speed_reference, 10);
+});
+
+make_fields!(DbkData {
+    /// Depth below the keel in feet; the unit is f.
     make_number_field!(depth_feet, f32, 0, 1);
+    /// Depth below the keel in metres; the unit is M.
     make_number_field!(depth_meters, f32, 2, 3);
+    /// Depth below the keel in fathoms; the unit is F.
     make_number_field!(depth_fathoms, f32, 4, 5);
-}
+});
 
-impl DbsData {
+make_fields!(DbsData {
+    /// Depth below the surface in feet; the unit is f.
     make_number_field!(depth_feet, f32, 0, 1);
+    /// Depth below the surface in metres; the unit is M.
     make_number_field!(depth_meters, f32, 2, 3);
+    /// Depth below the surface in fathoms; the unit is F.
     make_number_field!(depth_fathoms, f32, 4, 5);
-}
+});
 
-impl DbtData {
+make_fields!(DbtData {
+    /// Depth below the transducer in feet; the unit is f.
     make_number_field!(depth_feet, f32, 0, 1);
+    /// Depth below the transducer in metres; the unit is M.
     make_number_field!(depth_meters, f32, 2, 3);
+    /// Depth below the transducer in fathoms; the unit is F.
     make_number_field!(depth_fathoms, f32, 4, 5);
-}
+});
 
-impl DcnData {
+make_fields!(DcnData {
+    /// Decca chain identifier.
     make_number_field!(chain_identifier, f32, 0);
+    /// Red zone identifier.
     make_string_field!(red_zone_identider, 1);
+    /// Red line of position.
     make_number_field!(red_lop, f32, 2);
+    /// Red master line status: A = valid, V = not valid.
     make_char_field!(red_master_status, 3);
+    /// Green zone identifier.
     make_string_field!(green_zone_identifier, 4);
+    /// Green line of position.
     make_number_field!(green_lop, f32, 5);
+    /// Green master line status: A = valid, V = not valid.
     make_char_field!(green_master_status, 6);
+    /// Purple zone identifier.
     make_string_field!(purple_zone_identifier, 7);
+    /// Purple line of position.
     make_number_field!(purple_lop, f32, 8);
+    /// Purple master line status: A = valid, V = not valid.
     make_char_field!(purple_master_status, 9);
+    /// Red line used for navigation: A = yes, V = no.
     make_char_field!(red_navigation_use, 10);
+    /// Green line used for navigation: A = yes, V = no.
     make_char_field!(green_navigation_use, 11);
+    /// Purple line used for navigation: A = yes, V = no.
     make_char_field!(purple_navigation_use, 12);
+    /// Position uncertainty; the unit is N for nautical miles.
     make_number_field!(position_uncertainty, f32, 13, 14);
+    /// Fix data basis: 1 = normal pattern, 2 = lane identification pattern, 3 = lane identification transmissions.
     make_number_field!(fix_data_basis, u8, 15);
-}
+});
 
-impl DptData {
+make_fields!(DorData {
+    /// Message type: S = status of a section, E = status of one door, F = fault in the system.
+    [required] make_char_field!(message_type, 0);
+    /// Time of the status.
+    make_time_field!(time, 1);
+    /// Type of door monitoring panel: WT = watertight, WS = semi-watertight, FD = fire door, HD = hull door, OT = other.
+    make_string_field!(panel_type, 2);
+    /// First division indicator, e.g. the deck or zone.
+    make_string_field!(division, 3);
+    /// Second division indicator, e.g. the section.
+    make_number_field!(subdivision, u16, 4);
+    /// Door number, or the number of doors in the section for a section status.
+    make_number_field!(door_number, u16, 5);
+    /// Door status: O = open, C = closed, S = secured, F = free, X = fault.
+    make_char_field!(status, 6);
+    /// Watertight door switch setting: O = harbour mode, doors may be open, C = sea mode, doors ordered closed.
+    make_char_field!(switch_setting, 7);
+    /// Description, e.g. the location of the door.
+    make_string_field!(description, 8);
+});
+
+make_fields!(DptData {
+    /// Water depth relative to the transducer, in metres.
     make_number_field!(water_depth_meters, f32, 0);
+    /// Offset from the transducer in metres; positive is the distance to the waterline, negative the distance to the keel.
     make_number_field!(transducer_offset, f32, 1);
+    /// Maximum range scale in use, in metres.
     make_number_field!(maximum_range_scale, f32, 2);
-}
+});
 
-impl DtmData {
+make_fields!(DtmData {
+    /// Local datum code: W84, W72, S85, P90, 999 for user defined, or an IHO datum code.
     make_string_field!(datum_code, 0);
+    /// Local datum subdivision code.
     make_number_field!(datum_subcode, u8, 1);
-    make_number_field!(latitude, f32, 2, 3);
-    make_number_field!(longitude, f32, 4, 5);
+    /// Latitude offset in minutes; the unit is the hemisphere, N or S.
+    [range(0, 9000)] make_number_field!(latitude, f32, 2, 3);
+    /// Longitude offset in minutes; the unit is the hemisphere, E or W.
+    [range(0, 18000)] make_number_field!(longitude, f32, 4, 5);
+    /// Altitude offset in metres.
     make_number_field!(altitude, f32, 6);
+    /// Reference datum code: W84, W72, S85 or P90.
     make_string_field!(datum, 7);
-}
+});
 
-impl FsiData {
+make_fields!(FsiData {
+    /// Transmitting frequency.
     make_number_field!(transmitting_frequency, f32, 0);
+    /// Receiving frequency.
     make_number_field!(receiving_frequency, f32, 1);
+    /// Mode of operation, e.g. d = F3E/G3E simplex, telephone.
     make_char_field!(communications_mode, 2);
+    /// Power level: 0 = standby, 1 = lowest, 9 = highest.
     make_number_field!(power_level, u8, 3);
-}
+});
 
-impl GbsData {
+make_fields!(EtlData {
+    /// UTC time of the event.
+    make_time_field!(time, 0);
+    /// Message type: O = order, A = answer-back.
+    [required] make_char_field!(message_type, 1);
+    /// Telegraph position: 00 = stop, 01 to 05 = ahead from dead slow to navigation full, 11 to 15 = astern from dead slow to crash astern.
+    make_number_field!(telegraph_position, u8, 2);
+    /// Sub-telegraph position: 20 = stand-by, 30 = full away, 40 = finished with engine.
+    make_number_field!(sub_telegraph_position, u8, 3);
+    /// Operating location: B = bridge, P = port wing, S = starboard wing, C = engine control room, E = engine side, W = wing.
+    make_char_field!(operating_location, 4);
+    /// Engine or shaft number, numbered from the centreline; odd is starboard, even is port, 0 is single or on the centreline.
+    make_number_field!(engine_number, u8, 5);
+});
+
+make_fields!(FirData {
+    /// Message type: S = status of a section, E = status of one detector, F = fault in the system.
+    [required] make_char_field!(message_type, 0);
+    /// Time of the status.
+    make_time_field!(time, 1);
+    /// Type of detection: FD = generic, FH = heat, FS = smoke, FM = manual call point, GD = gas, GO = oxygen, GS = hydrogen sulphide, GH = hydrocarbon, SF = sprinkler flow, SV = sprinkler valve, CO = CO2 release, OT = other.
+    make_string_field!(detection_type, 2);
+    /// First division indicator, the zone.
+    make_string_field!(zone, 3);
+    /// Second division indicator, the loop.
+    make_number_field!(detector_loop, u16, 4);
+    /// Detector number, or the number of activated detectors for a section status.
+    make_number_field!(detector_number, u16, 5);
+    /// Condition: A = activated, V = not activated, X = fault, state unknown.
+    make_char_field!(condition, 6);
+    /// Alarm acknowledgement: A = acknowledged, V = not acknowledged.
+    make_char_field!(acknowledgement, 7);
+    /// Description, e.g. the location of the detector.
+    make_string_field!(description, 8);
+});
+
+make_fields!(GbsData {
+    /// UTC time of the GGA or GNS fix this sentence refers to.
     make_time_field!(time, 0);
+    /// Expected error in latitude, in metres.
     make_number_field!(expected_latitude_error, f32, 1);
+    /// Expected error in longitude, in metres.
     make_number_field!(expected_longitude_error, f32, 2);
+    /// Expected error in altitude, in metres.
     make_number_field!(expected_altitude_error, f32, 3);
+    /// ID of the most likely failed satellite.
     make_number_field!(failed_satellite_id, u16, 4);
+    /// Probability of missed detection for the most likely failed satellite.
     make_number_field!(probability_missed_detection, f32, 5);
+    /// Estimate of the bias on the most likely failed satellite, in metres.
     make_number_field!(estimated_bias, f32, 6);
+    /// Standard deviation of the bias estimate.
     make_number_field!(standard_deviation_estimated_bias, f32, 7);
-}
+});
 
-impl GgaData {
+make_fields!(GgaData {
+    /// UTC time of the fix.
     make_time_field!(time, 0);
-    make_number_field!(latitude, f32, 1, 2);
-    make_number_field!(longitude, f32, 3, 4);
-    make_number_field!(quality_indicator, u8, 5);
+    /// Latitude in ddmm.mmmm; the unit is the hemisphere, N or S.
+    [range(0, 9000)] make_number_field!(latitude, f32, 1, 2);
+    /// Longitude in dddmm.mmmm; the unit is the hemisphere, E or W.
+    [range(0, 18000)] make_number_field!(longitude, f32, 3, 4);
+    /// Fix quality: 0 = no fix, 1 = GPS, 2 = differential, 4 = RTK fixed, 5 = RTK float, 6 = estimated.
+    [required] make_number_field!(quality_indicator, u8, 5);
+    /// Number of satellites in use.
     make_number_field!(satellites_in_use_count, u8, 6);
+    /// Horizontal dilution of precision.
     make_number_field!(hdop, f32, 7);
+    /// Antenna altitude above mean sea level; the unit is M for metres.
     make_number_field!(antenna_altitude, f32, 8, 9);
+    /// Geoidal separation, the height of the geoid above the WGS84 ellipsoid; the unit is M.
     make_number_field!(geoidal_separation, f32, 10, 11);
+    /// Age of the differential corrections, in seconds.
     make_number_field!(age_differential_gps_data, f32, 12);
+    /// Differential reference station ID.
     make_number_field!(differential_station_id, u16, 13);
-}
+});
 
-impl GlcData {
+make_fields!(GlcData {
+    /// Group repetition interval, in tens of microseconds.
     make_number_field!(gri_microseconds, f32, 0);
+    /// Master time of arrival, in microseconds.
     make_number_field!(master_toa_microseconds, f32, 1);
+    /// Master signal status: B = blink warning, C = cycle warning, S = SNR warning, A = valid.
     make_char_field!(master_toa_status, 2);
+    /// Time difference 1, in microseconds.
     make_number_field!(time_difference_1_microseconds, f32, 3);
+    /// Time difference 1 signal status.
     make_char_field!(time_difference_1_signal_status, 4);
+    /// Time difference 2, in microseconds.
     make_number_field!(time_difference_2_microseconds, f32, 5);
+    /// Time difference 2 signal status.
     make_char_field!(time_difference_2_signal_status, 6);
+    /// Time difference 3, in microseconds.
     make_number_field!(time_difference_3_microseconds, f32, 7);
+    /// Time difference 3 signal status.
     make_char_field!(time_difference_3_signal_status, 8);
+    /// Time difference 4, in microseconds.
     make_number_field!(time_difference_4_microseconds, f32, 9);
+    /// Time difference 4 signal status.
     make_char_field!(time_difference_4_signal_status, 10);
+    /// Time difference 5, in microseconds.
     make_number_field!(time_difference_5_microseconds, f32, 11);
+    /// Time difference 5 signal status.
     make_char_field!(time_difference_5_signal_status, 12);
-}
-
-impl GllData {
-    make_number_field!(latitude, f32, 0, 1);
-    make_number_field!(longitude, f32, 2, 3);
+});
+
+make_fields!(GllData {
+    /// Latitude in ddmm.mmmm; the unit is the hemisphere, N or S.
+    [range(0, 9000)] make_number_field!(latitude, f32, 0, 1);
+    /// Longitude in dddmm.mmmm; the unit is the hemisphere, E or W.
+    [range(0, 18000)] make_number_field!(longitude, f32, 2, 3);
+    /// UTC time of the position.
     make_time_field!(time, 4);
-    make_char_field!(status, 5);
+    /// Status: A = data valid, V = data not valid.
+    [required] make_char_field!(status, 5);
+    /// Mode indicator: A = autonomous, D = differential, E = estimated, M = manual, S = simulator, N = not valid.
     make_char_field!(mode, 6);
-}
+});
 
-impl GnsData {
+make_fields!(GnsData {
+    /// UTC time of the fix.
     make_time_field!(time, 0);
-    make_number_field!(latitude, f32, 1, 2);
-    make_number_field!(longitude, f32, 3, 4);
-    make_char_field!(mode, 5);
+    /// Latitude in ddmm.mmmm; the unit is the hemisphere, N or S.
+    [range(0, 9000)] make_number_field!(latitude, f32, 1, 2);
+    /// Longitude in dddmm.mmmm; the unit is the hemisphere, E or W.
+    [range(0, 18000)] make_number_field!(longitude, f32, 3, 4);
+    /// Mode indicator, one character per constellation: GPS, GLONASS, Galileo, BeiDou, QZSS and NavIC.
+    [required] make_string_field!(mode, 5);
+    /// Number of satellites in use.
     make_number_field!(satellites_in_use_count, u8, 6);
+    /// Horizontal dilution of precision.
     make_number_field!(hdop, f32, 7);
+    /// Antenna altitude above mean sea level, in metres.
     make_number_field!(antenna_altitude, f32, 8);
+    /// Geoidal separation, in metres.
     make_number_field!(geoidal_separation, f32, 9);
+    /// Age of the differential corrections, in seconds.
     make_number_field!(age_differential_gps_data, f32, 10);
+    /// Differential reference station ID.
     make_number_field!(differential_station_id, u16, 11);
-}
+    /// Navigational status, NMEA 4.10 and later: S = safe, C = caution, U = unsafe, V = not valid.
+    make_char_field!(navigational_status, 12);
+});
 
-impl GrsData {
+make_fields!(GrsData {
+    /// UTC time of the GGA or GNS fix this sentence refers to.
     make_time_field!(time, 0);
-    make_number_field!(residual_calculation, u8, 1);
+    /// Residual mode: 0 = residuals used to calculate the position, 1 = recomputed after the position was calculated.
+    [required] make_number_field!(residual_calculation, u8, 1);
+    /// Range residual of the 1st satellite in the GSA, in metres.
     make_number_field!(satellite_1_residual, u8, 2);
+    /// Range residual of the 2nd satellite in the GSA, in metres.
     make_number_field!(satellite_2_residual, u8, 3);
+    /// Range residual of the 3rd satellite in the GSA, in metres.
     make_number_field!(satellite_3_residual, u8, 4);
+    /// Range residual of the 4th satellite in the GSA, in metres.
     make_number_field!(satellite_4_residual, u8, 5);
+    /// Range residual of the 5th satellite in the GSA, in metres.
     make_number_field!(satellite_5_residual, u8, 6);
+    /// Range residual of the 6th satellite in the GSA, in metres.
     make_number_field!(satellite_6_residual, u8, 7);
+    /// Range residual of the 7th satellite in the GSA, in metres.
     make_number_field!(satellite_7_residual, u8, 8);
+    /// Range residual of the 8th satellite in the GSA, in metres.
     make_number_field!(satellite_8_residual, u8, 9);
+    /// Range residual of the 9th satellite in the GSA, in metres.
     make_number_field!(satellite_9_residual, u8, 10);
+    /// Range residual of the 10th satellite in the GSA, in metres.
     make_number_field!(satellite_10_residual, u8, 11);
+    /// Range residual of the 11th satellite in the GSA, in metres.
     make_number_field!(satellite_11_residual, u8, 12);
+    /// Range residual of the 12th satellite in the GSA, in metres.
     make_number_field!(satellite_12_residual, u8, 13);
+    /// GNSS system ID, NMEA 4.10 and later.
     make_number_field!(system_id_1, u8, 14);
+    /// GNSS signal ID, NMEA 4.10 and later.
     make_number_field!(system_id_2, u8, 15);
-}
-
-impl GsaData {
-    make_char_field!(selection_mode, 0);
-    make_number_field!(mode, u8, 1);
+});
+
+make_fields!(GsaData {
+    /// Selection mode: M = manual, A = automatic 2D/3D.
+    [required] make_char_field!(selection_mode, 0);
+    /// Fix mode: 1 = no fix, 2 = 2D, 3 = 3D.
+    [required] make_number_field!(mode, u8, 1);
+    /// ID of the 1st satellite used in the solution.
     make_number_field!(satellite_id_1, u8, 2);
+    /// ID of the 2nd satellite used in the solution.
     make_number_field!(satellite_id_2, u8, 3);
+    /// ID of the 3rd satellite used in the solution.
     make_number_field!(satellite_id_3, u8, 4);
+    /// ID of the 4th satellite used in the solution.
     make_number_field!(satellite_id_4, u8, 5);
+    /// ID of the 5th satellite used in the solution.
     make_number_field!(satellite_id_5, u8, 6);
+    /// ID of the 6th satellite used in the solution.
     make_number_field!(satellite_id_6, u8, 7);
+    /// ID of the 7th satellite used in the solution.
     make_number_field!(satellite_id_7, u8, 8);
+    /// ID of the 8th satellite used in the solution.
     make_number_field!(satellite_id_8, u8, 9);
+    /// ID of the 9th satellite used in the solution.
     make_number_field!(satellite_id_9, u8, 10);
+    /// ID of the 10th satellite used in the solution.
     make_number_field!(satellite_id_10, u8, 11);
+    /// ID of the 11th satellite used in the solution.
     make_number_field!(satellite_id_11, u8, 12);
+    /// ID of the 12th satellite used in the solution.
     make_number_field!(satellite_id_12, u8, 13);
+    /// Position dilution of precision.
     make_number_field!(pdop, f32, 14);
+    /// Horizontal dilution of precision.
     make_number_field!(hdop, f32, 15);
+    /// Vertical dilution of precision.
     make_number_field!(vdop, f32, 16);
+    /// GNSS system ID, NMEA 4.10 and later.
     make_number_field!(system_id, u8, 17);
-}
+});
 
-impl GstData {
+make_fields!(GstData {
+    /// UTC time of the GGA or GNS fix this sentence refers to.
     make_time_field!(time, 0);
+    /// RMS value of the standard deviation of the range inputs.
     make_number_field!(total_rms_standard_deviation, f32, 1);
+    /// Standard deviation of the semi-major axis of the error ellipse, in metres.
     make_number_field!(semi_major_error_standard_deviation, f32, 2);
+    /// Standard deviation of the semi-minor axis of the error ellipse, in metres.
     make_number_field!(semi_minor_errpr_standard_deviation, f32, 3);
+    /// Orientation of the semi-major axis of the error ellipse, in degrees from true north.
     make_number_field!(semi_major_orientation, f32, 4);
+    /// Standard deviation of the latitude error, in metres.
     make_number_field!(latitude_error_standard_deviation, f32, 5);
+    /// Standard deviation of the longitude error, in metres.
     make_number_field!(longitude_error_standard_deviation, f32, 6);
+    /// Standard deviation of the altitude error, in metres.
     make_number_field!(altitude_error_standard_deviation, f32, 7);
-}
+});
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SatelliteInfo {
     satellite_id : u16,
     elevation: f32,
@@ -763,829 +2128,2481 @@ pub struct SatelliteInfo {
     snr : f32,
 }
 
-impl GsvData {
-    make_number_field!(total_number_of_sentences, u8, 0);
-    make_number_field!(sentence_number, u8, 1);
-    make_number_field!(total_number_of_satellites_in_view, u16, 2);
-
-    pub fn satellite_info(&self) -> Option<Vec<SatelliteInfo>> {
-        let mut returned_vec = Vec::new();
-        for i in (3..self.base.nfields()).step_by(4) {
-            if let Some(satellite_id) = self.base.get::<u16>(i) {
-                if let Some(elevation) = self.base.get::<f32>(i+1) {
-                    if let Some(azimuth) = self.base.get::<f32>(i+2) {
-                        if let Some(snr) = self.base.get::<f32>(i+3) {
-                            returned_vec.push(SatelliteInfo {
-                                satellite_id,
-                                elevation,
-                                azimuth,
-                                snr
-                            });
-                        }
-                    }
-                }
-            }
-        }
-        if returned_vec.is_empty() {
-            None
-        } else {
-            Some(returned_vec)
-        }
+impl SatelliteInfo {
+    pub fn satellite_id(&self) -> u16 {
+        self.satellite_id
     }
-}
 
-impl GtdData {
-    pub fn time_differences(&self) -> Option<Vec<f32>> {
-        let mut differences = Vec::new();
-        for i in 0..self.nfields() {
-            if let Some(n) = self.base.get::<f32>(i) {
-                differences.push(n);
-            }
-        }
-        if !differences.is_empty() {
-            return Some(differences);
-        };
-        None
+    pub fn elevation(&self) -> f32 {
+        self.elevation
     }
-}
 
-impl GxaData {
-    make_time_field!(time, 0);
-    make_number_field!(latitude, f32, 1, 2);
-    make_number_field!(longitude, f32, 3, 4);
-    make_string_field!(waypoint_id, 5);
-    make_number_field!(satellite_id, u16, 6);
-}
+    pub fn azimuth(&self) -> f32 {
+        self.azimuth
+    }
 
-impl HdgData {
-    make_number_field!(sensor_heading_degrees, f32, 0);
-    make_number_field!(deviation, f32, 1, 2);
-    make_number_field!(variation, f32, 3, 4);
+    pub fn snr(&self) -> f32 {
+        self.snr
+    }
 }
 
-impl HdmData {
-    make_number_field!(heading_magnetic, f32, 0, 1);
-}
+impl FieldGroup for SatelliteInfo {
+    const WIDTH: usize = 4;
+
+    fn read<S: FieldSource + ?Sized>(base: &S, index: usize) -> Option<Self> {
+        Some(SatelliteInfo {
+            satellite_id: base.get::<u16>(index)?,
+            elevation: base.get::<f32>(index + 1)?,
+            azimuth: base.get::<f32>(index + 2)?,
+            snr: base.get::<f32>(index + 3)?,
+        })
+    }
 
-impl HdtData {
-    make_number_field!(heading_true, f32, 0, 1);
+    #[cfg(feature = "alloc")]
+    fn write(&self, fields: &mut Vec<String>, index: usize) {
+        serialization::set(fields, index, self.satellite_id.to_string());
+        serialization::set(fields, index + 1, self.elevation.to_string());
+        serialization::set(fields, index + 2, self.azimuth.to_string());
+        serialization::set(fields, index + 3, self.snr.to_string());
+    }
 }
 
-impl HfbData {
+make_fields!(GsvData {
+    /// Total number of GSV sentences in this cycle.
+    [required] make_number_field!(total_number_of_sentences, u8, 0);
+    /// Number of this sentence.
+    [required] make_number_field!(sentence_number, u8, 1);
+    /// Total number of satellites in view.
+    [required] make_number_field!(total_number_of_satellites_in_view, u16, 2);
+    /// Up to four satellites: ID, elevation, azimuth and SNR.
+    make_group_field!(satellite_info, SatelliteInfo, 3);
+});
+
+make_fields!(GtdData {
+    /// Up to five LORAN-C time differences, in microseconds.
+    make_group_field!(time_differences, f32, 0);
+});
+
+make_fields!(GxaData {
+    /// UTC time of the fix.
+    make_time_field!(time, 0);
+    /// Latitude in ddmm.mmmm; the unit is the hemisphere, N or S.
+    [range(0, 9000)] make_number_field!(latitude, f32, 1, 2);
+    /// Longitude in dddmm.mmmm; the unit is the hemisphere, E or W.
+    [range(0, 18000)] make_number_field!(longitude, f32, 3, 4);
+    /// Waypoint ID.
+    make_string_field!(waypoint_id, 5);
+    /// Satellite number.
+    make_number_field!(satellite_id, u16, 6);
+});
+
+make_fields!(HdgData {
+    /// Magnetic sensor heading, in degrees.
+    [range(0, 360)] make_number_field!(sensor_heading_degrees, f32, 0);
+    /// Magnetic deviation in degrees; the unit is E or W.
+    [range(0, 180)] make_number_field!(deviation, f32, 1, 2);
+    /// Magnetic variation in degrees; the unit is E or W.
+    [range(0, 180)] make_number_field!(variation, f32, 3, 4);
+});
+
+make_fields!(HdmData {
+    /// Heading, magnetic; the unit is M.
+    [range(0, 360)] make_number_field!(heading_magnetic, f32, 0, 1);
+});
+
+make_fields!(HdtData {
+    /// Heading, true; the unit is T.
+    [range(0, 360)] make_number_field!(heading_true, f32, 0, 1);
+});
+
+make_fields!(HfbData {
+    /// Distance from the headrope to the footrope; the unit is M for metres.
     make_number_field!(distance_headrope_to_footrope, f32, 0, 1);
+    /// Distance from the headrope to the bottom; the unit is M for metres.
     make_number_field!(distance_headrope_to_bottom, f32, 2, 3);
-}
-
-impl HscData {
-    make_number_field!(heading_true, f32, 0, 1);
-    make_number_field!(heading_magnetic, f32, 2, 3);
-}
-
-impl ItsData {
+});
+
+make_fields!(HmrData {
+    /// ID of heading sensor 1.
+    make_string_field!(sensor_1_id, 0);
+    /// ID of heading sensor 2.
+    make_string_field!(sensor_2_id, 1);
+    /// Largest allowed difference between the sensors, in degrees.
+    [range(0, 180)] make_number_field!(set_difference, f32, 2);
+    /// Actual difference between the sensors, in degrees.
+    [range(0, 180)] make_number_field!(actual_difference, f32, 3);
+    /// Warning flag: A = within the set difference, V = set difference exceeded.
+    make_char_field!(warning, 4);
+    /// Heading reading of sensor 1, in degrees.
+    [range(0, 360)] make_number_field!(sensor_1_heading, f32, 5);
+    /// Status of sensor 1: A = data valid, V = data not valid.
+    make_char_field!(sensor_1_status, 6);
+    /// Type of sensor 1: T = true, M = magnetic.
+    make_char_field!(sensor_1_type, 7);
+    /// Deviation of sensor 1 in degrees; positive is east.
+    [range(-180, 180)] make_number_field!(sensor_1_deviation, f32, 8);
+    /// Variation at sensor 1 in degrees; the unit is E or W.
+    [range(0, 180)] make_number_field!(sensor_1_variation, f32, 9, 10);
+    /// Heading reading of sensor 2, in degrees.
+    [range(0, 360)] make_number_field!(sensor_2_heading, f32, 11);
+    /// Status of sensor 2: A = data valid, V = data not valid.
+    make_char_field!(sensor_2_status, 12);
+    /// Type of sensor 2: T = true, M = magnetic.
+    make_char_field!(sensor_2_type, 13);
+    /// Deviation of sensor 2 in degrees; positive is east.
+    [range(-180, 180)] make_number_field!(sensor_2_deviation, f32, 14);
+    /// Variation at sensor 2 in degrees; the unit is E or W.
+    [range(0, 180)] make_number_field!(sensor_2_variation, f32, 15, 16);
+});
+
+make_fields!(HmsData {
+    /// ID of heading sensor 1.
+    make_string_field!(sensor_1_id, 0);
+    /// ID of heading sensor 2.
+    make_string_field!(sensor_2_id, 1);
+    /// Largest allowed difference between the sensors, in degrees.
+    [range(0, 180)] make_number_field!(maximum_difference, f32, 2);
+});
+
+make_fields!(HscData {
+    /// Heading to steer, true; the unit is T.
+    [range(0, 360)] make_number_field!(heading_true, f32, 0, 1);
+    /// Heading to steer, magnetic; the unit is M.
+    [range(0, 360)] make_number_field!(heading_magnetic, f32, 2, 3);
+});
+
+make_fields!(HtcData {
+    /// Override: A = in use, V = not in use.
+    [required] make_char_field!(override_status, 0);
+    /// Commanded rudder angle in degrees.
+    [range(0, 90)] make_number_field!(commanded_rudder_angle, f32, 1);
+    /// Commanded rudder direction: L = port, R = starboard.
+    make_char_field!(commanded_rudder_direction, 2);
+    /// Selected steering mode: M = manual, S = stand-alone heading control, H = heading control with an external heading, T = track control, R = direct rudder control.
+    [required] make_char_field!(selected_steering_mode, 3);
+    /// Turn mode: R = radius controlled, T = turn rate controlled, N = not controlled.
+    make_char_field!(turn_mode, 4);
+    /// Commanded rudder limit in degrees.
+    [range(0, 90)] make_number_field!(commanded_rudder_limit, f32, 5);
+    /// Commanded off-heading limit in degrees.
+    [range(0, 180)] make_number_field!(commanded_off_heading_limit, f32, 6);
+    /// Commanded radius of turn for heading changes, in nautical miles.
+    make_number_field!(commanded_radius_of_turn, f32, 7);
+    /// Commanded rate of turn for heading changes, in degrees per minute.
+    make_number_field!(commanded_rate_of_turn, f32, 8);
+    /// Commanded heading to steer, in degrees.
+    [range(0, 360)] make_number_field!(commanded_heading_to_steer, f32, 9);
+    /// Commanded off-track limit, in nautical miles.
+    make_number_field!(commanded_off_track_limit, f32, 10);
+    /// Commanded track, in degrees.
+    [range(0, 360)] make_number_field!(commanded_track, f32, 11);
+    /// Reference of the headings and track: T = true, M = magnetic.
+    make_char_field!(heading_reference, 12);
+});
+
+make_fields!(HtdData {
+    /// Override: A = in use, V = not in use.
+    [required] make_char_field!(override_status, 0);
+    /// Commanded rudder angle in degrees.
+    [range(0, 90)] make_number_field!(commanded_rudder_angle, f32, 1);
+    /// Commanded rudder direction: L = port, R = starboard.
+    make_char_field!(commanded_rudder_direction, 2);
+    /// Selected steering mode: M = manual, S = stand-alone heading control, H = heading control with an external heading, T = track control, R = direct rudder control.
+    [required] make_char_field!(selected_steering_mode, 3);
+    /// Turn mode: R = radius controlled, T = turn rate controlled, N = not controlled.
+    make_char_field!(turn_mode, 4);
+    /// Commanded rudder limit in degrees.
+    [range(0, 90)] make_number_field!(commanded_rudder_limit, f32, 5);
+    /// Commanded off-heading limit in degrees.
+    [range(0, 180)] make_number_field!(commanded_off_heading_limit, f32, 6);
+    /// Commanded radius of turn for heading changes, in nautical miles.
+    make_number_field!(commanded_radius_of_turn, f32, 7);
+    /// Commanded rate of turn for heading changes, in degrees per minute.
+    make_number_field!(commanded_rate_of_turn, f32, 8);
+    /// Commanded heading to steer, in degrees.
+    [range(0, 360)] make_number_field!(commanded_heading_to_steer, f32, 9);
+    /// Commanded off-track limit, in nautical miles.
+    make_number_field!(commanded_off_track_limit, f32, 10);
+    /// Commanded track, in degrees.
+    [range(0, 360)] make_number_field!(commanded_track, f32, 11);
+    /// Reference of the headings and track: T = true, M = magnetic.
+    make_char_field!(heading_reference, 12);
+    /// Rudder status: A = within limits, V = limit reached or exceeded.
+    make_char_field!(rudder_status, 13);
+    /// Off-heading status: A = within limits, V = limit reached or exceeded.
+    make_char_field!(off_heading_status, 14);
+    /// Off-track status: A = within limits, V = limit reached or exceeded.
+    make_char_field!(off_track_status, 15);
+    /// Vessel heading, in degrees, in the heading reference.
+    [range(0, 360)] make_number_field!(vessel_heading, f32, 16);
+});
+
+make_fields!(ItsData {
+    /// Distance to the second spread sensor; the unit is M for metres.
     make_number_field!(second_spread_distance, f32, 0, 1);
-}
+});
 
-impl LcdData {
+make_fields!(LcdData {
+    /// Group repetition interval, in tens of microseconds.
     make_number_field!(gri_microseconds, u16, 0);
+    /// Master relative signal-to-noise ratio.
     make_number_field!(master_relative_snr, u16, 1);
+    /// Master relative envelope-to-cycle discrepancy.
     make_number_field!(master_relative_ecd, u16, 2);
+    /// Time difference 1, in microseconds.
     make_number_field!(time_difference_1_microseconds, u16, 3);
+    /// Time difference 1 signal status.
     make_number_field!(time_difference_1_status, u16, 4);
+    /// Time difference 2, in microseconds.
     make_number_field!(time_difference_2_microseconds, u16, 5);
+    /// Time difference 2 signal status.
     make_number_field!(time_difference_2_status, u16, 6);
+    /// Time difference 3, in microseconds.
     make_number_field!(time_difference_3_microseconds, u16, 7);
+    /// Time difference 3 signal status.
     make_number_field!(time_difference_3_status, u16, 8);
+    /// Time difference 4, in microseconds.
     make_number_field!(time_difference_4_microseconds, u16, 9);
+    /// Time difference 4 signal status.
     make_number_field!(time_difference_4_status, u16, 10);
+    /// Time difference 5, in microseconds.
     make_number_field!(time_difference_5_microseconds, u16, 11);
+    /// Time difference 5 signal status.
     make_number_field!(time_difference_5_status, u16, 12);
+    /// Up to five pairs of time difference and status.
+    make_group_field!(time_differences, (f32, f32), 3);
+});
 
-    pub fn time_differences(&self) -> Option<Vec<(f32, f32)>> {
-        let mut differences = Vec::new();
-        for i in (3..self.nfields()).step_by(2) {
-            if let Some(micros) = self.base.get::<f32>(i) {
-                if let Some(status) = self.base.get::<f32>(i+1) {
-                    differences.push((micros, status));
-                }
-            }
-        };
-        if differences.is_empty() { None }
-        else { Some(differences) }
-    }
-}
-
-impl MdaData {
+make_fields!(MdaData {
+    /// Barometric pressure in inches of mercury; the unit is I.
     make_number_field!(barometric_pressure_mercury, f32, 0, 1);
+    /// Barometric pressure in bars; the unit is B.
     make_number_field!(barometric_pressure_bars, f32, 2, 3);
+    /// Air temperature in degrees Celsius; the unit is C.
     make_number_field!(air_temperature_c, f32, 4, 5);
+    /// Water temperature in degrees Celsius; the unit is C.
     make_number_field!(water_temperature_c, f32, 6, 7);
-    make_number_field!(relative_humidity, f32, 8);
+    /// Relative humidity, in percent.
+    [range(0, 100)] make_number_field!(relative_humidity, f32, 8);
+    /// Absolute humidity, in percent.
     make_number_field!(absolute_humidity, f32, 9);
+    /// Dew point in degrees Celsius; the unit is C.
     make_number_field!(dew_point_c, f32, 10, 11);
-    make_number_field!(wind_direction_true, f32, 12, 13);
-    make_number_field!(wind_direction_magnetic, f32, 14, 15);
+    /// Wind direction, true; the unit is T.
+    [range(0, 360)] make_number_field!(wind_direction_true, f32, 12, 13);
+    /// Wind direction, magnetic; the unit is M.
+    [range(0, 360)] make_number_field!(wind_direction_magnetic, f32, 14, 15);
+    /// Wind speed in knots; the unit is N.
     make_number_field!(wind_speed_knots, f32, 16, 17);
+    /// Wind speed in metres per second; the unit is M.
     make_number_field!(wind_speed_mps, f32, 18, 19);
-}
+});
 
-impl MskData {
-    make_number_field!(beacon_frequency, f32, 0);
+make_fields!(MskData {
+    /// Beacon frequency, 283.5 to 325.0 kHz.
+    [range(283.5, 325)] make_number_field!(beacon_frequency, f32, 0);
+    /// Frequency selection: A = automatic, M = manual.
     make_char_field!(frequency_mode, 1);
+    /// Beacon bit rate: 25, 50, 100 or 200 bits per second.
     make_number_field!(beacon_bit_rate, u8, 2);
+    /// Bit rate selection: A = automatic, M = manual.
     make_char_field!(bitrate_mode, 3);
+    /// Interval for sending MSS status, in seconds.
     make_number_field!(interval_for_mss_message, u16, 4);
-}
+});
 
-impl MssData {
+make_fields!(MssData {
+    /// Signal strength, in dB referred to 1 uV/m.
     make_number_field!(signal_strength, u8, 0);
-    make_number_field!(snr, f32, 1);
-    make_number_field!(beacon_frequency, f32, 2);
+    /// Signal-to-noise ratio, in dB.
+    [range(0, 99)] make_number_field!(snr, f32, 1);
+    /// Beacon frequency, 283.5 to 325.0 kHz.
+    [range(283.5, 325)] make_number_field!(beacon_frequency, f32, 2);
+    /// Beacon bit rate, in bits per second.
     make_number_field!(beacon_bit_rate, u8, 3);
+    /// Channel number.
     make_number_field!(channel_number, u16, 4);
-}
+});
 
-impl MtwData {
+make_fields!(MtwData {
+    /// Water temperature; the unit is C for Celsius.
     make_number_field!(temperature, f32, 0, 1);
-}
-
-impl MwvData {
-    make_number_field!(wind_angle, f32, 0, 1);
+});
+
+make_fields!(MwdData {
+    /// Wind direction the wind is coming from, true; the unit is T.
+    [range(0, 360)] make_number_field!(wind_direction_true, f32, 0, 1);
+    /// Wind direction the wind is coming from, magnetic; the unit is M.
+    [range(0, 360)] make_number_field!(wind_direction_magnetic, f32, 2, 3);
+    /// Wind speed in knots; the unit is N.
+    make_number_field!(wind_speed_knots, f32, 4, 5);
+    /// Wind speed in metres per second; the unit is M.
+    make_number_field!(wind_speed_mps, f32, 6, 7);
+});
+
+make_fields!(MwvData {
+    /// Wind angle, 0 to 359 degrees; the unit is R for relative or T for theoretical.
+    [range(0, 360)] make_number_field!(wind_angle, f32, 0, 1);
+    /// Wind speed; the unit is K for km/h, M for m/s, N for knots or S for statute miles per hour.
     make_number_field!(wind_speed, f32, 2, 3);
-    make_char_field!(status, 4);
-}
+    /// Status: A = data valid, V = data not valid.
+    [required] make_char_field!(status, 4);
+});
 
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OmegaData {
     name : String,
     first : f32,
     second : f32,
 }
 
-impl OlnData {
+#[cfg(feature = "alloc")]
+impl OmegaData {
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn first(&self) -> f32 {
+        self.first
+    }
+
+    pub fn second(&self) -> f32 {
+        self.second
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FieldGroup for OmegaData {
+    const WIDTH: usize = 3;
+
+    fn read<S: FieldSource + ?Sized>(base: &S, index: usize) -> Option<Self> {
+        Some(OmegaData {
+            name: base.get::<String>(index)?,
+            first: base.get::<f32>(index + 1)?,
+            second: base.get::<f32>(index + 2)?,
+        })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn write(&self, fields: &mut Vec<String>, index: usize) {
+        serialization::set(fields, index, self.name.clone());
+        serialization::set(fields, index + 1, self.first.to_string());
+        serialization::set(fields, index + 2, self.second.to_string());
+    }
+}
+
+make_fields!(OlnData {
+    /// Omega pair 1 name.
     make_string_field!(omega1_name, 0);
+    /// Omega pair 1 first line of position.
     make_number_field!(omega1_first, f32, 1);
+    /// Omega pair 1 second line of position.
     make_number_field!(omega1_second, f32, 2);
+    /// Omega pair 2 name.
     make_string_field!(omega2_name, 3);
+    /// Omega pair 2 first line of position.
     make_number_field!(omega2_first, f32, 4);
+    /// Omega pair 2 second line of position.
     make_number_field!(omega2_second, f32, 5);
+    /// Omega pair 3 name.
     make_string_field!(omega3_name, 6);
+    /// Omega pair 3 first line of position.
     make_number_field!(omega3_first, f32, 7);
+    /// Omega pair 3 second line of position.
     make_number_field!(omega3_second, f32, 8);
-
-    pub fn omegas(&self) -> Option<Vec<OmegaData>> {
-        let mut returned_vec = Vec::new();
-        for i in (0..self.base.nfields()).step_by(3) {
-            if let Some(name) = self.base.get::<String>(i) {
-                if let Some(first) = self.base.get::<f32>(i+1) {
-                    if let Some(second) = self.base.get::<f32>(i+2) {
-                        returned_vec.push(OmegaData {
-                            name,
-                            first,
-                            second,
-                        });
-                    }
-                }
-            }
-        };
-
-        if returned_vec.is_empty() {
-            None
-        } else {
-            Some(returned_vec)
-        }
-    }
-}
-
-impl OsdData {
-    make_number_field!(heading_true, f32, 0);
+    /// The Omega pairs: name and the two lines of position.
+    make_group_field!(omegas, OmegaData, 0);
+});
+
+make_fields!(OsdData {
+    /// Heading, true, in degrees.
+    [range(0, 360)] make_number_field!(heading_true, f32, 0);
+    /// Heading status: A = data valid, V = data not valid.
     make_char_field!(heading_true_value, 1);
-    make_number_field!(course_true, f32, 2);
+    /// Vessel course, true, in degrees.
+    [range(0, 360)] make_number_field!(course_true, f32, 2);
+    /// Course reference: B = bottom tracking log, M = manually entered, W = water referenced, R = radar tracking, P = positioning system.
     make_char_field!(course_reference, 3);
+    /// Vessel speed; the unit is shared with the drift speed: K = km/h, N = knots, S = statute miles per hour.
     make_number_field!(speed, f32, 4, 8);
+    /// Speed reference, as for the course.
     make_char_field!(speed_reference, 5);
+    /// Vessel set, true, in degrees.
     make_number_field!(set_true, f32, 6);
+    /// Vessel drift speed; the unit is shared with the vessel speed.
     make_number_field!(drift, f32, 7, 8);
-}
+});
 
-impl R00Data {
-    pub fn waypoints(&self) -> Option<Vec<String>> {
-        let mut returned_vec = Vec::new();
-        for i in 0..self.nfields() {
-            if let Some(w) = self.base.get::<String>(i) {
-                returned_vec.push(w);
-            }
-        };
-        if returned_vec.is_empty() {
-            None
-        } else {
-            Some(returned_vec)
-        }
-    }
-}
+make_fields!(R00Data {
+    /// Waypoint IDs in the active route.
+    make_group_field!(waypoints, String, 0);
+});
 
-impl RlmData {
+make_fields!(RlmData {
+    /// Beacon ID.
     make_string_field!(beacon_id, 0);
+    /// UTC time of reception of the return link message.
     make_time_field!(time, 1);
+    /// Message code.
     make_hex_field!(message_code, u8, 2);
+    /// Message body.
     make_string_field!(message_body, 3);
-}
+});
 
-impl RmbData {
-    make_char_field!(status, 0);
+make_fields!(RmbData {
+    /// Status: A = data valid, V = navigation receiver warning.
+    [required] make_char_field!(status, 0);
+    /// Cross-track error, in nautical miles.
     make_number_field!(xte, f32, 1);
+    /// Direction to steer: L or R.
     make_char_field!(direction_to_steer, 2);
+    /// Origin waypoint ID.
     make_string_field!(origin_waypoint_id, 3);
+    /// Destination waypoint ID.
     make_string_field!(destination_waypoint_id, 4);
-    make_number_field!(destination_latitude, f32, 5, 6);
-    make_number_field!(destination_longitude, f32, 7, 8);
+    /// Destination latitude in ddmm.mmmm; the unit is the hemisphere, N or S.
+    [range(0, 9000)] make_number_field!(destination_latitude, f32, 5, 6);
+    /// Destination longitude in dddmm.mmmm; the unit is the hemisphere, E or W.
+    [range(0, 18000)] make_number_field!(destination_longitude, f32, 7, 8);
+    /// Range to the destination, in nautical miles.
     make_number_field!(destination_range, f32, 9);
-    make_number_field!(destination_bearing, f32, 10);
+    /// Bearing to the destination, true, in degrees.
+    [range(0, 360)] make_number_field!(destination_bearing, f32, 10);
+    /// Velocity towards the destination, in knots.
     make_number_field!(vmg_knots, f32, 11);
+    /// Arrival status: A = arrival circle entered or perpendicular passed, V = not arrived.
     make_char_field!(arrival_status, 12);
+    /// Mode indicator: A = autonomous, D = differential, E = estimated, M = manual, S = simulator, N = not valid.
     make_number_field!(mode, u8, 13);
-}
+});
 
-impl RmcData {
+make_fields!(RmcData {
+    /// UTC time of the fix.
     make_time_field!(time, 0);
-    make_char_field!(status, 1);
-    make_number_field!(latitude, f32, 2, 3);
-    make_number_field!(longitude, f32, 4, 5);
+    /// Status: A = data valid, V = navigation receiver warning.
+    [required] make_char_field!(status, 1);
+    /// Latitude in ddmm.mmmm; the unit is the hemisphere, N or S.
+    [range(0, 9000)] make_number_field!(latitude, f32, 2, 3);
+    /// Longitude in dddmm.mmmm; the unit is the hemisphere, E or W.
+    [range(0, 18000)] make_number_field!(longitude, f32, 4, 5);
+    /// Speed over ground, in knots.
     make_number_field!(sog_knots, f32, 6);
-    make_number_field!(track_made_good_true, f32, 7);
+    /// Course over ground, true, in degrees.
+    [range(0, 360)] make_number_field!(track_made_good_true, f32, 7);
+    /// Date of the fix.
     make_date_field!(date, 8);
-    make_number_field!(variation, f32, 9, 10);
-    make_number_field!(mode, u16, 11);
+    /// Magnetic variation in degrees; the unit is E or W.
+    [range(0, 180)] make_number_field!(variation, f32, 9, 10);
+    /// Mode indicator: A = autonomous, D = differential, E = estimated, M = manual, S = simulator, N = not valid.
+    make_char_field!(mode, 11);
+    /// Navigational status, NMEA 4.10 and later: S = safe, C = caution, U = unsafe, V = not valid.
     make_char_field!(nav_status, 12);
-}
+});
 
-impl RotData {
+make_fields!(RotData {
+    /// Rate of turn in degrees per minute; negative means turning to port.
     make_number_field!(rate, f32, 0);
-    make_char_field!(valid, 1);
-}
-
-impl RpmData {
-    make_char_field!(source, 0);
-    make_number_field!(source_number, u8, 1);
+    /// Status: A = data valid, V = data not valid.
+    [required] make_char_field!(valid, 1);
+});
+
+make_fields!(RpmData {
+    /// Source: S = shaft, E = engine.
+    [required] make_char_field!(source, 0);
+    /// Engine or shaft number, numbered from the centreline; odd is starboard, even is port, 0 is single or on the centreline.
+    [required] make_number_field!(source_number, u8, 1);
+    /// Revolutions per minute; negative means turning in reverse.
     make_number_field!(rpms, f32, 2);
+    /// Propeller pitch, in percent of maximum; negative means astern.
     make_number_field!(propeller_pitch, f32, 3);
-    make_char_field!(status, 4);
-}
-
-impl RsaData {
-    make_number_field!(angle, f32, 0); // Synonym for starboard rudder.
-    make_number_field!(starboard_angle, f32, 0);
-    make_char_field!(status, 1);        // Synonym for starboard rudder
-    make_char_field!(starboard_status, 1);
-    make_number_field!(port_angle, f32, 2);
-    make_char_field!(port_status, 2);
-}
-
-impl RsdData {
+    /// Status: A = data valid, V = data not valid.
+    [required] make_char_field!(status, 4);
+});
+
+make_fields!(RsaData {
+    /// Same as starboard_angle, for vessels with a single rudder.
+    [alias, range(-90, 90)] make_number_field!(angle, f32, 0);
+    /// Starboard, or single, rudder angle in degrees; negative means bow turns to port.
+    [range(-90, 90)] make_number_field!(starboard_angle, f32, 0);
+    /// Same as starboard_status.
+    [alias] make_char_field!(status, 1);
+    /// Starboard rudder status: A = data valid, V = data not valid.
+    [required] make_char_field!(starboard_status, 1);
+    /// Port rudder angle in degrees; negative means bow turns to port.
+    [range(-90, 90)] make_number_field!(port_angle, f32, 2);
+    /// Port rudder status: A = data valid, V = data not valid.
+    make_char_field!(port_status, 3);
+});
+
+make_fields!(RsdData {
+    /// Origin 1 range from own ship.
     make_number_field!(origin_1_range, f32, 0);
-    make_number_field!(origin_1_bearing, f32, 1);
+    /// Origin 1 bearing, in degrees from 0.
+    [range(0, 360)] make_number_field!(origin_1_bearing, f32, 1);
+    /// Variable range marker 1 range.
     make_number_field!(vrm_1, f32, 2);
-    make_number_field!(bearing_line_1, f32, 3);
+    /// Bearing line 1, in degrees from 0.
+    [range(0, 360)] make_number_field!(bearing_line_1, f32, 3);
+    /// Origin 2 range from own ship.
     make_number_field!(origin_2_range, f32, 4);
-    make_number_field!(origin_2_bearing, f32, 5);
+    /// Origin 2 bearing, in degrees from 0.
+    [range(0, 360)] make_number_field!(origin_2_bearing, f32, 5);
+    /// Variable range marker 2 range.
     make_number_field!(vrm_3, f32, 6);
-    make_number_field!(bearing_line_2, f32, 7);
+    /// Bearing line 2, in degrees from 0.
+    [range(0, 360)] make_number_field!(bearing_line_2, f32, 7);
+    /// Cursor range from own ship.
     make_number_field!(cursor_range, f32, 8);
-    make_number_field!(cursor_bearing, f32, 9);
+    /// Cursor bearing, in degrees clockwise from 0.
+    [range(0, 360)] make_number_field!(cursor_bearing, f32, 9);
+    /// Range scale in use; the unit is K for km, N for nautical miles or S for statute miles.
     make_number_field!(range_scale, f32, 10, 11);
+    /// Display rotation: C = course up, H = head up, N = north up.
     make_char_field!(display_rotation, 12);
-}
-
-impl RteData {
-    make_number_field!(total_sentence_count, u8, 0);
-    make_number_field!(sentence_number, u8, 1);
-    make_char_field!(sentence_mode, 2);
+});
+
+make_fields!(RteData {
+    /// Total number of sentences needed for the whole route.
+    [required] make_number_field!(total_sentence_count, u8, 0);
+    /// Number of this sentence.
+    [required] make_number_field!(sentence_number, u8, 1);
+    /// Sentence mode: c = complete route, w = working route, the first waypoint being the previous one.
+    [required] make_char_field!(sentence_mode, 2);
+    /// Route name or number.
     make_string_field!(route_name, 3);
-
-    pub fn waypoints(&self) -> Option<Vec<String>> {
-        let mut returned_vec = Vec::new();
-        for i in 4..self.nfields() {
-            if let Some(w) = self.base.get::<String>(i) {
-                returned_vec.push(w)
-            }
-        };
-        if returned_vec.is_empty() {
-            None
-        } else {
-            Some(returned_vec)
-        }
-    }
-}
-
-impl Sf1Data {
-    make_number_field!(total_sentence_count, u8, 0);
-    make_number_field!(sentence_number, u8, 1);
-    pub fn frequencies (&self) -> Option<Vec<(f32, char)>> {
-        let mut returned_vec = Vec::new();
-        for i in (2..self.nfields()).step_by(2) {
-            if let Some(f) = self.base.get::<f32>(i) {
-                if let Some(mode) = self.base.get::<char>(i+1) {
-                    returned_vec.push((f, mode));
-                }
-            }
-        };
-        if returned_vec.is_empty() {
-            None
-        } else {
-            Some(returned_vec)
-        }
-    }
-}
-
-impl StnData {
-    make_number_field!(talker_id, u8, 0);
-}
-
-impl TdsData {
+    /// Waypoint IDs, in the order they are to be sailed.
+    make_group_field!(waypoints, String, 4);
+});
+
+make_fields!(Sf1Data {
+    /// Total number of sentences.
+    [required] make_number_field!(total_sentence_count, u8, 0);
+    /// Number of this sentence.
+    [required] make_number_field!(sentence_number, u8, 1);
+    /// Pairs of frequency and mode of operation.
+    make_group_field!(frequencies, (f32, char), 2);
+});
+
+make_fields!(SsdData {
+    /// Call sign, up to 7 characters.
+    make_string_field!(call_sign, 0);
+    /// Name of the vessel, up to 20 characters.
+    make_string_field!(name, 1);
+    /// Distance from the bow to the position reference point in metres.
+    [range(0, 511)] make_number_field!(bow_distance, u16, 2);
+    /// Distance from the stern to the position reference point in metres.
+    [range(0, 511)] make_number_field!(stern_distance, u16, 3);
+    /// Distance from the port beam to the position reference point in metres.
+    [range(0, 63)] make_number_field!(port_distance, u8, 4);
+    /// Distance from the starboard beam to the position reference point in metres.
+    [range(0, 63)] make_number_field!(starboard_distance, u8, 5);
+    /// DTE indicator flag: 0 = keyboard and display available, 1 = not available.
+    [range(0, 1)] make_number_field!(dte, u8, 6);
+    /// Source identifier, e.g. AI for the transponder's internal reference point.
+    make_string_field!(source_identifier, 7);
+});
+
+make_fields!(StnData {
+    /// Talker ID number, 00 to 99.
+    [required] make_number_field!(talker_id, u8, 0);
+});
+
+make_fields!(TdsData {
+    /// Distance between the trawl doors; the unit is M for metres.
     make_number_field!(distance_between_doors, f32, 0, 1);
-}
+});
 
-impl TfiData {
+make_fields!(TfiData {
+    /// Catch sensor 1: 0 = no catch, 1 = catch, 2 = no answer.
     make_char_field!(sensor_1, 0);
-    make_char_field!(sensor_2, 0);
-    make_char_field!(sensor_3, 0);
-}
-
-impl TlbData {
-    pub fn targets(&self) -> Option<Vec<(u8, String)>> {
-        let mut returned_vec = Vec::new();
-        for i in (0..self.nfields()).step_by(2) {
-            if let Some(n) = self.base.get::<u8>(i) {
-                if let Some(l) = self.base.get::<String>(i+1) {
-                    returned_vec.push((n, l));
-                }
-            }
-        };
-
-        if returned_vec.is_empty() { None }
-        else {Some(returned_vec) }
-    }
-}
-
-impl TllData {
-    make_number_field!(target_number, u8, 0);
-    make_number_field!(latitude, f32, 1, 2);
-    make_number_field!(longitude, f32, 3, 4);
+    /// Catch sensor 2: 0 = no catch, 1 = catch, 2 = no answer.
+    make_char_field!(sensor_2, 1);
+    /// Catch sensor 3: 0 = no catch, 1 = catch, 2 = no answer.
+    make_char_field!(sensor_3, 2);
+});
+
+make_fields!(TlbData {
+    /// Pairs of target number and the label assigned to it.
+    make_group_field!(targets, (u8, String), 0);
+});
+
+make_fields!(TllData {
+    /// Target number, 00 to 99.
+    [required] make_number_field!(target_number, u8, 0);
+    /// Target latitude in ddmm.mmmm; the unit is the hemisphere, N or S.
+    [range(0, 9000)] make_number_field!(latitude, f32, 1, 2);
+    /// Target longitude in dddmm.mmmm; the unit is the hemisphere, E or W.
+    [range(0, 18000)] make_number_field!(longitude, f32, 3, 4);
+    /// Target name.
     make_string_field!(name, 5);
+    /// UTC time of the data.
     make_time_field!(time, 6);
+    /// Target status: L = lost, Q = being acquired, T = tracked.
     make_char_field!(status, 7);
+    /// Reference target: R, or empty if this is not the reference target.
     make_char_field!(reference, 8);
-}
+});
 
-impl TpcData {
+make_fields!(TpcData {
+    /// Horizontal distance from the centreline; the unit is M for metres.
     make_number_field!(horizontal_distance_from_centerline, f32, 0, 1);
+    /// Horizontal distance from the transducer, forward or aft; the unit is M.
     make_number_field!(horizontal_distance_from_transducer, f32, 2, 3);
+    /// Depth below the surface; the unit is M.
     make_number_field!(depth, f32, 4, 5);
-}
+});
 
-impl TprData {
+make_fields!(TprData {
+    /// Horizontal range relative to the target; the unit is M for metres.
     make_number_field!(horizontal_range, f32, 0, 1);
-    make_number_field!(bearing_to_target, u16, 2);
+    /// Bearing to the target, relative to the vessel's heading, in degrees.
+    [range(0, 360)] make_number_field!(bearing_to_target, u16, 2);
+    /// Separator, always P.
     make_char_field!(separator, 3);
+    /// Depth of the trawl below the surface; the unit is M.
     make_number_field!(depth, f32, 4, 5);
-}
+});
 
-impl TptData {
+make_fields!(TptData {
+    /// Horizontal range relative to the target; the unit is M for metres.
     make_number_field!(horizontal_range, f32, 0, 1);
-    make_number_field!(bearing_true, u16, 2);
+    /// Bearing to the target, true, in degrees.
+    [range(0, 360)] make_number_field!(bearing_true, u16, 2);
+    /// Separator, always P.
     make_char_field!(separator, 3);
+    /// Depth of the trawl below the surface; the unit is M.
     make_number_field!(depth, f32, 4, 5);
-}
+});
 
-impl TrfData {
+make_fields!(TrfData {
+    /// UTC time of the fix.
     make_time_field!(time, 0);
+    /// Date of the fix.
     make_date_field!(date, 1);
-    make_number_field!(latitude, f32, 2, 3);
-    make_number_field!(longitude, f32, 4, 5);
-    make_number_field!(elevation_angle, f32, 6);
+    /// Latitude in ddmm.mmmm; the unit is the hemisphere, N or S.
+    [range(0, 9000)] make_number_field!(latitude, f32, 2, 3);
+    /// Longitude in dddmm.mmmm; the unit is the hemisphere, E or W.
+    [range(0, 18000)] make_number_field!(longitude, f32, 4, 5);
+    /// Elevation angle, in degrees.
+    [range(-90, 90)] make_number_field!(elevation_angle, f32, 6);
+    /// Number of iterations.
     make_number_field!(iteration_count, u8, 7);
+    /// Number of Doppler intervals.
     make_number_field!(dopper_count, u8, 8);
+    /// Update distance, in nautical miles.
     make_number_field!(update_distance, f32, 9);
+    /// Satellite ID.
     make_number_field!(satellite_id, u16, 10);
-    make_char_field!(data_validity, 11);
-}
-
-impl TtmData {
-    make_number_field!(target_number, u8, 0);
+    /// Data validity: A = valid, V = not valid.
+    [required] make_char_field!(data_validity, 11);
+});
+
+make_fields!(TtmData {
+    /// Target number, 00 to 99.
+    [required] make_number_field!(target_number, u8, 0);
+    /// Target distance from own ship; the unit is shared with the speed: K, N or S.
     make_number_field!(target_distance, f32, 1, 9);
-    make_number_field!(target_bearing, f32, 2, 3);
+    /// Bearing from own ship; the unit is T for true or R for relative.
+    [range(0, 360)] make_number_field!(target_bearing, f32, 2, 3);
+    /// Target speed; the unit is shared with the distance.
     make_number_field!(target_speed, f32, 4, 9);
-    make_number_field!(target_course, f32, 5, 6);
+    /// Target course; the unit is T for true or R for relative.
+    [range(0, 360)] make_number_field!(target_course, f32, 5, 6);
+    /// Distance of the closest point of approach.
     make_number_field!(cpa, f32, 7);
+    /// Time to the closest point of approach, in minutes; negative means it has passed.
     make_number_field!(tpa, f32, 8);
+    /// Target name.
     make_string_field!(name, 10);
+    /// Target status: L = lost, Q = being acquired, T = tracked.
     make_char_field!(status, 11);
+    /// Reference target: R, or empty if this is not the reference target.
     make_char_field!(reference, 12);
+    /// UTC time of the data.
     make_time_field!(time, 13);
+    /// Type of acquisition: A = automatic, M = manual, R = reported.
     make_char_field!(report_type, 14);
+});
+
+make_fields!(TxtData {
+    /// Total number of sentences in the message, 1 to 99.
+    [required] make_number_field!(total, u8, 0);
+    /// Number of this sentence, 1 to 99.
+    [required] make_number_field!(number, u8, 1);
+    /// Text identifier, 1 to 99; the sentences of one message share it.
+    [required] make_number_field!(text_identifier, u8, 2);
+    /// Text, up to 61 characters per sentence, e.g. ANTENNA OK.
+    make_string_field!(text, 3);
+});
+
+// A message put back together from its TXT sentences.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextMessage {
+    pub talker: String,
+    pub identifier: u8,
+    pub text: String,
+}
+
+#[cfg(feature = "alloc")]
+struct PartialText {
+    total: u8,
+    next: u8,
+    text: String,
+}
+
+// Joins multi-sentence TXT messages, keyed by talker and text identifier.  A sentence that
+// arrives out of order abandons the message it belongs to.
+#[cfg(feature = "alloc")]
+#[derive(Default)]
+pub struct TxtAssembler {
+    partial: BTreeMap<(String, u8), PartialText>,
+}
+
+#[cfg(feature = "alloc")]
+impl TxtAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push<B: FieldSource>(&mut self, txt: &TxtData<B>) -> Option<TextMessage> {
+        let (total, number, identifier) = (txt.total()?, txt.number()?, txt.text_identifier()?);
+        // Both run from 1 to 99.
+        if total == 0 || total > 99 || number == 0 || number > total {
+            return None;
+        }
+        let talker = txt.base.sender_str().trim_start_matches(['$', '!']).to_string();
+        let text = txt.text().unwrap_or_default();
+        let key = (talker, identifier);
+        if number == 1 {
+            self.partial.insert(key.clone(), PartialText { total, next: 1, text: String::new() });
+        }
+        let partial = self.partial.get_mut(&key)?;
+        if partial.next != number || partial.total != total {
+            self.partial.remove(&key);
+            return None;
+        }
+        partial.text.push_str(&text);
+        partial.next = number + 1;
+        if number < total {
+            return None;
+        }
+        let partial = self.partial.remove(&key)?;
+        Some(TextMessage { talker: key.0, identifier, text: partial.text })
+    }
 }
 
-impl VbwData {
+make_fields!(VbwData {
+    /// Longitudinal water speed in knots; negative means astern.
     make_number_field!(longitudinal_water_speed, f32, 0);
+    /// Transverse water speed in knots; negative means port.
     make_number_field!(transverse_water_speed, f32, 1);
-    make_char_field!(status_water_speed, 2);
+    /// Water speed status: A = data valid, V = data not valid.
+    [required] make_char_field!(status_water_speed, 2);
+    /// Longitudinal ground speed in knots; negative means astern.
     make_number_field!(longitudinal_ground_speed, f32, 3);
+    /// Transverse ground speed in knots; negative means port.
     make_number_field!(transverse_ground_speed, f32, 4);
-    make_char_field!(status_ground_speed, 5);
+    /// Ground speed status: A = data valid, V = data not valid.
+    [required] make_char_field!(status_ground_speed, 5);
+    /// Stern transverse water speed in knots.
     make_number_field!(stern_traverse_water_speed_knots,f32, 6);
+    /// Stern transverse water speed status: A = data valid, V = data not valid.
     make_char_field!(status_stern_traverse_water_speed, 7);
+    /// Stern transverse ground speed in knots.
     make_number_field!(stern_traverse_ground_speed_knots,f32, 8);
+    /// Stern transverse ground speed status: A = data valid, V = data not valid.
     make_char_field!(status_stern_traverse_ground_speed, 9);
-}
-
-impl VdrData {
-    make_number_field!(direction_true, f32, 0, 1);
-    make_number_field!(direction_magnetic, f32, 2, 3);
+});
+
+make_fields!(VdrData {
+    /// Direction of the current, true; the unit is T.
+    [range(0, 360)] make_number_field!(direction_true, f32, 0, 1);
+    /// Direction of the current, magnetic; the unit is M.
+    [range(0, 360)] make_number_field!(direction_magnetic, f32, 2, 3);
+    /// Speed of the current; the unit is N for knots.
     make_number_field!(current_speed, f32, 4, 5);
-}
-
-impl VhwData {
-    make_number_field!(heading_true, f32, 0, 1);
-    make_number_field!(heading_magnetic, f32, 2, 3);
+});
+
+make_fields!(VhwData {
+    /// Heading, true; the unit is T.
+    [range(0, 360)] make_number_field!(heading_true, f32, 0, 1);
+    /// Heading, magnetic; the unit is M.
+    [range(0, 360)] make_number_field!(heading_magnetic, f32, 2, 3);
+    /// Speed through the water in knots; the unit is N.
     make_number_field!(stw_knots, f32, 4, 5);
+    /// Speed through the water in km/h; the unit is K.
     make_number_field!(stw_kph, f32, 6, 7);
-}
+});
 
-impl VlwData {
+make_fields!(VlwData {
+    /// Total cumulative water distance; the unit is N for nautical miles.
     make_number_field!(water_distance_total, f32, 0, 1);
+    /// Water distance since reset; the unit is N.
     make_number_field!(water_distance_since_reset, f32, 2, 3);
+    /// Total cumulative ground distance, NMEA 3.0 and later; the unit is N.
     make_number_field!(ground_distance_total, f32, 4, 5);
+    /// Ground distance since reset, NMEA 3.0 and later; the unit is N.
     make_number_field!(ground_distance_since_reset, f32, 6, 7);
-}
+});
 
-impl VpwData {
+make_fields!(VpwData {
+    /// Speed parallel to the wind in knots; negative means downwind. The unit is N.
     make_number_field!(speed_knots, f32, 0, 1);
-    make_number_field!(speed_mps, f32, 1, 2);
-}
-
-impl VtgData {
-    make_number_field!(cog_true, f32, 0, 1);
-    make_number_field!(cog_magnetic, f32, 2, 3);
-    make_number_field!(sog_knots, f32, 4, 5);
-    make_number_field!(sog_kph, f32, 6, 7);
+    /// Speed parallel to the wind in metres per second; the unit is M.
+    make_number_field!(speed_mps, f32, 2, 3);
+});
+
+make_fields!(VsdData {
+    /// Type of ship and cargo category, 0 to 255.
+    make_number_field!(ship_and_cargo_type, u8, 0);
+    /// Maximum present static draught in metres.
+    [range(0, 25.5)] make_number_field!(draught, f32, 1);
+    /// Persons on board.
+    [range(0, 8191)] make_number_field!(persons_on_board, u16, 2);
+    /// Destination, up to 20 characters.
+    make_string_field!(destination, 3);
+    /// Estimated UTC time of arrival.
+    make_time_field!(eta_time, 4);
+    /// Estimated day of arrival, 1 to 31; 0 = not available.
+    [range(0, 31)] make_number_field!(eta_day, u8, 5);
+    /// Estimated month of arrival, 1 to 12; 0 = not available.
+    [range(0, 12)] make_number_field!(eta_month, u8, 6);
+    /// Navigational status, 0 to 15, e.g. 0 = under way using engine or 5 = moored.
+    [range(0, 15)] make_number_field!(status, u8, 7);
+    /// Regional application flags, 0 to 15.
+    [range(0, 15)] make_number_field!(regional_application_flags, u8, 8);
+});
+
+make_fields!(VtgData {
+    /// Course over ground, true; the unit is T.
+    [manual] make_number_field!(cog_true, f32, 0, 1);
+    /// Course over ground, magnetic; the unit is M.
+    [manual] make_number_field!(cog_magnetic, f32, 2, 3);
+    /// Speed over ground in knots; the unit is N.
+    [manual] make_number_field!(sog_knots, f32, 4, 5);
+    /// Speed over ground in km/h; the unit is K.
+    [manual] make_number_field!(sog_kph, f32, 6, 7);
+    /// Mode indicator: A = autonomous, D = differential, E = estimated, M = manual, S = simulator, N = not valid.
     make_char_field!(mode, 8);
-}
+});
 
-impl VwrData {
-    make_number_field!(wind_direction, f32, 0, 1);
+make_fields!(VwrData {
+    /// Wind angle relative to the bow, 0 to 180 degrees; the unit is L or R for the side it comes from.
+    [range(0, 360)] make_number_field!(wind_direction, f32, 0, 1);
+    /// Wind speed in knots; the unit is N.
     make_number_field!(speed_knots, f32, 2, 3);
+    /// Wind speed in metres per second; the unit is M.
     make_number_field!(speed_mps, f32, 4, 5);
+    /// Wind speed in km/h; the unit is K.
     make_number_field!(speed_kph, f32, 6, 7);
-}
+});
 
-impl WcvData {
+make_fields!(WatData {
+    /// Message type: S = status of a section, E = status of one detection point, F = fault in the system.
+    [required] make_char_field!(message_type, 0);
+    /// Time of the status.
+    make_time_field!(time, 1);
+    /// Type of water alarm system: WL = water level detection, HB = high water level by bilge system, LB = low water level by bilge system, OT = other.
+    make_string_field!(system_type, 2);
+    /// First location indicator, e.g. the hold or tank.
+    make_string_field!(location, 3);
+    /// Second location indicator.
+    make_number_field!(sublocation, u16, 4);
+    /// Detection point number, or the number of detection points in alarm for a section status.
+    make_number_field!(point_number, u16, 5);
+    /// Alarm condition: N = normal, H = high, J = very high, L = low, K = very low, X = other.
+    make_char_field!(condition, 6);
+    /// Override setting: O = overridden, N = normal.
+    make_char_field!(override_setting, 7);
+    /// Description, e.g. the location of the detection point.
+    make_string_field!(description, 8);
+});
+
+make_fields!(WcvData {
+    /// Velocity towards the waypoint; the unit is N for knots.
     make_number_field!(velocity_knot, f32, 0, 1);
+    /// Waypoint ID.
     make_string_field!(waypoint_id, 2);
+    /// Mode indicator: A = autonomous, D = differential, E = estimated, M = manual, S = simulator, N = not valid.
     make_char_field!(mode, 3);
-}
+});
 
-impl WncData {
+make_fields!(WncData {
+    /// Distance between the waypoints in nautical miles; the unit is N.
     make_number_field!(distance_nm, f32, 0, 1);
+    /// Distance between the waypoints in kilometres; the unit is K.
     make_number_field!(distance_km, f32, 2, 3);
+    /// TO waypoint ID.
     make_string_field!(destination_waypoint_id, 4);
+    /// FROM waypoint ID.
     make_string_field!(origin_waypoint_id, 5);
-}
-
-impl WplData {
-    make_number_field!(latitude, f32, 0, 1);
-    make_number_field!(longitude, f32, 2, 3);
+});
+
+make_fields!(WplData {
+    /// Waypoint latitude in ddmm.mmmm; the unit is the hemisphere, N or S.
+    [range(0, 9000)] make_number_field!(latitude, f32, 0, 1);
+    /// Waypoint longitude in dddmm.mmmm; the unit is the hemisphere, E or W.
+    [range(0, 18000)] make_number_field!(longitude, f32, 2, 3);
+    /// Waypoint ID.
     make_string_field!(waypoint_id, 4);
-}
+});
 
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransducerData {
     pub transducer_type : char,
     pub data : ValueWithUnit<f32>,
     pub name : String,
 }
 
-impl XdrData {
-    pub fn measurements(&self) -> Option<Vec<TransducerData>> {
-        let mut returned_vec = Vec::new();
-        for i in (0..self.nfields()).step_by(4) {
-            if let Some(transducer_type) = self.base.get::<char>(i) {
-                if let Some(value) = self.base.get::<f32>(i+1) {
-                    if let Some(unit) = self.base.get::<char>(i+2) {
-                        if let Some(name) = self.base.get::<String>(i+3) {
-                            returned_vec.push(TransducerData{
-                                transducer_type,
-                                data: ValueWithUnit {value, unit},
-                                name,
-                            })
-                        }
-                    }
-                }
-            }
-        };
+#[cfg(feature = "alloc")]
+impl FieldGroup for TransducerData {
+    const WIDTH: usize = 4;
+
+    fn read<S: FieldSource + ?Sized>(base: &S, index: usize) -> Option<Self> {
+        Some(TransducerData {
+            transducer_type: base.get::<char>(index)?,
+            data: ValueWithUnit { value: base.get::<f32>(index + 1)?, unit: base.get::<char>(index + 2)? },
+            name: base.get::<String>(index + 3)?,
+        })
+    }
 
-        if returned_vec.is_empty() { None }
-        else { Some(returned_vec) }
+    #[cfg(feature = "alloc")]
+    fn write(&self, fields: &mut Vec<String>, index: usize) {
+        serialization::set(fields, index, self.transducer_type.to_string());
+        serialization::set(fields, index + 1, self.data.value.to_string());
+        serialization::set(fields, index + 2, self.data.unit.to_string());
+        serialization::set(fields, index + 3, self.name.clone());
     }
 }
 
-impl XteData {
-    make_char_field!(status, 0);
-    make_char_field!(loran_status, 1);
+make_fields!(XdrData {
+    /// Measurements: transducer type, value, unit and transducer name.
+    make_group_field!(measurements, TransducerData, 0);
+});
+
+make_fields!(XteData {
+    /// Status: A = data valid, V = LORAN-C blink or SNR warning.
+    [required] make_char_field!(status, 0);
+    /// Status: A = data valid, V = LORAN-C cycle lock warning.
+    [required] make_char_field!(loran_status, 1);
+    /// Magnitude of the cross-track error; the unit is N for nautical miles or K for kilometres.
     make_number_field!(xte, f32, 2, 4);
+    /// Direction to steer: L or R.
     make_char_field!(direction_to_steer, 3);
+    /// Mode indicator: A = autonomous, D = differential, E = estimated, M = manual, S = simulator, N = not valid.
     make_char_field!(mode, 5);
-}
+});
 
-impl XtrData {
+make_fields!(XtrData {
+    /// Magnitude of the dead reckoning cross-track error; the unit is N for nautical miles.
     make_number_field!(xte, f32, 0, 2);
+    /// Direction to steer: L or R.
     make_char_field!(direction_to_steer, 1);
-}
+});
 
-impl ZdaData {
+make_fields!(ZdaData {
+    /// UTC time.
     make_time_field!(time, 0);
+    /// UTC day, 01 to 31.
     make_number_field!(day, u32, 1);
+    /// UTC month, 01 to 12.
     make_number_field!(month, u32, 2);
+    /// UTC year.
     make_number_field!(year, i32, 3);
+    /// Local zone hours, -13 to 13.
     make_number_field!(local_zone_description, i8, 4);
+    /// Local zone minutes, 00 to 59.
     make_number_field!(local_zone_minutes, i8, 5);
+});
+
+// How a device signs the ZDA zone.  The standard gives the zone description, the hours to
+// add to local time to get UTC, so it is negative east of Greenwich; many receivers send
+// the offset from UTC instead, positive to the east.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZoneConvention {
+    #[default]
+    Description,
+    Offset,
+}
+
+#[cfg(feature = "chrono")]
+impl<B: FieldSource> ZdaData<B> {
+    pub fn datetime_utc(&self) -> Option<DateTime<Utc>> {
+        let date = NaiveDate::from_ymd_opt(self.year()?, self.month()?, self.day()?)?;
+        Some(DateTime::<Utc>::from_naive_utc_and_offset(NaiveDateTime::new(date, self.time()?), Utc))
+    }
+
+    // The minutes take the sign of the hours, -00 included, whether or not the device
+    // signed them too.
+    pub fn zone_offset(&self, convention: ZoneConvention) -> Option<FixedOffset> {
+        let hours = self.base.field(4).filter(|f| !f.is_empty())?;
+        let negative = hours.starts_with('-');
+        let seconds = hours.parse::<i32>().ok()?.abs() * 3600 + self.local_zone_minutes().unwrap_or(0).abs() as i32 * 60;
+        let east = match (negative, convention) {
+            (false, ZoneConvention::Offset) | (true, ZoneConvention::Description) => seconds,
+            _ => -seconds,
+        };
+        FixedOffset::east_opt(east)
+    }
+
+    pub fn local_datetime(&self) -> Option<DateTime<FixedOffset>> {
+        self.local_datetime_with(ZoneConvention::Description)
+    }
+
+    pub fn local_datetime_with(&self, convention: ZoneConvention) -> Option<DateTime<FixedOffset>> {
+        Some(self.datetime_utc()?.with_timezone(&self.zone_offset(convention)?))
+    }
+}
+
+make_fields!(ZdlData {
+    /// Time to the point, hhmmss.ss; the hours run to 99.
+    make_string_field!(time_to_point, 0);
+    /// Distance to the point, nautical miles.
+    make_number_field!(distance_to_point, f32, 1);
+    /// Type of point: C = collision, T = turning point, R = reference (general), W = wheelover.
+    make_char_field!(point_type, 2);
+});
+
+impl<B: FieldSource> ZdlData<B> {
+    pub fn duration_to_point(&self) -> Option<core::time::Duration> {
+        let field = self.base.field(0).filter(|f| f.len() >= 6)?;
+        let hours = field.get(0..2)?.parse::<u64>().ok()?;
+        let minutes = field.get(2..4)?.parse::<u64>().ok()?;
+        let seconds = field.get(4..)?.parse::<f64>().ok()?;
+        if minutes > 59 || !(0.0..60.0).contains(&seconds) {
+            return None;
+        }
+        Some(core::time::Duration::from_secs(hours * 3600 + minutes * 60) + core::time::Duration::from_secs_f64(seconds))
+    }
 }
 
-impl ZfoData {
+make_fields!(ZfoData {
+    /// UTC time of the observation.
     make_time_field!(time, 0);
+    /// Elapsed time since leaving the origin waypoint.
     make_time_field!(elapsed_time, 1);
+    /// Origin waypoint ID.
     make_string_field!(origin_waypoint_id, 2);
-}
+});
 
-impl ZtgData {
+make_fields!(ZtgData {
+    /// UTC time of the observation.
     make_time_field!(time, 0);
+    /// Time remaining to the destination waypoint.
     make_time_field!(time_remaining, 1);
+    /// Destination waypoint ID.
     make_string_field!(destination_waypoint_id, 2);
-}
+});
 
-pub enum NmeaSentence {
-    AAM(AamData),
-    ABK(AbkData),
-    ACA(AcaData),
-    ACK(AckData),
-    ACS(AcsData),
-    ADS(AdsData),
-    AIR(AirData),
-    AKD(AkdData),
-    ALA(AlaData),
-    ALM(AlmData),
-    ALR(AlrData),
-    APA(ApaData),
-    APB(ApbData),
-    ASD(AsdData),
-    BEC(BecData),
-    BOD(BodData),
-    BWC(BwcData),
-    BWR(BwrData),
-    BWW(BwwData),
-    CEK(CekData),
-    COP(CopData),
-    CUR(CurData),
-    DBK(DbkData),
-    DBS(DbsData),
-    DBT(DbtData),
-    DCN(DcnData),
-    DCR(DcrData),
-    DDC(DdcData),
-    DOR(DorData),
-    DPT(DptData),
-    DSC(DscData),
-    DSE(DseData),
-    DSI(DsiData),
-    DSR(DsrData),
-    DTM(DtmData),
-    ETL(EtlData),
-    EVE(EveData),
-    FIR(FirData),
-    FSI(FsiData),
-    GBS(GbsData),
-    GGA(GgaData),
-    GLC(GlcData),
-    GLL(GllData),
-    GMP(GmpData),
-    GNS(GnsData),
-    GRS(GrsData),
-    GSA(GsaData),
-    GST(GstData),
-    GSV(GsvData),
-    GTD(GtdData),
-    GXA(GxaData),
-    HDG(HdgData),
-    HDM(HdmData),
-    HDT(HdtData),
-    HFB(HfbData),
-    HMR(HmrData),
-    HMS(HmsData),
-    HSC(HscData),
-    HTC(HtcData),
-    HTD(HtdData),
-    ITS(ItsData),
-    LCD(LcdData),
-    LRF(LrfData),
-    LR1(Lr1Data),
-    LR2(Lr2Data),
-    LR3(Lr3Data),
-    MDA(MdaData),
-    MLA(MlaData),
-    MSK(MskData),
-    MSS(MssData),
-    MTW(MtwData),
-    MWD(MwdData),
-    MWV(MwvData),
-    OLN(OlnData),
-    OSD(OsdData),
-    R00(R00Data),
-    RLM(RlmData),
-    RMA(RmaData),
-    RMB(RmbData),
-    RMC(RmcData),
-    ROT(RotData),
-    RPM(RpmData),
-    RSA(RsaData),
-    RSD(RsdData),
-    RTE(RteData),
-    SF1(Sf1Data),
-    SSD(SsdData),
-    STN(StnData),
-    TDS(TdsData),
-    TFI(TfiData),
-    TLB(TlbData),
-    TLL(TllData),
-    TPC(TpcData),
-    TPR(TprData),
-    TPT(TptData),
-    TRF(TrfData),
-    TTM(TtmData),
-    TUT(TutData),
-    TXT(TxtData),
-    VBW(VbwData),
-    VDR(VdrData),
-    VHW(VhwData),
-    VLW(VlwData),
-    VPW(VpwData),
-    VSD(VsdData),
-    VTG(VtgData),
-    VWR(VwrData),
-    WCV(WcvData),
-    WDC(WdcData),
-    WDR(WdrData),
-    WNC(WncData),
-    WPL(WplData),
-    XDR(XdrData),
-    XTE(XteData),
-    XTR(XtrData),
-    ZDA(ZdaData),
-    ZDL(ZdlData),
-    ZFO(ZfoData),
-    ZTG(ZtgData),
-    ABM(AbmData),
-    BBM(BbmData),
-    VDM(VdmData),
-    VDO(VdoData),
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(serialize = "B: FieldSource", deserialize = "B: From<NmeaBaseSentence>")))]
+pub enum NmeaSentence<B = DefaultBase> {
+    AAM(AamData<B>),
+    ABK(AbkData<B>),
+    ACA(AcaData<B>),
+    ACK(AckData<B>),
+    ACS(AcsData<B>),
+    ADS(AdsData<B>),
+    AIR(AirData<B>),
+    AKD(AkdData<B>),
+    ALA(AlaData<B>),
+    ALM(AlmData<B>),
+    ALR(AlrData<B>),
+    APA(ApaData<B>),
+    APB(ApbData<B>),
+    ASD(AsdData<B>),
+    BEC(BecData<B>),
+    BOD(BodData<B>),
+    BWC(BwcData<B>),
+    BWR(BwrData<B>),
+    BWW(BwwData<B>),
+    CEK(CekData<B>),
+    COP(CopData<B>),
+    CUR(CurData<B>),
+    DBK(DbkData<B>),
+    DBS(DbsData<B>),
+    DBT(DbtData<B>),
+    DCN(DcnData<B>),
+    DCR(DcrData<B>),
+    DDC(DdcData<B>),
+    DOR(DorData<B>),
+    DPT(DptData<B>),
+    DSC(DscData<B>),
+    DSE(DseData<B>),
+    DSI(DsiData<B>),
+    DSR(DsrData<B>),
+    DTM(DtmData<B>),
+    ETL(EtlData<B>),
+    EVE(EveData<B>),
+    FIR(FirData<B>),
+    FSI(FsiData<B>),
+    GBS(GbsData<B>),
+    GGA(GgaData<B>),
+    GLC(GlcData<B>),
+    GLL(GllData<B>),
+    GMP(GmpData<B>),
+    GNS(GnsData<B>),
+    GRS(GrsData<B>),
+    GSA(GsaData<B>),
+    GST(GstData<B>),
+    GSV(GsvData<B>),
+    GTD(GtdData<B>),
+    GXA(GxaData<B>),
+    HDG(HdgData<B>),
+    HDM(HdmData<B>),
+    HDT(HdtData<B>),
+    HFB(HfbData<B>),
+    HMR(HmrData<B>),
+    HMS(HmsData<B>),
+    HSC(HscData<B>),
+    HTC(HtcData<B>),
+    HTD(HtdData<B>),
+    ITS(ItsData<B>),
+    LCD(LcdData<B>),
+    LRF(LrfData<B>),
+    LR1(Lr1Data<B>),
+    LR2(Lr2Data<B>),
+    LR3(Lr3Data<B>),
+    MDA(MdaData<B>),
+    MLA(MlaData<B>),
+    MSK(MskData<B>),
+    MSS(MssData<B>),
+    MTW(MtwData<B>),
+    MWD(MwdData<B>),
+    MWV(MwvData<B>),
+    OLN(OlnData<B>),
+    OSD(OsdData<B>),
+    R00(R00Data<B>),
+    RLM(RlmData<B>),
+    RMA(RmaData<B>),
+    RMB(RmbData<B>),
+    RMC(RmcData<B>),
+    ROT(RotData<B>),
+    RPM(RpmData<B>),
+    RSA(RsaData<B>),
+    RSD(RsdData<B>),
+    RTE(RteData<B>),
+    SF1(Sf1Data<B>),
+    SSD(SsdData<B>),
+    STN(StnData<B>),
+    TDS(TdsData<B>),
+    TFI(TfiData<B>),
+    TLB(TlbData<B>),
+    TLL(TllData<B>),
+    TPC(TpcData<B>),
+    TPR(TprData<B>),
+    TPT(TptData<B>),
+    TRF(TrfData<B>),
+    TTM(TtmData<B>),
+    TUT(TutData<B>),
+    TXT(TxtData<B>),
+    VBW(VbwData<B>),
+    VDR(VdrData<B>),
+    VHW(VhwData<B>),
+    VLW(VlwData<B>),
+    VPW(VpwData<B>),
+    VSD(VsdData<B>),
+    VTG(VtgData<B>),
+    VWR(VwrData<B>),
+    WAT(WatData<B>),
+    WCV(WcvData<B>),
+    WDC(WdcData<B>),
+    WDR(WdrData<B>),
+    WNC(WncData<B>),
+    WPL(WplData<B>),
+    XDR(XdrData<B>),
+    XTE(XteData<B>),
+    XTR(XtrData<B>),
+    ZDA(ZdaData<B>),
+    ZDL(ZdlData<B>),
+    ZFO(ZfoData<B>),
+    ZTG(ZtgData<B>),
+    ABM(AbmData<B>),
+    BBM(BbmData<B>),
+    VDM(VdmData<B>),
+    VDO(VdoData<B>),
+    // Sentences parsed by a parser registered with a SentenceRegistry.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(Box<dyn CustomSentence>),
     ERROR(ErrorData),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MessageType {
+    AAM,
+    ABK,
+    ACA,
+    ACK,
+    ACS,
+    ADS,
+    AIR,
+    AKD,
+    ALA,
+    ALM,
+    ALR,
+    APA,
+    APB,
+    ASD,
+    BEC,
+    BOD,
+    BWC,
+    BWR,
+    BWW,
+    CEK,
+    COP,
+    CUR,
+    DBK,
+    DBS,
+    DBT,
+    DCN,
+    DCR,
+    DDC,
+    DOR,
+    DPT,
+    DSC,
+    DSE,
+    DSI,
+    DSR,
+    DTM,
+    ETL,
+    EVE,
+    FIR,
+    FSI,
+    GBS,
+    GGA,
+    GLC,
+    GLL,
+    GMP,
+    GNS,
+    GRS,
+    GSA,
+    GST,
+    GSV,
+    GTD,
+    GXA,
+    HDG,
+    HDM,
+    HDT,
+    HFB,
+    HMR,
+    HMS,
+    HSC,
+    HTC,
+    HTD,
+    ITS,
+    LCD,
+    LRF,
+    LR1,
+    LR2,
+    LR3,
+    MDA,
+    MLA,
+    MSK,
+    MSS,
+    MTW,
+    MWD,
+    MWV,
+    OLN,
+    OSD,
+    R00,
+    RLM,
+    RMA,
+    RMB,
+    RMC,
+    ROT,
+    RPM,
+    RSA,
+    RSD,
+    RTE,
+    SF1,
+    SSD,
+    STN,
+    TDS,
+    TFI,
+    TLB,
+    TLL,
+    TPC,
+    TPR,
+    TPT,
+    TRF,
+    TTM,
+    TUT,
+    TXT,
+    VBW,
+    VDR,
+    VHW,
+    VLW,
+    VPW,
+    VSD,
+    VTG,
+    VWR,
+    WAT,
+    WCV,
+    WDC,
+    WDR,
+    WNC,
+    WPL,
+    XDR,
+    XTE,
+    XTR,
+    ZDA,
+    ZDL,
+    ZFO,
+    ZTG,
+    ABM,
+    BBM,
+    VDM,
+    VDO,
+    Unknown,
+}
+
+impl MessageType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MessageType::AAM => "AAM",
+            MessageType::ABK => "ABK",
+            MessageType::ACA => "ACA",
+            MessageType::ACK => "ACK",
+            MessageType::ACS => "ACS",
+            MessageType::ADS => "ADS",
+            MessageType::AIR => "AIR",
+            MessageType::AKD => "AKD",
+            MessageType::ALA => "ALA",
+            MessageType::ALM => "ALM",
+            MessageType::ALR => "ALR",
+            MessageType::APA => "APA",
+            MessageType::APB => "APB",
+            MessageType::ASD => "ASD",
+            MessageType::BEC => "BEC",
+            MessageType::BOD => "BOD",
+            MessageType::BWC => "BWC",
+            MessageType::BWR => "BWR",
+            MessageType::BWW => "BWW",
+            MessageType::CEK => "CEK",
+            MessageType::COP => "COP",
+            MessageType::CUR => "CUR",
+            MessageType::DBK => "DBK",
+            MessageType::DBS => "DBS",
+            MessageType::DBT => "DBT",
+            MessageType::DCN => "DCN",
+            MessageType::DCR => "DCR",
+            MessageType::DDC => "DDC",
+            MessageType::DOR => "DOR",
+            MessageType::DPT => "DPT",
+            MessageType::DSC => "DSC",
+            MessageType::DSE => "DSE",
+            MessageType::DSI => "DSI",
+            MessageType::DSR => "DSR",
+            MessageType::DTM => "DTM",
+            MessageType::ETL => "ETL",
+            MessageType::EVE => "EVE",
+            MessageType::FIR => "FIR",
+            MessageType::FSI => "FSI",
+            MessageType::GBS => "GBS",
+            MessageType::GGA => "GGA",
+            MessageType::GLC => "GLC",
+            MessageType::GLL => "GLL",
+            MessageType::GMP => "GMP",
+            MessageType::GNS => "GNS",
+            MessageType::GRS => "GRS",
+            MessageType::GSA => "GSA",
+            MessageType::GST => "GST",
+            MessageType::GSV => "GSV",
+            MessageType::GTD => "GTD",
+            MessageType::GXA => "GXA",
+            MessageType::HDG => "HDG",
+            MessageType::HDM => "HDM",
+            MessageType::HDT => "HDT",
+            MessageType::HFB => "HFB",
+            MessageType::HMR => "HMR",
+            MessageType::HMS => "HMS",
+            MessageType::HSC => "HSC",
+            MessageType::HTC => "HTC",
+            MessageType::HTD => "HTD",
+            MessageType::ITS => "ITS",
+            MessageType::LCD => "LCD",
+            MessageType::LRF => "LRF",
+            MessageType::LR1 => "LR1",
+            MessageType::LR2 => "LR2",
+            MessageType::LR3 => "LR3",
+            MessageType::MDA => "MDA",
+            MessageType::MLA => "MLA",
+            MessageType::MSK => "MSK",
+            MessageType::MSS => "MSS",
+            MessageType::MTW => "MTW",
+            MessageType::MWD => "MWD",
+            MessageType::MWV => "MWV",
+            MessageType::OLN => "OLN",
+            MessageType::OSD => "OSD",
+            MessageType::R00 => "R00",
+            MessageType::RLM => "RLM",
+            MessageType::RMA => "RMA",
+            MessageType::RMB => "RMB",
+            MessageType::RMC => "RMC",
+            MessageType::ROT => "ROT",
+            MessageType::RPM => "RPM",
+            MessageType::RSA => "RSA",
+            MessageType::RSD => "RSD",
+            MessageType::RTE => "RTE",
+            MessageType::SF1 => "SF1",
+            MessageType::SSD => "SSD",
+            MessageType::STN => "STN",
+            MessageType::TDS => "TDS",
+            MessageType::TFI => "TFI",
+            MessageType::TLB => "TLB",
+            MessageType::TLL => "TLL",
+            MessageType::TPC => "TPC",
+            MessageType::TPR => "TPR",
+            MessageType::TPT => "TPT",
+            MessageType::TRF => "TRF",
+            MessageType::TTM => "TTM",
+            MessageType::TUT => "TUT",
+            MessageType::TXT => "TXT",
+            MessageType::VBW => "VBW",
+            MessageType::VDR => "VDR",
+            MessageType::VHW => "VHW",
+            MessageType::VLW => "VLW",
+            MessageType::VPW => "VPW",
+            MessageType::VSD => "VSD",
+            MessageType::VTG => "VTG",
+            MessageType::VWR => "VWR",
+            MessageType::WAT => "WAT",
+            MessageType::WCV => "WCV",
+            MessageType::WDC => "WDC",
+            MessageType::WDR => "WDR",
+            MessageType::WNC => "WNC",
+            MessageType::WPL => "WPL",
+            MessageType::XDR => "XDR",
+            MessageType::XTE => "XTE",
+            MessageType::XTR => "XTR",
+            MessageType::ZDA => "ZDA",
+            MessageType::ZDL => "ZDL",
+            MessageType::ZFO => "ZFO",
+            MessageType::ZTG => "ZTG",
+            MessageType::ABM => "ABM",
+            MessageType::BBM => "BBM",
+            MessageType::VDM => "VDM",
+            MessageType::VDO => "VDO",
+            MessageType::Unknown => "",
+        }
+    }
+}
+
+impl From<&str> for MessageType {
+    fn from(value: &str) -> Self {
+        match value {
+            "AAM" => MessageType::AAM,
+            "ABK" => MessageType::ABK,
+            "ACA" => MessageType::ACA,
+            "ACK" => MessageType::ACK,
+            "ACS" => MessageType::ACS,
+            "ADS" => MessageType::ADS,
+            "AIR" => MessageType::AIR,
+            "AKD" => MessageType::AKD,
+            "ALA" => MessageType::ALA,
+            "ALM" => MessageType::ALM,
+            "ALR" => MessageType::ALR,
+            "APA" => MessageType::APA,
+            "APB" => MessageType::APB,
+            "ASD" => MessageType::ASD,
+            "BEC" => MessageType::BEC,
+            "BOD" => MessageType::BOD,
+            "BWC" => MessageType::BWC,
+            "BWR" => MessageType::BWR,
+            "BWW" => MessageType::BWW,
+            "CEK" => MessageType::CEK,
+            "COP" => MessageType::COP,
+            "CUR" => MessageType::CUR,
+            "DBK" => MessageType::DBK,
+            "DBS" => MessageType::DBS,
+            "DBT" => MessageType::DBT,
+            "DCN" => MessageType::DCN,
+            "DCR" => MessageType::DCR,
+            "DDC" => MessageType::DDC,
+            "DOR" => MessageType::DOR,
+            "DPT" => MessageType::DPT,
+            "DSC" => MessageType::DSC,
+            "DSE" => MessageType::DSE,
+            "DSI" => MessageType::DSI,
+            "DSR" => MessageType::DSR,
+            "DTM" => MessageType::DTM,
+            "ETL" => MessageType::ETL,
+            "EVE" => MessageType::EVE,
+            "FIR" => MessageType::FIR,
+            "FSI" => MessageType::FSI,
+            "GBS" => MessageType::GBS,
+            "GGA" => MessageType::GGA,
+            "GLC" => MessageType::GLC,
+            "GLL" => MessageType::GLL,
+            "GMP" => MessageType::GMP,
+            "GNS" => MessageType::GNS,
+            "GRS" => MessageType::GRS,
+            "GSA" => MessageType::GSA,
+            "GST" => MessageType::GST,
+            "GSV" => MessageType::GSV,
+            "GTD" => MessageType::GTD,
+            "GXA" => MessageType::GXA,
+            "HDG" => MessageType::HDG,
+            "HDM" => MessageType::HDM,
+            "HDT" => MessageType::HDT,
+            "HFB" => MessageType::HFB,
+            "HMR" => MessageType::HMR,
+            "HMS" => MessageType::HMS,
+            "HSC" => MessageType::HSC,
+            "HTC" => MessageType::HTC,
+            "HTD" => MessageType::HTD,
+            "ITS" => MessageType::ITS,
+            "LCD" => MessageType::LCD,
+            "LRF" => MessageType::LRF,
+            "LR1" => MessageType::LR1,
+            "LR2" => MessageType::LR2,
+            "LR3" => MessageType::LR3,
+            "MDA" => MessageType::MDA,
+            "MLA" => MessageType::MLA,
+            "MSK" => MessageType::MSK,
+            "MSS" => MessageType::MSS,
+            "MTW" => MessageType::MTW,
+            "MWD" => MessageType::MWD,
+            "MWV" => MessageType::MWV,
+            "OLN" => MessageType::OLN,
+            "OSD" => MessageType::OSD,
+            "R00" => MessageType::R00,
+            "RLM" => MessageType::RLM,
+            "RMA" => MessageType::RMA,
+            "RMB" => MessageType::RMB,
+            "RMC" => MessageType::RMC,
+            "ROT" => MessageType::ROT,
+            "RPM" => MessageType::RPM,
+            "RSA" => MessageType::RSA,
+            "RSD" => MessageType::RSD,
+            "RTE" => MessageType::RTE,
+            "SF1" => MessageType::SF1,
+            "SSD" => MessageType::SSD,
+            "STN" => MessageType::STN,
+            "TDS" => MessageType::TDS,
+            "TFI" => MessageType::TFI,
+            "TLB" => MessageType::TLB,
+            "TLL" => MessageType::TLL,
+            "TPC" => MessageType::TPC,
+            "TPR" => MessageType::TPR,
+            "TPT" => MessageType::TPT,
+            "TRF" => MessageType::TRF,
+            "TTM" => MessageType::TTM,
+            "TUT" => MessageType::TUT,
+            "TXT" => MessageType::TXT,
+            "VBW" => MessageType::VBW,
+            "VDR" => MessageType::VDR,
+            "VHW" => MessageType::VHW,
+            "VLW" => MessageType::VLW,
+            "VPW" => MessageType::VPW,
+            "VSD" => MessageType::VSD,
+            "VTG" => MessageType::VTG,
+            "VWR" => MessageType::VWR,
+            "WAT" => MessageType::WAT,
+            "WCV" => MessageType::WCV,
+            "WDC" => MessageType::WDC,
+            "WDR" => MessageType::WDR,
+            "WNC" => MessageType::WNC,
+            "WPL" => MessageType::WPL,
+            "XDR" => MessageType::XDR,
+            "XTE" => MessageType::XTE,
+            "XTR" => MessageType::XTR,
+            "ZDA" => MessageType::ZDA,
+            "ZDL" => MessageType::ZDL,
+            "ZFO" => MessageType::ZFO,
+            "ZTG" => MessageType::ZTG,
+            "ABM" => MessageType::ABM,
+            "BBM" => MessageType::BBM,
+            "VDM" => MessageType::VDM,
+            "VDO" => MessageType::VDO,
+            _ => MessageType::Unknown,
+        }
+    }
+}
+
+impl Display for MessageType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl<B: FieldSource> NmeaSentence<B> {
+    fn parts(&self) -> Option<(&B, &'static [FieldInfo])> {
+        match self {
+            AAM(data) => Some((&data.base, AamData::FIELDS)),
+            ABK(data) => Some((&data.base, AbkData::FIELDS)),
+            ACA(data) => Some((&data.base, AcaData::FIELDS)),
+            ACK(data) => Some((&data.base, AckData::FIELDS)),
+            ACS(data) => Some((&data.base, AcsData::FIELDS)),
+            ADS(data) => Some((&data.base, AdsData::FIELDS)),
+            AIR(data) => Some((&data.base, AirData::FIELDS)),
+            AKD(data) => Some((&data.base, AkdData::FIELDS)),
+            ALA(data) => Some((&data.base, AlaData::FIELDS)),
+            ALM(data) => Some((&data.base, AlmData::FIELDS)),
+            ALR(data) => Some((&data.base, AlrData::FIELDS)),
+            APA(data) => Some((&data.base, ApaData::FIELDS)),
+            APB(data) => Some((&data.base, ApbData::FIELDS)),
+            ASD(data) => Some((&data.base, AsdData::FIELDS)),
+            BEC(data) => Some((&data.base, BecData::FIELDS)),
+            BOD(data) => Some((&data.base, BodData::FIELDS)),
+            BWC(data) => Some((&data.base, BwcData::FIELDS)),
+            BWR(data) => Some((&data.base, BwrData::FIELDS)),
+            BWW(data) => Some((&data.base, BwwData::FIELDS)),
+            CEK(data) => Some((&data.base, CekData::FIELDS)),
+            COP(data) => Some((&data.base, CopData::FIELDS)),
+            CUR(data) => Some((&data.base, CurData::FIELDS)),
+            DBK(data) => Some((&data.base, DbkData::FIELDS)),
+            DBS(data) => Some((&data.base, DbsData::FIELDS)),
+            DBT(data) => Some((&data.base, DbtData::FIELDS)),
+            DCN(data) => Some((&data.base, DcnData::FIELDS)),
+            DCR(data) => Some((&data.base, DcrData::FIELDS)),
+            DDC(data) => Some((&data.base, DdcData::FIELDS)),
+            DOR(data) => Some((&data.base, DorData::FIELDS)),
+            DPT(data) => Some((&data.base, DptData::FIELDS)),
+            DSC(data) => Some((&data.base, DscData::FIELDS)),
+            DSE(data) => Some((&data.base, DseData::FIELDS)),
+            DSI(data) => Some((&data.base, DsiData::FIELDS)),
+            DSR(data) => Some((&data.base, DsrData::FIELDS)),
+            DTM(data) => Some((&data.base, DtmData::FIELDS)),
+            ETL(data) => Some((&data.base, EtlData::FIELDS)),
+            EVE(data) => Some((&data.base, EveData::FIELDS)),
+            FIR(data) => Some((&data.base, FirData::FIELDS)),
+            FSI(data) => Some((&data.base, FsiData::FIELDS)),
+            GBS(data) => Some((&data.base, GbsData::FIELDS)),
+            GGA(data) => Some((&data.base, GgaData::FIELDS)),
+            GLC(data) => Some((&data.base, GlcData::FIELDS)),
+            GLL(data) => Some((&data.base, GllData::FIELDS)),
+            GMP(data) => Some((&data.base, GmpData::FIELDS)),
+            GNS(data) => Some((&data.base, GnsData::FIELDS)),
+            GRS(data) => Some((&data.base, GrsData::FIELDS)),
+            GSA(data) => Some((&data.base, GsaData::FIELDS)),
+            GST(data) => Some((&data.base, GstData::FIELDS)),
+            GSV(data) => Some((&data.base, GsvData::FIELDS)),
+            GTD(data) => Some((&data.base, GtdData::FIELDS)),
+            GXA(data) => Some((&data.base, GxaData::FIELDS)),
+            HDG(data) => Some((&data.base, HdgData::FIELDS)),
+            HDM(data) => Some((&data.base, HdmData::FIELDS)),
+            HDT(data) => Some((&data.base, HdtData::FIELDS)),
+            HFB(data) => Some((&data.base, HfbData::FIELDS)),
+            HMR(data) => Some((&data.base, HmrData::FIELDS)),
+            HMS(data) => Some((&data.base, HmsData::FIELDS)),
+            HSC(data) => Some((&data.base, HscData::FIELDS)),
+            HTC(data) => Some((&data.base, HtcData::FIELDS)),
+            HTD(data) => Some((&data.base, HtdData::FIELDS)),
+            ITS(data) => Some((&data.base, ItsData::FIELDS)),
+            LCD(data) => Some((&data.base, LcdData::FIELDS)),
+            LRF(data) => Some((&data.base, LrfData::FIELDS)),
+            LR1(data) => Some((&data.base, Lr1Data::FIELDS)),
+            LR2(data) => Some((&data.base, Lr2Data::FIELDS)),
+            LR3(data) => Some((&data.base, Lr3Data::FIELDS)),
+            MDA(data) => Some((&data.base, MdaData::FIELDS)),
+            MLA(data) => Some((&data.base, MlaData::FIELDS)),
+            MSK(data) => Some((&data.base, MskData::FIELDS)),
+            MSS(data) => Some((&data.base, MssData::FIELDS)),
+            MTW(data) => Some((&data.base, MtwData::FIELDS)),
+            MWD(data) => Some((&data.base, MwdData::FIELDS)),
+            MWV(data) => Some((&data.base, MwvData::FIELDS)),
+            OLN(data) => Some((&data.base, OlnData::FIELDS)),
+            OSD(data) => Some((&data.base, OsdData::FIELDS)),
+            R00(data) => Some((&data.base, R00Data::FIELDS)),
+            RLM(data) => Some((&data.base, RlmData::FIELDS)),
+            RMA(data) => Some((&data.base, RmaData::FIELDS)),
+            RMB(data) => Some((&data.base, RmbData::FIELDS)),
+            RMC(data) => Some((&data.base, RmcData::FIELDS)),
+            ROT(data) => Some((&data.base, RotData::FIELDS)),
+            RPM(data) => Some((&data.base, RpmData::FIELDS)),
+            RSA(data) => Some((&data.base, RsaData::FIELDS)),
+            RSD(data) => Some((&data.base, RsdData::FIELDS)),
+            RTE(data) => Some((&data.base, RteData::FIELDS)),
+            SF1(data) => Some((&data.base, Sf1Data::FIELDS)),
+            SSD(data) => Some((&data.base, SsdData::FIELDS)),
+            STN(data) => Some((&data.base, StnData::FIELDS)),
+            TDS(data) => Some((&data.base, TdsData::FIELDS)),
+            TFI(data) => Some((&data.base, TfiData::FIELDS)),
+            TLB(data) => Some((&data.base, TlbData::FIELDS)),
+            TLL(data) => Some((&data.base, TllData::FIELDS)),
+            TPC(data) => Some((&data.base, TpcData::FIELDS)),
+            TPR(data) => Some((&data.base, TprData::FIELDS)),
+            TPT(data) => Some((&data.base, TptData::FIELDS)),
+            TRF(data) => Some((&data.base, TrfData::FIELDS)),
+            TTM(data) => Some((&data.base, TtmData::FIELDS)),
+            TUT(data) => Some((&data.base, TutData::FIELDS)),
+            TXT(data) => Some((&data.base, TxtData::FIELDS)),
+            VBW(data) => Some((&data.base, VbwData::FIELDS)),
+            VDR(data) => Some((&data.base, VdrData::FIELDS)),
+            VHW(data) => Some((&data.base, VhwData::FIELDS)),
+            VLW(data) => Some((&data.base, VlwData::FIELDS)),
+            VPW(data) => Some((&data.base, VpwData::FIELDS)),
+            VSD(data) => Some((&data.base, VsdData::FIELDS)),
+            VTG(data) => Some((&data.base, VtgData::FIELDS)),
+            VWR(data) => Some((&data.base, VwrData::FIELDS)),
+            WAT(data) => Some((&data.base, WatData::FIELDS)),
+            WCV(data) => Some((&data.base, WcvData::FIELDS)),
+            WDC(data) => Some((&data.base, WdcData::FIELDS)),
+            WDR(data) => Some((&data.base, WdrData::FIELDS)),
+            WNC(data) => Some((&data.base, WncData::FIELDS)),
+            WPL(data) => Some((&data.base, WplData::FIELDS)),
+            XDR(data) => Some((&data.base, XdrData::FIELDS)),
+            XTE(data) => Some((&data.base, XteData::FIELDS)),
+            XTR(data) => Some((&data.base, XtrData::FIELDS)),
+            ZDA(data) => Some((&data.base, ZdaData::FIELDS)),
+            ZDL(data) => Some((&data.base, ZdlData::FIELDS)),
+            ZFO(data) => Some((&data.base, ZfoData::FIELDS)),
+            ZTG(data) => Some((&data.base, ZtgData::FIELDS)),
+            ABM(data) => Some((&data.base, AbmData::FIELDS)),
+            BBM(data) => Some((&data.base, BbmData::FIELDS)),
+            VDM(data) => Some((&data.base, VdmData::FIELDS)),
+            VDO(data) => Some((&data.base, VdoData::FIELDS)),
+            #[cfg(feature = "alloc")]
+            NmeaSentence::Custom(_) => None,
+            NmeaSentence::ERROR(_) => None,
+        }
+    }
+
+    pub fn base(&self) -> Option<&B> {
+        self.parts().map(|(base, _)| base)
+    }
+
+    // The fields of the layout the sentence has, as schema() on the typed sentence gives.
+    fn layout(&self) -> Option<(&B, &'static [FieldInfo])> {
+        let (base, fields) = self.parts()?;
+        Some((base, version::layout_fields(self.message_type(), base, fields)))
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self, NmeaSentence::ERROR(_))
+    }
+
+    pub fn message_type(&self) -> MessageType {
+        match self {
+            AAM(_) => MessageType::AAM,
+            ABK(_) => MessageType::ABK,
+            ACA(_) => MessageType::ACA,
+            ACK(_) => MessageType::ACK,
+            ACS(_) => MessageType::ACS,
+            ADS(_) => MessageType::ADS,
+            AIR(_) => MessageType::AIR,
+            AKD(_) => MessageType::AKD,
+            ALA(_) => MessageType::ALA,
+            ALM(_) => MessageType::ALM,
+            ALR(_) => MessageType::ALR,
+            APA(_) => MessageType::APA,
+            APB(_) => MessageType::APB,
+            ASD(_) => MessageType::ASD,
+            BEC(_) => MessageType::BEC,
+            BOD(_) => MessageType::BOD,
+            BWC(_) => MessageType::BWC,
+            BWR(_) => MessageType::BWR,
+            BWW(_) => MessageType::BWW,
+            CEK(_) => MessageType::CEK,
+            COP(_) => MessageType::COP,
+            CUR(_) => MessageType::CUR,
+            DBK(_) => MessageType::DBK,
+            DBS(_) => MessageType::DBS,
+            DBT(_) => MessageType::DBT,
+            DCN(_) => MessageType::DCN,
+            DCR(_) => MessageType::DCR,
+            DDC(_) => MessageType::DDC,
+            DOR(_) => MessageType::DOR,
+            DPT(_) => MessageType::DPT,
+            DSC(_) => MessageType::DSC,
+            DSE(_) => MessageType::DSE,
+            DSI(_) => MessageType::DSI,
+            DSR(_) => MessageType::DSR,
+            DTM(_) => MessageType::DTM,
+            ETL(_) => MessageType::ETL,
+            EVE(_) => MessageType::EVE,
+            FIR(_) => MessageType::FIR,
+            FSI(_) => MessageType::FSI,
+            GBS(_) => MessageType::GBS,
+            GGA(_) => MessageType::GGA,
+            GLC(_) => MessageType::GLC,
+            GLL(_) => MessageType::GLL,
+            GMP(_) => MessageType::GMP,
+            GNS(_) => MessageType::GNS,
+            GRS(_) => MessageType::GRS,
+            GSA(_) => MessageType::GSA,
+            GST(_) => MessageType::GST,
+            GSV(_) => MessageType::GSV,
+            GTD(_) => MessageType::GTD,
+            GXA(_) => MessageType::GXA,
+            HDG(_) => MessageType::HDG,
+            HDM(_) => MessageType::HDM,
+            HDT(_) => MessageType::HDT,
+            HFB(_) => MessageType::HFB,
+            HMR(_) => MessageType::HMR,
+            HMS(_) => MessageType::HMS,
+            HSC(_) => MessageType::HSC,
+            HTC(_) => MessageType::HTC,
+            HTD(_) => MessageType::HTD,
+            ITS(_) => MessageType::ITS,
+            LCD(_) => MessageType::LCD,
+            LRF(_) => MessageType::LRF,
+            LR1(_) => MessageType::LR1,
+            LR2(_) => MessageType::LR2,
+            LR3(_) => MessageType::LR3,
+            MDA(_) => MessageType::MDA,
+            MLA(_) => MessageType::MLA,
+            MSK(_) => MessageType::MSK,
+            MSS(_) => MessageType::MSS,
+            MTW(_) => MessageType::MTW,
+            MWD(_) => MessageType::MWD,
+            MWV(_) => MessageType::MWV,
+            OLN(_) => MessageType::OLN,
+            OSD(_) => MessageType::OSD,
+            R00(_) => MessageType::R00,
+            RLM(_) => MessageType::RLM,
+            RMA(_) => MessageType::RMA,
+            RMB(_) => MessageType::RMB,
+            RMC(_) => MessageType::RMC,
+            ROT(_) => MessageType::ROT,
+            RPM(_) => MessageType::RPM,
+            RSA(_) => MessageType::RSA,
+            RSD(_) => MessageType::RSD,
+            RTE(_) => MessageType::RTE,
+            SF1(_) => MessageType::SF1,
+            SSD(_) => MessageType::SSD,
+            STN(_) => MessageType::STN,
+            TDS(_) => MessageType::TDS,
+            TFI(_) => MessageType::TFI,
+            TLB(_) => MessageType::TLB,
+            TLL(_) => MessageType::TLL,
+            TPC(_) => MessageType::TPC,
+            TPR(_) => MessageType::TPR,
+            TPT(_) => MessageType::TPT,
+            TRF(_) => MessageType::TRF,
+            TTM(_) => MessageType::TTM,
+            TUT(_) => MessageType::TUT,
+            TXT(_) => MessageType::TXT,
+            VBW(_) => MessageType::VBW,
+            VDR(_) => MessageType::VDR,
+            VHW(_) => MessageType::VHW,
+            VLW(_) => MessageType::VLW,
+            VPW(_) => MessageType::VPW,
+            VSD(_) => MessageType::VSD,
+            VTG(_) => MessageType::VTG,
+            VWR(_) => MessageType::VWR,
+            WAT(_) => MessageType::WAT,
+            WCV(_) => MessageType::WCV,
+            WDC(_) => MessageType::WDC,
+            WDR(_) => MessageType::WDR,
+            WNC(_) => MessageType::WNC,
+            WPL(_) => MessageType::WPL,
+            XDR(_) => MessageType::XDR,
+            XTE(_) => MessageType::XTE,
+            XTR(_) => MessageType::XTR,
+            ZDA(_) => MessageType::ZDA,
+            ZDL(_) => MessageType::ZDL,
+            ZFO(_) => MessageType::ZFO,
+            ZTG(_) => MessageType::ZTG,
+            ABM(_) => MessageType::ABM,
+            BBM(_) => MessageType::BBM,
+            VDM(_) => MessageType::VDM,
+            VDO(_) => MessageType::VDO,
+            #[cfg(feature = "alloc")]
+            NmeaSentence::Custom(custom) => MessageType::from(custom.base().message_type_str()),
+            NmeaSentence::ERROR(_) => MessageType::Unknown,
+        }
+    }
+
+    // The sender without its leading $ or !; empty for errors.
+    pub fn talker(&self) -> &str {
+        let sender = match self {
+            #[cfg(feature = "alloc")]
+            NmeaSentence::Custom(custom) => custom.base().sender_str(),
+            _ => self.base().map_or("", |base| base.sender_str()),
+        };
+        sender.trim_start_matches(['$', '!'])
+    }
+
+    // The line the sentence was parsed from.  Without an allocator, errors do not keep it.
+    pub fn raw(&self) -> &str {
+        match self {
+            #[cfg(feature = "alloc")]
+            NmeaSentence::Custom(custom) => custom.base().original(),
+            #[cfg(feature = "alloc")]
+            NmeaSentence::ERROR(error) => &error.message,
+            _ => self.base().map_or("", |base| base.original()),
+        }
+    }
+
+    // Sentences without accessors yet, and errors, have no fields.
+    pub fn schema(&self) -> &'static [FieldInfo] {
+        match self {
+            #[cfg(feature = "alloc")]
+            NmeaSentence::Custom(custom) => custom.schema(),
+            _ => self.layout().map_or(&[], |(_, fields)| fields),
+        }
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = (&'static str, Value<'_>)> {
+        let fields = self.layout().into_iter().flat_map(|(base, fields)| field_values(base, fields));
+        #[cfg(feature = "alloc")]
+        let fields = fields.chain(match self {
+            NmeaSentence::Custom(custom) => Some(custom.as_ref()),
+            _ => None,
+        }.into_iter().flat_map(|custom| field_values(custom.base(), custom.schema())));
+        fields
+    }
+}
+
+impl<B: FieldSource> Display for NmeaSentence<B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.raw())
+    }
+}
+
+// What an error reports as its base: no sender, message type or fields.
+#[cfg(feature = "alloc")]
+static EMPTY_BASE: NmeaBaseSentence = NmeaBaseSentence {
+    _sender: String::new(),
+    _message_type: String::new(),
+    _fields: Vec::new(),
+    _checksum: 0,
+    _original: String::new(),
+};
+
+#[cfg(feature = "alloc")]
+impl NmeaBaseTrait for NmeaSentence {
+    fn get_base(&self) -> &NmeaBaseSentence {
+        match self {
+            NmeaSentence::Custom(custom) => custom.base(),
+            _ => self.base().unwrap_or(&EMPTY_BASE),
+        }
+    }
+
+    fn talker(&self) -> &str {
+        NmeaSentence::talker(self)
+    }
+
+    fn raw(&self) -> &str {
+        NmeaSentence::raw(self)
+    }
+}
+
+fn dispatch<B: FieldSource>(base: B) -> NmeaSentence<B> {
+    // The message type is copied out so that base can be moved into the variant.
+    let mut message_type = [0u8; 3];
+    if let [a, b, c] = base.message_type_str().as_bytes() {
+        message_type = [*a, *b, *c];
+    }
+    match &message_type {
+        b"AAM" => AAM(AamData { base }),
+        b"ABK" => ABK(AbkData { base }),
+        b"ACA" => ACA(AcaData { base }),
+        b"ACK" => ACK(AckData { base }),
+        b"ACS" => ACS(AcsData { base }),
+        b"ADS" => ADS(AdsData { base }),
+        b"AIR" => AIR(AirData { base }),
+        b"AKD" => AKD(AkdData { base }),
+        b"ALA" => ALA(AlaData { base }),
+        b"ALM" => ALM(AlmData { base }),
+        b"ALR" => ALR(AlrData { base }),
+        b"APA" => APA(ApaData { base }),
+        b"APB" => APB(ApbData { base }),
+        b"ASD" => ASD(AsdData { base }),
+        b"BEC" => BEC(BecData { base }),
+        b"BOD" => BOD(BodData { base }),
+        b"BWC" => BWC(BwcData { base }),
+        b"BWR" => BWR(BwrData { base }),
+        b"BWW" => BWW(BwwData { base }),
+        b"CEK" => CEK(CekData { base }),
+        b"COP" => COP(CopData { base }),
+        b"CUR" => CUR(CurData { base }),
+        b"DBK" => DBK(DbkData { base }),
+        b"DBS" => DBS(DbsData { base }),
+        b"DBT" => DBT(DbtData { base }),
+        b"DCN" => DCN(DcnData { base }),
+        b"DCR" => DCR(DcrData { base }),
+        b"DDC" => DDC(DdcData { base }),
+        b"DOR" => DOR(DorData { base }),
+        b"DPT" => DPT(DptData { base }),
+        b"DSC" => DSC(DscData { base }),
+        b"DSE" => DSE(DseData { base }),
+        b"DSI" => DSI(DsiData { base }),
+        b"DSR" => DSR(DsrData { base }),
+        b"DTM" => DTM(DtmData { base }),
+        b"ETL" => ETL(EtlData { base }),
+        b"EVE" => EVE(EveData { base }),
+        b"FIR" => FIR(FirData { base }),
+        b"FSI" => FSI(FsiData { base }),
+        b"GBS" => GBS(GbsData { base }),
+        b"GGA" => GGA(GgaData { base }),
+        b"GLC" => GLC(GlcData { base }),
+        b"GLL" => GLL(GllData { base }),
+        b"GMP" => GMP(GmpData { base }),
+        b"GNS" => GNS(GnsData { base }),
+        b"GRS" => GRS(GrsData { base }),
+        b"GSA" => GSA(GsaData { base }),
+        b"GST" => GST(GstData { base }),
+        b"GSV" => GSV(GsvData { base }),
+        b"GTD" => GTD(GtdData { base }),
+        b"GXA" => GXA(GxaData { base }),
+        b"HDG" => HDG(HdgData { base }),
+        b"HDM" => HDM(HdmData { base }),
+        b"HDT" => HDT(HdtData { base }),
+        b"HFB" => HFB(HfbData { base }),
+        b"HMR" => HMR(HmrData { base }),
+        b"HMS" => HMS(HmsData { base }),
+        b"HSC" => HSC(HscData { base }),
+        b"HTC" => HTC(HtcData { base }),
+        b"HTD" => HTD(HtdData { base }),
+        b"ITS" => ITS(ItsData { base }),
+        b"LCD" => LCD(LcdData { base }),
+        b"LRF" => LRF(LrfData { base }),
+        b"LR1" => LR1(Lr1Data { base }),
+        b"LR2" => LR2(Lr2Data { base }),
+        b"LR3" => LR3(Lr3Data { base }),
+        b"MDA" => MDA(MdaData { base }),
+        b"MLA" => MLA(MlaData { base }),
+        b"MSK" => MSK(MskData { base }),
+        b"MSS" => MSS(MssData { base }),
+        b"MTW" => MTW(MtwData { base }),
+        b"MWD" => MWD(MwdData { base }),
+        b"MWV" => MWV(MwvData { base }),
+        b"OLN" => OLN(OlnData { base }),
+        b"OSD" => OSD(OsdData { base }),
+        b"R00" => R00(R00Data { base }),
+        b"RLM" => RLM(RlmData { base }),
+        b"RMA" => RMA(RmaData { base }),
+        b"RMB" => RMB(RmbData { base }),
+        b"RMC" => RMC(RmcData { base }),
+        b"ROT" => ROT(RotData { base }),
+        b"RPM" => RPM(RpmData { base }),
+        b"RSA" => RSA(RsaData { base }),
+        b"RSD" => RSD(RsdData { base }),
+        b"RTE" => RTE(RteData { base }),
+        b"SF1" => SF1(Sf1Data { base }),
+        b"SSD" => SSD(SsdData { base }),
+        b"STN" => STN(StnData { base }),
+        b"TDS" => TDS(TdsData { base }),
+        b"TFI" => TFI(TfiData { base }),
+        b"TLB" => TLB(TlbData { base }),
+        b"TLL" => TLL(TllData { base }),
+        b"TPC" => TPC(TpcData { base }),
+        b"TPR" => TPR(TprData { base }),
+        b"TPT" => TPT(TptData { base }),
+        b"TRF" => TRF(TrfData { base }),
+        b"TTM" => TTM(TtmData { base }),
+        b"TUT" => TUT(TutData { base }),
+        b"TXT" => TXT(TxtData { base }),
+        b"VBW" => VBW(VbwData { base }),
+        b"VDR" => VDR(VdrData { base }),
+        b"VHW" => VHW(VhwData { base }),
+        b"VLW" => VLW(VlwData { base }),
+        b"VPW" => VPW(VpwData { base }),
+        b"VSD" => VSD(VsdData { base }),
+        b"VTG" => VTG(VtgData { base }),
+        b"VWR" => VWR(VwrData { base }),
+        b"WAT" => WAT(WatData { base }),
+        b"WCV" => WCV(WcvData { base }),
+        b"WDC" => WDC(WdcData { base }),
+        b"WDR" => WDR(WdrData { base }),
+        b"WNC" => WNC(WncData { base }),
+        b"WPL" => WPL(WplData { base }),
+        b"XDR" => XDR(XdrData { base }),
+        b"XTE" => XTE(XteData { base }),
+        b"XTR" => XTR(XtrData { base }),
+        b"ZDA" => ZDA(ZdaData { base }),
+        b"ZDL" => ZDL(ZdlData { base }),
+        b"ZFO" => ZFO(ZfoData { base }),
+        b"ZTG" => ZTG(ZtgData { base }),
+        b"ABM" => ABM(AbmData { base }),
+        b"BBM" => BBM(BbmData { base }),
+        b"VDM" => VDM(VdmData { base }),
+        b"VDO" => VDO(VdoData { base }),
+        _ => NmeaSentence::ERROR(ErrorData::new("Invalid or unknown message type", base.original().as_bytes())),
+    }
+}
+
+// Sentences an application parses itself.  The base keeps the sentence as it was received,
+// and a schema, if given, makes the fields available through NmeaSentence::fields.
+#[cfg(feature = "alloc")]
+pub trait CustomSentence: core::any::Any + core::fmt::Debug + Send + Sync {
+    fn base(&self) -> &NmeaBaseSentence;
+
+    fn schema(&self) -> &'static [FieldInfo] {
+        &[]
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl dyn CustomSentence {
+    pub fn downcast_ref<T: CustomSentence>(&self) -> Option<&T> {
+        (self as &dyn core::any::Any).downcast_ref()
+    }
+}
+
+#[cfg(feature = "alloc")]
+type SentenceParser = Box<dyn Fn(NmeaBaseSentence) -> Box<dyn CustomSentence> + Send + Sync>;
+
+// Parsers registered by the application, tried before the built-in sentences.  A parser is
+// registered either for a message type, e.g. "ZZZ", which matches any talker, or for a full
+// address, e.g. "PGRMZ", which takes precedence.  The default registry has no parsers and
+// parses exactly as NmeaSentence::from does.
+#[cfg(feature = "alloc")]
+#[derive(Default)]
+pub struct SentenceRegistry {
+    parsers: BTreeMap<String, SentenceParser>,
+}
+
+#[cfg(feature = "alloc")]
+impl SentenceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_parser<F>(mut self, message_type: &str, parser: F) -> Self
+    where
+        F: Fn(NmeaBaseSentence) -> Box<dyn CustomSentence> + Send + Sync + 'static,
+    {
+        self.parsers.insert(message_type.to_string(), Box::new(parser));
+        self
+    }
+
+    pub fn with_sentence<T: CustomSentence + From<NmeaBaseSentence>>(self, message_type: &str) -> Self {
+        self.with_parser(message_type, |base| Box::new(T::from(base)))
+    }
+
+    pub fn parse(&self, sentence: &str) -> NmeaSentence {
+        match NmeaSentenceRef::parse(sentence.as_bytes()) {
+            Ok(base) => self.parse_base(base.into_owned()),
+            Err(error) => NmeaSentence::ERROR(ErrorData::new(error, sentence.as_bytes())),
+        }
+    }
+
+    pub fn parse_with(&self, line: &str, options: &ParseOptions) -> Parsed {
+        let mut warnings = Vec::new();
+        let sentence = match options.read(line, &mut |w| warnings.push(w)) {
+            Ok(base) => self.parse_base(base.into_owned()),
+            Err(error) => NmeaSentence::ERROR(ErrorData::new(error, line.as_bytes())),
+        };
+        let parts = match &sentence {
+            NmeaSentence::Custom(custom) => Some((custom.base(), custom.schema())),
+            _ => sentence.layout(),
+        };
+        if let Some((base, fields)) = parts {
+            options.check(base, fields, &mut |w| warnings.push(w));
+        }
+        Parsed { sentence, warnings }
+    }
+
+    pub fn parse_base(&self, base: NmeaBaseSentence) -> NmeaSentence {
+        if self.parsers.is_empty() {
+            return dispatch(base);
+        }
+        let address = format!("{}{}", base.sender_str().trim_start_matches(['$', '!']), base.message_type_str());
+        match self.parsers.get(&address).or_else(|| self.parsers.get(base.message_type_str())) {
+            Some(parser) => NmeaSentence::Custom(parser(base)),
+            None => dispatch(base),
+        }
+    }
+}
+
+// How forgiving to be of devices that stray from the standard.  The default parses exactly
+// as NmeaSentence::from does; strict() and lenient() are starting points.  Problems that
+// are let through are reported as warnings.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ParseOptions {
+    // Accept sentences that end without *HH.
+    pub allow_missing_checksum: bool,
+    // Keep sentences whose checksum does not match, rather than returning an error.
+    pub keep_bad_checksum: bool,
+    // Reject sentences longer than this, not counting the line ending.
+    pub max_length: Option<usize>,
+    // Strip leading and trailing whitespace, such as a stray \r or padding.
+    pub trim_whitespace: bool,
+    // Check the fields against the sentence's field table, including number ranges.
+    pub validate_fields: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseWarning {
+    Trimmed,
+    MissingChecksum,
+    ChecksumMismatch { expected: u8, found: u8 },
+    Field(FieldError),
+}
+
+impl Display for ParseWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ParseWarning::Trimmed => write!(f, "whitespace was trimmed"),
+            ParseWarning::MissingChecksum => write!(f, "checksum is missing"),
+            ParseWarning::ChecksumMismatch { expected, found } => write!(f, "checksum is {:02X}, expected {:02X}", found, expected),
+            ParseWarning::Field(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+// A sentence together with what was wrong with it.
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct Parsed {
+    pub sentence: NmeaSentence,
+    pub warnings: Vec<ParseWarning>,
+}
+
+#[cfg(feature = "alloc")]
+impl Parsed {
+    pub fn is_clean(&self) -> bool {
+        !self.sentence.is_error() && self.warnings.is_empty()
+    }
+}
+
+impl ParseOptions {
+    // The standard's limit of 82 characters, and field checks.
+    pub fn strict() -> Self {
+        Self { max_length: Some(82), validate_fields: true, ..Self::default() }
+    }
+
+    // Anything that can be split into fields, with warnings for the rest.
+    pub fn lenient() -> Self {
+        Self {
+            allow_missing_checksum: true,
+            keep_bad_checksum: true,
+            trim_whitespace: true,
+            validate_fields: true,
+            ..Self::default()
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn parse(&self, line: &str) -> Parsed {
+        SentenceRegistry::default().parse_with(line, self)
+    }
+
+    // Without an allocator, each warning is passed to warn as it is found.
+    pub fn parse_ref<'a>(&self, line: &'a str, mut warn: impl FnMut(ParseWarning)) -> NmeaSentence<NmeaSentenceRef<'a>> {
+        match self.read(line, &mut warn) {
+            Ok(base) => {
+                let sentence = dispatch(base);
+                if let Some((base, fields)) = sentence.parts() {
+                    self.check(base, fields, &mut warn);
+                }
+                sentence
+            }
+            Err(error) => NmeaSentence::ERROR(ErrorData::new(error, line.as_bytes())),
+        }
+    }
+
+    fn read<'a>(&self, line: &'a str, warn: &mut impl FnMut(ParseWarning)) -> Result<NmeaSentenceRef<'a>, &'static str> {
+        let mut line = line;
+        if self.trim_whitespace && line.trim() != line {
+            line = line.trim();
+            warn(ParseWarning::Trimmed);
+        }
+        if self.max_length.is_some_and(|max| line.len() > max) {
+            return Err("Sentence too long");
+        }
+        let (sentence, checksum) = NmeaSentenceRef::read(line.as_bytes())?;
+        match checksum {
+            Checksum::Valid(_) => {}
+            Checksum::Missing(_) if self.allow_missing_checksum => warn(ParseWarning::MissingChecksum),
+            Checksum::Missing(_) => return Err("Missing checksum"),
+            Checksum::Mismatch { expected, found } if self.keep_bad_checksum => {
+                warn(ParseWarning::ChecksumMismatch { expected, found });
+            }
+            Checksum::Mismatch { .. } => return Err("Checksum mismatch"),
+        }
+        Ok(sentence)
+    }
+
+    // Only the first structural problem is reported, but every field out of range is.
+    // Sentences without a field table are not checked.
+    fn check<B: FieldSource + ?Sized>(&self, base: &B, fields: &'static [FieldInfo], warn: &mut impl FnMut(ParseWarning)) {
+        if !self.validate_fields || fields.is_empty() {
+            return;
+        }
+        if let Err(error) = validate_fields(base, fields) {
+            warn(ParseWarning::Field(error));
+        }
+        out_of_range(base, fields).for_each(|error| warn(ParseWarning::Field(error)));
+    }
+}
+
+#[cfg(feature = "alloc")]
 impl From<&NmeaBaseSentence> for NmeaSentence {
     fn from(value: &NmeaBaseSentence) -> Self {
-        match value._message_type.as_str() {
-            "AAM" => AAM(AamData { base: (*value).clone() }),
-            "ABK" => ABK(AbkData { base: (*value).clone() }),
-            "ACA" => ACA(AcaData { base: (*value).clone() }),
-            "ACK" => ACK(AckData { base: (*value).clone() }),
-            "ACS" => ACS(AcsData { base: (*value).clone() }),
-            "ADS" => ADS(AdsData { base: (*value).clone() }),
-            "AIR" => AIR(AirData { base: (*value).clone() }),
-            "AKD" => AKD(AkdData { base: (*value).clone() }),
-            "ALA" => ALA(AlaData { base: (*value).clone() }),
-            "ALM" => ALM(AlmData { base: (*value).clone() }),
-            "ALR" => ALR(AlrData { base: (*value).clone() }),
-            "APA" => APA(ApaData { base: (*value).clone() }),
-            "APB" => APB(ApbData { base: (*value).clone() }),
-            "ASD" => ASD(AsdData { base: (*value).clone() }),
-            "BEC" => BEC(BecData { base: (*value).clone() }),
-            "BOD" => BOD(BodData { base: (*value).clone() }),
-            "BWC" => BWC(BwcData { base: (*value).clone() }),
-            "BWR" => BWR(BwrData { base: (*value).clone() }),
-            "BWW" => BWW(BwwData { base: (*value).clone() }),
-            "CEK" => CEK(CekData { base: (*value).clone() }),
-            "COP" => COP(CopData { base: (*value).clone() }),
-            "CUR" => CUR(CurData { base: (*value).clone() }),
-            "DBK" => DBK(DbkData { base: (*value).clone() }),
-            "DBS" => DBS(DbsData { base: (*value).clone() }),
-            "DBT" => DBT(DbtData { base: (*value).clone() }),
-            "DCN" => DCN(DcnData { base: (*value).clone() }),
-            "DCR" => DCR(DcrData { base: (*value).clone() }),
-            "DDC" => DDC(DdcData { base: (*value).clone() }),
-            "DOR" => DOR(DorData { base: (*value).clone() }),
-            "DPT" => DPT(DptData { base: (*value).clone() }),
-            "DSC" => DSC(DscData { base: (*value).clone() }),
-            "DSE" => DSE(DseData { base: (*value).clone() }),
-            "DSI" => DSI(DsiData { base: (*value).clone() }),
-            "DSR" => DSR(DsrData { base: (*value).clone() }),
-            "DTM" => DTM(DtmData { base: (*value).clone() }),
-            "ETL" => ETL(EtlData { base: (*value).clone() }),
-            "EVE" => EVE(EveData { base: (*value).clone() }),
-            "FIR" => FIR(FirData { base: (*value).clone() }),
-            "FSI" => FSI(FsiData { base: (*value).clone() }),
-            "GBS" => GBS(GbsData { base: (*value).clone() }),
-            "GGA" => GGA(GgaData { base: (*value).clone() }),
-            "GLC" => GLC(GlcData { base: (*value).clone() }),
-            "GLL" => GLL(GllData { base: (*value).clone() }),
-            "GMP" => GMP(GmpData { base: (*value).clone() }),
-            "GNS" => GNS(GnsData { base: (*value).clone() }),
-            "GRS" => GRS(GrsData { base: (*value).clone() }),
-            "GSA" => GSA(GsaData { base: (*value).clone() }),
-            "GST" => GST(GstData { base: (*value).clone() }),
-            "GSV" => GSV(GsvData { base: (*value).clone() }),
-            "GTD" => GTD(GtdData { base: (*value).clone() }),
-            "GXA" => GXA(GxaData { base: (*value).clone() }),
-            "HDG" => HDG(HdgData { base: (*value).clone() }),
-            "HDM" => HDM(HdmData { base: (*value).clone() }),
-            "HDT" => HDT(HdtData { base: (*value).clone() }),
-            "HFB" => HFB(HfbData { base: (*value).clone() }),
-            "HMR" => HMR(HmrData { base: (*value).clone() }),
-            "HMS" => HMS(HmsData { base: (*value).clone() }),
-            "HSC" => HSC(HscData { base: (*value).clone() }),
-            "HTC" => HTC(HtcData { base: (*value).clone() }),
-            "HTD" => HTD(HtdData { base: (*value).clone() }),
-            "ITS" => ITS(ItsData { base: (*value).clone() }),
-            "LCD" => LCD(LcdData { base: (*value).clone() }),
-            "LRF" => LRF(LrfData { base: (*value).clone() }),
-            "LR1" => LR1(Lr1Data { base: (*value).clone() }),
-            "LR2" => LR2(Lr2Data { base: (*value).clone() }),
-            "LR3" => LR3(Lr3Data { base: (*value).clone() }),
-            "MDA" => MDA(MdaData { base: (*value).clone() }),
-            "MLA" => MLA(MlaData { base: (*value).clone() }),
-            "MSK" => MSK(MskData { base: (*value).clone() }),
-            "MSS" => MSS(MssData { base: (*value).clone() }),
-            "MTW" => MTW(MtwData { base: (*value).clone() }),
-            "MWD" => MWD(MwdData { base: (*value).clone() }),
-            "MWV" => MWV(MwvData { base: (*value).clone() }),
-            "OLN" => OLN(OlnData { base: (*value).clone() }),
-            "OSD" => OSD(OsdData { base: (*value).clone() }),
-            "R00" => R00(R00Data { base: (*value).clone() }),
-            "RLM" => RLM(RlmData { base: (*value).clone() }),
-            "RMA" => RMA(RmaData { base: (*value).clone() }),
-            "RMB" => RMB(RmbData { base: (*value).clone() }),
-            "RMC" => RMC(RmcData { base: (*value).clone() }),
-            "ROT" => ROT(RotData { base: (*value).clone() }),
-            "RPM" => RPM(RpmData { base: (*value).clone() }),
-            "RSA" => RSA(RsaData { base: (*value).clone() }),
-            "RSD" => RSD(RsdData { base: (*value).clone() }),
-            "RTE" => RTE(RteData { base: (*value).clone() }),
-            "SF1" => SF1(Sf1Data { base: (*value).clone() }),
-            "SSD" => SSD(SsdData { base: (*value).clone() }),
-            "STN" => STN(StnData { base: (*value).clone() }),
-            "TDS" => TDS(TdsData { base: (*value).clone() }),
-            "TFI" => TFI(TfiData { base: (*value).clone() }),
-            "TLB" => TLB(TlbData { base: (*value).clone() }),
-            "TLL" => TLL(TllData { base: (*value).clone() }),
-            "TPC" => TPC(TpcData { base: (*value).clone() }),
-            "TPR" => TPR(TprData { base: (*value).clone() }),
-            "TPT" => TPT(TptData { base: (*value).clone() }),
-            "TRF" => TRF(TrfData { base: (*value).clone() }),
-            "TTM" => TTM(TtmData { base: (*value).clone() }),
-            "TUT" => TUT(TutData { base: (*value).clone() }),
-            "TXT" => TXT(TxtData { base: (*value).clone() }),
-            "VBW" => VBW(VbwData { base: (*value).clone() }),
-            "VDR" => VDR(VdrData { base: (*value).clone() }),
-            "VHW" => VHW(VhwData { base: (*value).clone() }),
-            "VLW" => VLW(VlwData { base: (*value).clone() }),
-            "VPW" => VPW(VpwData { base: (*value).clone() }),
-            "VSD" => VSD(VsdData { base: (*value).clone() }),
-            "VTG" => VTG(VtgData { base: (*value).clone() }),
-            "VWR" => VWR(VwrData { base: (*value).clone() }),
-            "WCV" => WCV(WcvData { base: (*value).clone() }),
-            "WDC" => WDC(WdcData { base: (*value).clone() }),
-            "WDR" => WDR(WdrData { base: (*value).clone() }),
-            "WNC" => WNC(WncData { base: (*value).clone() }),
-            "WPL" => WPL(WplData { base: (*value).clone() }),
-            "XDR" => XDR(XdrData { base: (*value).clone() }),
-            "XTE" => XTE(XteData { base: (*value).clone() }),
-            "XTR" => XTR(XtrData { base: (*value).clone() }),
-            "ZDA" => ZDA(ZdaData { base: (*value).clone() }),
-            "ZDL" => ZDL(ZdlData { base: (*value).clone() }),
-            "ZFO" => ZFO(ZfoData { base: (*value).clone() }),
-            "ZTG" => ZTG(ZtgData { base: (*value).clone() }),
-            "ABM" => ABM(AbmData { base: (*value).clone() }),
-            "BBM" => BBM(BbmData { base: (*value).clone() }),
-            "VDM" => VDM(VdmData { base: (*value).clone() }),
-            "VDO" => VDO(VdoData { base: (*value).clone() }),
-            _ => NmeaSentence::ERROR(ErrorData {
-                error: "Invalid or unknown message type".to_string(),
-                message: value._original.clone()
-            }),
-        }
+        dispatch(value.clone())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<NmeaBaseSentence> for NmeaSentence {
+    fn from(value: NmeaBaseSentence) -> Self {
+        dispatch(value)
     }
 }
 
+#[cfg(feature = "alloc")]
 impl From<&String> for NmeaSentence {
     fn from(value: &String) -> Self {
-        let base = NmeaBaseSentence::from(value);
-        NmeaSentence::from(&base)
+        NmeaSentence::from(value.as_str()).into_owned()
+    }
+}
+
+impl<'a> From<NmeaSentenceRef<'a>> for NmeaSentence<NmeaSentenceRef<'a>> {
+    fn from(value: NmeaSentenceRef<'a>) -> Self {
+        dispatch(value)
+    }
+}
+
+impl<'a> From<&'a str> for NmeaSentence<NmeaSentenceRef<'a>> {
+    fn from(value: &'a str) -> Self {
+        NmeaSentence::from(value.as_bytes())
+    }
+}
+
+// Unlike NmeaSentenceRef::from, errors say what was wrong with the sentence.
+impl<'a> From<&'a [u8]> for NmeaSentence<NmeaSentenceRef<'a>> {
+    fn from(value: &'a [u8]) -> Self {
+        match NmeaSentenceRef::parse(value) {
+            Ok(sentence) => dispatch(sentence),
+            Err(error) => NmeaSentence::ERROR(ErrorData::new(error, value)),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl NmeaSentence<NmeaSentenceRef<'_>> {
+    pub fn into_owned(self) -> NmeaSentence {
+        match self {
+            AAM(data) => AAM(data.into_owned()),
+            ABK(data) => ABK(data.into_owned()),
+            ACA(data) => ACA(data.into_owned()),
+            ACK(data) => ACK(data.into_owned()),
+            ACS(data) => ACS(data.into_owned()),
+            ADS(data) => ADS(data.into_owned()),
+            AIR(data) => AIR(data.into_owned()),
+            AKD(data) => AKD(data.into_owned()),
+            ALA(data) => ALA(data.into_owned()),
+            ALM(data) => ALM(data.into_owned()),
+            ALR(data) => ALR(data.into_owned()),
+            APA(data) => APA(data.into_owned()),
+            APB(data) => APB(data.into_owned()),
+            ASD(data) => ASD(data.into_owned()),
+            BEC(data) => BEC(data.into_owned()),
+            BOD(data) => BOD(data.into_owned()),
+            BWC(data) => BWC(data.into_owned()),
+            BWR(data) => BWR(data.into_owned()),
+            BWW(data) => BWW(data.into_owned()),
+            CEK(data) => CEK(data.into_owned()),
+            COP(data) => COP(data.into_owned()),
+            CUR(data) => CUR(data.into_owned()),
+            DBK(data) => DBK(data.into_owned()),
+            DBS(data) => DBS(data.into_owned()),
+            DBT(data) => DBT(data.into_owned()),
+            DCN(data) => DCN(data.into_owned()),
+            DCR(data) => DCR(data.into_owned()),
+            DDC(data) => DDC(data.into_owned()),
+            DOR(data) => DOR(data.into_owned()),
+            DPT(data) => DPT(data.into_owned()),
+            DSC(data) => DSC(data.into_owned()),
+            DSE(data) => DSE(data.into_owned()),
+            DSI(data) => DSI(data.into_owned()),
+            DSR(data) => DSR(data.into_owned()),
+            DTM(data) => DTM(data.into_owned()),
+            ETL(data) => ETL(data.into_owned()),
+            EVE(data) => EVE(data.into_owned()),
+            FIR(data) => FIR(data.into_owned()),
+            FSI(data) => FSI(data.into_owned()),
+            GBS(data) => GBS(data.into_owned()),
+            GGA(data) => GGA(data.into_owned()),
+            GLC(data) => GLC(data.into_owned()),
+            GLL(data) => GLL(data.into_owned()),
+            GMP(data) => GMP(data.into_owned()),
+            GNS(data) => GNS(data.into_owned()),
+            GRS(data) => GRS(data.into_owned()),
+            GSA(data) => GSA(data.into_owned()),
+            GST(data) => GST(data.into_owned()),
+            GSV(data) => GSV(data.into_owned()),
+            GTD(data) => GTD(data.into_owned()),
+            GXA(data) => GXA(data.into_owned()),
+            HDG(data) => HDG(data.into_owned()),
+            HDM(data) => HDM(data.into_owned()),
+            HDT(data) => HDT(data.into_owned()),
+            HFB(data) => HFB(data.into_owned()),
+            HMR(data) => HMR(data.into_owned()),
+            HMS(data) => HMS(data.into_owned()),
+            HSC(data) => HSC(data.into_owned()),
+            HTC(data) => HTC(data.into_owned()),
+            HTD(data) => HTD(data.into_owned()),
+            ITS(data) => ITS(data.into_owned()),
+            LCD(data) => LCD(data.into_owned()),
+            LRF(data) => LRF(data.into_owned()),
+            LR1(data) => LR1(data.into_owned()),
+            LR2(data) => LR2(data.into_owned()),
+            LR3(data) => LR3(data.into_owned()),
+            MDA(data) => MDA(data.into_owned()),
+            MLA(data) => MLA(data.into_owned()),
+            MSK(data) => MSK(data.into_owned()),
+            MSS(data) => MSS(data.into_owned()),
+            MTW(data) => MTW(data.into_owned()),
+            MWD(data) => MWD(data.into_owned()),
+            MWV(data) => MWV(data.into_owned()),
+            OLN(data) => OLN(data.into_owned()),
+            OSD(data) => OSD(data.into_owned()),
+            R00(data) => R00(data.into_owned()),
+            RLM(data) => RLM(data.into_owned()),
+            RMA(data) => RMA(data.into_owned()),
+            RMB(data) => RMB(data.into_owned()),
+            RMC(data) => RMC(data.into_owned()),
+            ROT(data) => ROT(data.into_owned()),
+            RPM(data) => RPM(data.into_owned()),
+            RSA(data) => RSA(data.into_owned()),
+            RSD(data) => RSD(data.into_owned()),
+            RTE(data) => RTE(data.into_owned()),
+            SF1(data) => SF1(data.into_owned()),
+            SSD(data) => SSD(data.into_owned()),
+            STN(data) => STN(data.into_owned()),
+            TDS(data) => TDS(data.into_owned()),
+            TFI(data) => TFI(data.into_owned()),
+            TLB(data) => TLB(data.into_owned()),
+            TLL(data) => TLL(data.into_owned()),
+            TPC(data) => TPC(data.into_owned()),
+            TPR(data) => TPR(data.into_owned()),
+            TPT(data) => TPT(data.into_owned()),
+            TRF(data) => TRF(data.into_owned()),
+            TTM(data) => TTM(data.into_owned()),
+            TUT(data) => TUT(data.into_owned()),
+            TXT(data) => TXT(data.into_owned()),
+            VBW(data) => VBW(data.into_owned()),
+            VDR(data) => VDR(data.into_owned()),
+            VHW(data) => VHW(data.into_owned()),
+            VLW(data) => VLW(data.into_owned()),
+            VPW(data) => VPW(data.into_owned()),
+            VSD(data) => VSD(data.into_owned()),
+            VTG(data) => VTG(data.into_owned()),
+            VWR(data) => VWR(data.into_owned()),
+            WAT(data) => WAT(data.into_owned()),
+            WCV(data) => WCV(data.into_owned()),
+            WDC(data) => WDC(data.into_owned()),
+            WDR(data) => WDR(data.into_owned()),
+            WNC(data) => WNC(data.into_owned()),
+            WPL(data) => WPL(data.into_owned()),
+            XDR(data) => XDR(data.into_owned()),
+            XTE(data) => XTE(data.into_owned()),
+            XTR(data) => XTR(data.into_owned()),
+            ZDA(data) => ZDA(data.into_owned()),
+            ZDL(data) => ZDL(data.into_owned()),
+            ZFO(data) => ZFO(data.into_owned()),
+            ZTG(data) => ZTG(data.into_owned()),
+            ABM(data) => ABM(data.into_owned()),
+            BBM(data) => BBM(data.into_owned()),
+            VDM(data) => VDM(data.into_owned()),
+            VDO(data) => VDO(data.into_owned()),
+            NmeaSentence::Custom(custom) => NmeaSentence::Custom(custom),
+            NmeaSentence::ERROR(data) => NmeaSentence::ERROR(data),
+        }
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests;
@@ -0,0 +1,720 @@
+use std::f64::consts::PI;
+use chrono::{Duration, NaiveTime, Timelike};
+use crate::*;
+use crate::units::*;
+
+pub const PGN_RUDDER: u32 = 127245;
+pub const PGN_VESSEL_HEADING: u32 = 127250;
+pub const PGN_SPEED: u32 = 128259;
+pub const PGN_WATER_DEPTH: u32 = 128267;
+pub const PGN_POSITION_RAPID: u32 = 129025;
+pub const PGN_COG_SOG_RAPID: u32 = 129026;
+pub const PGN_GNSS_POSITION: u32 = 129029;
+pub const PGN_CROSS_TRACK_ERROR: u32 = 129283;
+pub const PGN_NAVIGATION_DATA: u32 = 129284;
+pub const PGN_WIND_DATA: u32 = 130306;
+pub const PGN_TEMPERATURE: u32 = 130312;
+
+pub const BROADCAST: u8 = 255;
+
+// Heading and course references.
+const REFERENCE_TRUE: u8 = 0;
+const REFERENCE_MAGNETIC: u8 = 1;
+
+// Wind references.
+const WIND_TRUE_NORTH: u8 = 0;
+const WIND_MAGNETIC_NORTH: u8 = 1;
+const WIND_APPARENT: u8 = 2;
+const WIND_TRUE_BOAT: u8 = 3;
+const WIND_TRUE_WATER: u8 = 4;
+
+// Temperature sources.
+const TEMPERATURE_SEA: u8 = 0;
+const TEMPERATURE_OUTSIDE: u8 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct N2kMessage {
+    pub pgn: u32,
+    pub priority: u8,
+    pub source: u8,
+    pub destination: u8,
+    pub timestamp: Option<NaiveTime>,
+    pub data: Vec<u8>,
+}
+
+fn default_priority(pgn: u32) -> u8 {
+    match pgn {
+        PGN_WATER_DEPTH | PGN_GNSS_POSITION | PGN_CROSS_TRACK_ERROR | PGN_NAVIGATION_DATA => 3,
+        PGN_TEMPERATURE => 5,
+        _ => 2,
+    }
+}
+
+// Builds payloads field by field.  Missing values are written as the "data not available"
+// pattern for the field's width; values outside the field's range are clamped below the
+// reserved top values, which Fields reads as unavailable.
+struct Payload {
+    data: Vec<u8>,
+}
+
+impl Payload {
+    fn new() -> Self {
+        Self { data: Vec::new() }
+    }
+
+    fn byte(&mut self, b: u8) -> &mut Self {
+        self.data.push(b);
+        self
+    }
+
+    fn u8(&mut self, v: Option<u8>) -> &mut Self {
+        self.byte(v.map(|v| v.min(0xFC)).unwrap_or(0xFF))
+    }
+
+    fn unsigned(&mut self, v: Option<f64>, resolution: f64, bytes: usize) -> &mut Self {
+        let not_available = (1u64 << (bytes * 8)) - 1;
+        let raw = match v {
+            Some(v) if v.is_finite() => ((v / resolution).round().max(0.0) as u64).min(not_available - 3),
+            _ => not_available,
+        };
+        self.data.extend_from_slice(&raw.to_le_bytes()[..bytes]);
+        self
+    }
+
+    fn signed(&mut self, v: Option<f64>, resolution: f64, bytes: usize) -> &mut Self {
+        let not_available = (1i64 << (bytes * 8 - 1)) - 1;
+        let raw = match v {
+            Some(v) if v.is_finite() => ((v / resolution).round() as i64).clamp(-not_available - 1, not_available - 2),
+            _ => not_available,
+        };
+        self.data.extend_from_slice(&raw.to_le_bytes()[..bytes]);
+        self
+    }
+
+    fn u16(&mut self, v: Option<f64>, resolution: f64) -> &mut Self {
+        self.unsigned(v, resolution, 2)
+    }
+
+    fn i16(&mut self, v: Option<f64>, resolution: f64) -> &mut Self {
+        self.signed(v, resolution, 2)
+    }
+
+    fn u32(&mut self, v: Option<f64>, resolution: f64) -> &mut Self {
+        self.unsigned(v, resolution, 4)
+    }
+
+    fn i32(&mut self, v: Option<f64>, resolution: f64) -> &mut Self {
+        self.signed(v, resolution, 4)
+    }
+
+    fn i64(&mut self, v: Option<f64>, resolution: f64) -> &mut Self {
+        // 64 bit fields are too wide for the clamping arithmetic in signed().
+        let raw = match v {
+            Some(v) if v.is_finite() => (v / resolution).round() as i64,
+            _ => i64::MAX,
+        };
+        self.data.extend_from_slice(&raw.to_le_bytes());
+        self
+    }
+
+    fn angle(&mut self, radians: Option<f64>) -> &mut Self {
+        self.u16(radians.map(|r| r.rem_euclid(2.0 * PI)), 0.0001)
+    }
+
+    fn finish(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.data)
+    }
+}
+
+// Reads payload fields, treating the reserved top values of each width as unavailable.
+struct Fields<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Fields<'a> {
+    fn raw(&self, offset: usize, bytes: usize) -> Option<u64> {
+        let slice = self.data.get(offset..offset + bytes)?;
+        let mut buffer = [0u8; 8];
+        buffer[..bytes].copy_from_slice(slice);
+        Some(u64::from_le_bytes(buffer))
+    }
+
+    fn byte(&self, offset: usize) -> Option<u8> {
+        self.data.get(offset).copied()
+    }
+
+    fn u8(&self, offset: usize) -> Option<u8> {
+        self.byte(offset).filter(|b| *b < 0xFD)
+    }
+
+    fn unsigned(&self, offset: usize, bytes: usize, resolution: f64) -> Option<f64> {
+        let raw = self.raw(offset, bytes)?;
+        let max = (1u64 << (bytes * 8)) - 1;
+        if raw >= max - 2 { None } else { Some(raw as f64 * resolution) }
+    }
+
+    fn signed(&self, offset: usize, bytes: usize, resolution: f64) -> Option<f64> {
+        let raw = self.raw(offset, bytes)?;
+        let shift = 64 - bytes * 8;
+        let value = ((raw << shift) as i64) >> shift;
+        let max = (1i64 << (bytes * 8 - 1)) - 1;
+        if value >= max - 1 { None } else { Some(value as f64 * resolution) }
+    }
+
+    fn u16(&self, offset: usize, resolution: f64) -> Option<f64> {
+        self.unsigned(offset, 2, resolution)
+    }
+
+    fn i16(&self, offset: usize, resolution: f64) -> Option<f64> {
+        self.signed(offset, 2, resolution)
+    }
+
+    fn u32(&self, offset: usize, resolution: f64) -> Option<f64> {
+        self.unsigned(offset, 4, resolution)
+    }
+
+    fn i32(&self, offset: usize, resolution: f64) -> Option<f64> {
+        self.signed(offset, 4, resolution)
+    }
+
+    fn i64(&self, offset: usize, resolution: f64) -> Option<f64> {
+        let raw = self.raw(offset, 8)? as i64;
+        if raw >= i64::MAX - 1 { None } else { Some(raw as f64 * resolution) }
+    }
+}
+
+fn knots(mps: f64) -> f64 {
+    mps / KNOTS_TO_MPS
+}
+
+fn optional(v: Option<f64>, decimals: usize) -> String {
+    v.map(|v| format_number(v, decimals)).unwrap_or_default()
+}
+
+fn optional_angle(v: Option<f64>) -> String {
+    v.map(format_angle).unwrap_or_default()
+}
+
+fn seconds_of_day(time: Option<NaiveTime>) -> Option<f64> {
+    time.map(|t| t.num_seconds_from_midnight() as f64 + t.nanosecond() as f64 / 1e9)
+}
+
+fn coordinate(v: Option<ValueWithUnit<f32>>) -> Option<f64> {
+    v.map(|v| decimal_degrees(&v))
+}
+
+pub struct N2kConverter {
+    source: u8,
+    talker: String,
+    sid: u8,
+}
+
+impl Default for N2kConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl N2kConverter {
+    pub fn new() -> Self {
+        Self {
+            source: 0,
+            talker: "YD".to_string(),
+            sid: 0,
+        }
+    }
+
+    // Source address put on generated PGNs.
+    pub fn with_source(mut self, source: u8) -> Self {
+        self.source = source;
+        self
+    }
+
+    // Talker ID put on generated sentences.
+    pub fn with_talker(mut self, talker: &str) -> Self {
+        self.talker = talker.to_string();
+        self
+    }
+
+    fn message(&self, pgn: u32, data: Vec<u8>) -> N2kMessage {
+        N2kMessage {
+            pgn,
+            priority: default_priority(pgn),
+            source: self.source,
+            destination: BROADCAST,
+            timestamp: None,
+            data,
+        }
+    }
+
+    // All PGNs made from one sentence share a sequence ID.
+    pub fn to_pgns(&mut self, sentence: &NmeaSentence) -> Vec<N2kMessage> {
+        let sid = self.sid;
+        self.sid = if self.sid >= 252 { 0 } else { self.sid + 1 };
+        let mut messages = Vec::new();
+        let mut add = |pgn: u32, data: Vec<u8>| messages.push(self.message(pgn, data));
+        match sentence {
+            NmeaSentence::HDG(d) => {
+                if let Some(heading) = d.sensor_heading_degrees() {
+                    add(PGN_VESSEL_HEADING, heading_payload(sid, Some(radians(heading)),
+                        d.deviation().map(|v| signed_angle(&v)),
+                        d.variation().map(|v| signed_angle(&v)),
+                        REFERENCE_MAGNETIC));
+                }
+            }
+            NmeaSentence::HDM(d) => {
+                if let Some(heading) = d.heading_magnetic() {
+                    add(PGN_VESSEL_HEADING, heading_payload(sid, Some(radians(heading.value)), None, None, REFERENCE_MAGNETIC));
+                }
+            }
+            NmeaSentence::HDT(d) => {
+                if let Some(heading) = d.heading_true() {
+                    add(PGN_VESSEL_HEADING, heading_payload(sid, Some(radians(heading.value)), None, None, REFERENCE_TRUE));
+                }
+            }
+            NmeaSentence::VHW(d) => {
+                if let Some(stw) = d.stw_knots().or_else(|| d.stw_kph()).and_then(|s| speed_mps(&s)) {
+                    add(PGN_SPEED, Payload::new()
+                        .byte(sid).u16(Some(stw), 0.01).u16(None, 0.01)
+                        .byte(0x00).byte(0xF0).byte(0xFF)
+                        .finish());
+                }
+            }
+            NmeaSentence::DPT(d) => {
+                if let Some(depth) = d.water_depth_meters() {
+                    add(PGN_WATER_DEPTH, depth_payload(sid, depth as f64,
+                        d.transducer_offset().map(|o| o as f64),
+                        d.maximum_range_scale().map(|r| r as f64)));
+                }
+            }
+            NmeaSentence::DBT(d) => {
+                let depth = d.depth_meters().or_else(|| d.depth_feet()).or_else(|| d.depth_fathoms())
+                    .and_then(|v| distance_meters(&v));
+                if let Some(depth) = depth {
+                    add(PGN_WATER_DEPTH, depth_payload(sid, depth, None, None));
+                }
+            }
+            NmeaSentence::GLL(d) if d.status() != Some('V') => {
+                if let Some(p) = position_payload(coordinate(d.latitude()), coordinate(d.longitude())) {
+                    add(PGN_POSITION_RAPID, p);
+                }
+            }
+            NmeaSentence::RMC(d) if d.status() != Some('V') => {
+                if let Some(p) = position_payload(coordinate(d.latitude()), coordinate(d.longitude())) {
+                    add(PGN_POSITION_RAPID, p);
+                }
+                add(PGN_COG_SOG_RAPID, cog_sog_payload(sid, REFERENCE_TRUE,
+                    d.track_made_good_true().map(radians),
+                    d.sog_knots().map(|s| s as f64 * KNOTS_TO_MPS)));
+            }
+            NmeaSentence::VTG(d) if d.mode() != Some('N') => {
+                let (reference, cog) = match (d.cog_true(), d.cog_magnetic()) {
+                    (Some(c), _) => (REFERENCE_TRUE, Some(radians(c.value))),
+                    (None, Some(c)) => (REFERENCE_MAGNETIC, Some(radians(c.value))),
+                    _ => (REFERENCE_TRUE, None),
+                };
+                let sog = d.sog_knots().or_else(|| d.sog_kph()).and_then(|s| speed_mps(&s));
+                add(PGN_COG_SOG_RAPID, cog_sog_payload(sid, reference, cog, sog));
+            }
+            NmeaSentence::GGA(d) => {
+                let latitude = coordinate(d.latitude());
+                let longitude = coordinate(d.longitude());
+                if let Some(p) = position_payload(latitude, longitude) {
+                    add(PGN_POSITION_RAPID, p);
+                }
+                add(PGN_GNSS_POSITION, Payload::new()
+                    .byte(sid)
+                    .u16(None, 1.0)
+                    .u32(seconds_of_day(d.time()), 0.0001)
+                    .i64(latitude, 1e-16)
+                    .i64(longitude, 1e-16)
+                    .i64(d.antenna_altitude().and_then(|a| distance_meters(&a)), 1e-6)
+                    // GPS system; GGA's quality indicator numbering matches the method field.
+                    .byte(d.quality_indicator().unwrap_or(0).min(8) << 4)
+                    .byte(0xFC)
+                    .u8(d.satellites_in_use_count())
+                    .i16(d.hdop().map(|h| h as f64), 0.01)
+                    .i16(None, 0.01)
+                    .i32(d.geoidal_separation().and_then(|g| distance_meters(&g)), 0.01)
+                    .byte(0)
+                    .finish());
+            }
+            NmeaSentence::MWV(d) if d.status() == Some('A') => {
+                let reference = match d.wind_angle().map(|a| a.unit) {
+                    Some('R') => Some(WIND_APPARENT),
+                    Some('T') => Some(WIND_TRUE_BOAT),
+                    _ => None,
+                };
+                if let Some(reference) = reference {
+                    add(PGN_WIND_DATA, wind_payload(sid,
+                        d.wind_speed().and_then(|s| speed_mps(&s)),
+                        d.wind_angle().map(|a| radians(a.value)),
+                        reference));
+                }
+            }
+            NmeaSentence::MWD(d) => {
+                let speed = d.wind_speed_mps().or_else(|| d.wind_speed_knots()).and_then(|s| speed_mps(&s));
+                if let Some(direction) = d.wind_direction_true() {
+                    add(PGN_WIND_DATA, wind_payload(sid, speed, Some(radians(direction.value)), WIND_TRUE_NORTH));
+                } else if let Some(direction) = d.wind_direction_magnetic() {
+                    add(PGN_WIND_DATA, wind_payload(sid, speed, Some(radians(direction.value)), WIND_MAGNETIC_NORTH));
+                }
+            }
+            NmeaSentence::MTW(d) => {
+                if let Some(t) = d.temperature().and_then(|t| kelvin(&t)) {
+                    add(PGN_TEMPERATURE, temperature_payload(sid, 0, TEMPERATURE_SEA, t));
+                }
+            }
+            NmeaSentence::XDR(d) => {
+                for m in d.measurements().unwrap_or_default() {
+                    if m.transducer_type == 'C' {
+                        if let Some(t) = kelvin(&m.data) {
+                            let source = match m.name.to_uppercase().as_str() {
+                                "WATERTEMP" | "ENV_WATER_T" => TEMPERATURE_SEA,
+                                _ => TEMPERATURE_OUTSIDE,
+                            };
+                            add(PGN_TEMPERATURE, temperature_payload(sid, 0, source, t));
+                        }
+                    } else if m.transducer_type == 'A' && m.name.eq_ignore_ascii_case("RUDDER") {
+                        add(PGN_RUDDER, rudder_payload(0, radians(m.data.value)));
+                    }
+                }
+            }
+            NmeaSentence::RSA(d) => {
+                if d.starboard_status() == Some('A') {
+                    if let Some(angle) = d.starboard_angle() {
+                        add(PGN_RUDDER, rudder_payload(0, radians(angle)));
+                    }
+                }
+                if d.port_status() == Some('A') {
+                    if let Some(angle) = d.port_angle() {
+                        add(PGN_RUDDER, rudder_payload(1, radians(angle)));
+                    }
+                }
+            }
+            NmeaSentence::XTE(d) if d.status() != Some('V') => {
+                if let Some(xte) = d.xte().and_then(|x| distance_meters(&x)) {
+                    add(PGN_CROSS_TRACK_ERROR, xte_payload(sid, xte, d.direction_to_steer()));
+                }
+            }
+            NmeaSentence::APB(d) => {
+                if let Some(xte) = d.xte().and_then(|x| distance_meters(&x)) {
+                    add(PGN_CROSS_TRACK_ERROR, xte_payload(sid, xte, d.steer_direction()));
+                }
+                let origin = d.bearing_origin_to_destination();
+                let present = d.bearing_present_to_destination();
+                let reference = match origin.as_ref().or(present.as_ref()).map(|b| b.unit) {
+                    Some('M') => REFERENCE_MAGNETIC,
+                    _ => REFERENCE_TRUE,
+                };
+                add(PGN_NAVIGATION_DATA, navigation_payload(sid, NavigationData {
+                    distance: None,
+                    reference,
+                    perpendicular_crossed: d.perpendicular_status() == Some('A'),
+                    arrival_circle_entered: d.arrival_status() == Some('A'),
+                    bearing_origin: origin.map(|b| radians(b.value)),
+                    bearing_position: present.map(|b| radians(b.value)),
+                    destination_id: d.destination_id().and_then(|id| id.parse::<u32>().ok()),
+                    latitude: None,
+                    longitude: None,
+                    closing_velocity: None,
+                }));
+            }
+            NmeaSentence::RMB(d) if d.status() != Some('V') => {
+                if let Some(xte) = d.xte() {
+                    add(PGN_CROSS_TRACK_ERROR, xte_payload(sid, xte as f64 * NAUTICAL_MILE_METERS, d.direction_to_steer()));
+                }
+                add(PGN_NAVIGATION_DATA, navigation_payload(sid, NavigationData {
+                    distance: d.destination_range().map(|r| r as f64 * NAUTICAL_MILE_METERS),
+                    reference: REFERENCE_TRUE,
+                    perpendicular_crossed: false,
+                    arrival_circle_entered: d.arrival_status() == Some('A'),
+                    bearing_origin: None,
+                    bearing_position: d.destination_bearing().map(radians),
+                    destination_id: d.destination_waypoint_id().and_then(|id| id.parse::<u32>().ok()),
+                    latitude: coordinate(d.destination_latitude()),
+                    longitude: coordinate(d.destination_longitude()),
+                    closing_velocity: d.vmg_knots().map(|v| v as f64 * KNOTS_TO_MPS),
+                }));
+            }
+            _ => {}
+        }
+        messages
+    }
+
+    // The reverse direction.  PGNs without an NMEA 0183 equivalent give no sentences.
+    pub fn to_sentences(&self, message: &N2kMessage) -> Vec<NmeaBaseSentence> {
+        let f = Fields { data: &message.data };
+        let sender = format!("${}", self.talker);
+        let build = |message_type: &str, fields: Vec<String>| NmeaBaseSentence::build(&sender, message_type, &fields);
+        let east_west = |v: Option<f64>| match v {
+            Some(v) => (format_number(v.to_degrees().abs(), 1), if v < 0.0 { "W" } else { "E" }.to_string()),
+            None => ("".to_string(), "".to_string()),
+        };
+        match message.pgn {
+            PGN_VESSEL_HEADING => {
+                let heading = match f.u16(1, 0.0001) {
+                    Some(h) => h,
+                    None => return vec![],
+                };
+                if f.byte(7).map(|b| b & 0x03) == Some(REFERENCE_TRUE) {
+                    vec![build("HDT", vec![format_angle(heading), "T".to_string()])]
+                } else {
+                    let (deviation, deviation_direction) = east_west(f.i16(3, 0.0001));
+                    let (variation, variation_direction) = east_west(f.i16(5, 0.0001));
+                    vec![build("HDG", vec![format_angle(heading), deviation, deviation_direction, variation, variation_direction])]
+                }
+            }
+            PGN_SPEED => match f.u16(1, 0.01) {
+                Some(stw) => vec![build("VHW", vec![
+                    "".to_string(), "T".to_string(), "".to_string(), "M".to_string(),
+                    format_number(knots(stw), 2), "N".to_string(),
+                    format_number(stw * 3.6, 2), "K".to_string(),
+                ])],
+                None => vec![],
+            },
+            PGN_WATER_DEPTH => match f.u32(1, 0.01) {
+                Some(depth) => vec![build("DPT", vec![
+                    format_number(depth, 2),
+                    optional(f.i16(5, 0.001), 3),
+                    f.u8(7).map(|r| (r as u32 * 10).to_string()).unwrap_or_default(),
+                ])],
+                None => vec![],
+            },
+            PGN_POSITION_RAPID => match (f.i32(0, 1e-7), f.i32(4, 1e-7)) {
+                (Some(latitude), Some(longitude)) => {
+                    let (lat, lat_hemisphere) = format_coordinate(latitude, 2, 'N', 'S');
+                    let (lon, lon_hemisphere) = format_coordinate(longitude, 3, 'E', 'W');
+                    vec![build("GLL", vec![lat, lat_hemisphere, lon, lon_hemisphere, "".to_string(), "A".to_string(), "A".to_string()])]
+                }
+                _ => vec![],
+            },
+            PGN_COG_SOG_RAPID => {
+                let cog = f.u16(2, 0.0001);
+                let sog = f.u16(4, 0.01);
+                let magnetic = f.byte(1).map(|b| b & 0x03) == Some(REFERENCE_MAGNETIC);
+                vec![build("VTG", vec![
+                    if magnetic { "".to_string() } else { optional_angle(cog) }, "T".to_string(),
+                    if magnetic { optional_angle(cog) } else { "".to_string() }, "M".to_string(),
+                    optional(sog.map(knots), 1), "N".to_string(),
+                    optional(sog.map(|s| s * 3.6), 1), "K".to_string(),
+                    "A".to_string(),
+                ])]
+            }
+            PGN_GNSS_POSITION => {
+                let (latitude, longitude) = match (f.i64(7, 1e-16), f.i64(15, 1e-16)) {
+                    (Some(latitude), Some(longitude)) => (latitude, longitude),
+                    _ => return vec![],
+                };
+                let (lat, lat_hemisphere) = format_coordinate(latitude, 2, 'N', 'S');
+                let (lon, lon_hemisphere) = format_coordinate(longitude, 3, 'E', 'W');
+                let time = f.u32(3, 0.0001)
+                    .and_then(|s| NaiveTime::from_hms_opt(0, 0, 0).map(|m| m + Duration::milliseconds((s * 1000.0) as i64)))
+                    .map(|t| format!("{:02}{:02}{:02}.{:02}", t.hour(), t.minute(), t.second(), t.nanosecond() / 10_000_000))
+                    .unwrap_or_default();
+                vec![build("GGA", vec![
+                    time, lat, lat_hemisphere, lon, lon_hemisphere,
+                    f.byte(31).map(|b| (b >> 4).to_string()).unwrap_or_default(),
+                    f.u8(33).map(|n| n.to_string()).unwrap_or_default(),
+                    optional(f.i16(34, 0.01), 2),
+                    optional(f.i64(23, 1e-6), 1), "M".to_string(),
+                    optional(f.i32(38, 0.01), 1), "M".to_string(),
+                    "".to_string(), "".to_string(),
+                ])]
+            }
+            PGN_WIND_DATA => {
+                let speed = f.u16(1, 0.01);
+                let angle = f.u16(3, 0.0001);
+                match f.byte(5).map(|b| b & 0x07) {
+                    Some(WIND_APPARENT) | Some(WIND_TRUE_BOAT) | Some(WIND_TRUE_WATER) => {
+                        let reference = if f.byte(5).map(|b| b & 0x07) == Some(WIND_APPARENT) { "R" } else { "T" };
+                        vec![build("MWV", vec![
+                            optional_angle(angle), reference.to_string(),
+                            optional(speed.map(knots), 1), "N".to_string(),
+                            if angle.is_some() && speed.is_some() { "A" } else { "V" }.to_string(),
+                        ])]
+                    }
+                    Some(WIND_TRUE_NORTH) | Some(WIND_MAGNETIC_NORTH) => {
+                        let magnetic = f.byte(5).map(|b| b & 0x07) == Some(WIND_MAGNETIC_NORTH);
+                        vec![build("MWD", vec![
+                            if magnetic { "".to_string() } else { optional_angle(angle) }, "T".to_string(),
+                            if magnetic { optional_angle(angle) } else { "".to_string() }, "M".to_string(),
+                            optional(speed.map(knots), 1), "N".to_string(),
+                            optional(speed, 1), "M".to_string(),
+                        ])]
+                    }
+                    _ => vec![],
+                }
+            }
+            PGN_TEMPERATURE => {
+                let celsius = match f.u16(3, 0.01) {
+                    Some(t) => t - KELVIN_OFFSET,
+                    None => return vec![],
+                };
+                match f.byte(2) {
+                    Some(TEMPERATURE_SEA) => vec![build("MTW", vec![format_number(celsius, 1), "C".to_string()])],
+                    Some(TEMPERATURE_OUTSIDE) => vec![build("XDR", vec![
+                        "C".to_string(), format_number(celsius, 1), "C".to_string(), "AIRTEMP".to_string(),
+                    ])],
+                    Some(source) => vec![build("XDR", vec![
+                        "C".to_string(), format_number(celsius, 1), "C".to_string(), format!("TEMP{}", source),
+                    ])],
+                    None => vec![],
+                }
+            }
+            PGN_RUDDER => match f.i16(4, 0.0001) {
+                Some(angle) => {
+                    let angle = format_number(angle.to_degrees(), 1);
+                    if f.byte(0) == Some(1) {
+                        vec![build("RSA", vec!["".to_string(), "V".to_string(), angle, "A".to_string()])]
+                    } else {
+                        vec![build("RSA", vec![angle, "A".to_string(), "".to_string(), "V".to_string()])]
+                    }
+                }
+                None => vec![],
+            },
+            PGN_CROSS_TRACK_ERROR => match f.i32(2, 0.01) {
+                Some(xte) => vec![build("XTE", vec![
+                    "A".to_string(), "A".to_string(),
+                    format_number(xte.abs() / NAUTICAL_MILE_METERS, 3),
+                    if xte < 0.0 { "R" } else { "L" }.to_string(),
+                    "N".to_string(), "A".to_string(),
+                ])],
+                None => vec![],
+            },
+            PGN_NAVIGATION_DATA => {
+                let flags = f.byte(5).unwrap_or(0);
+                let latitude = f.i32(24, 1e-7);
+                let longitude = f.i32(28, 1e-7);
+                let (lat, lat_hemisphere) = latitude.map(|l| format_coordinate(l, 2, 'N', 'S')).unwrap_or_default();
+                let (lon, lon_hemisphere) = longitude.map(|l| format_coordinate(l, 3, 'E', 'W')).unwrap_or_default();
+                vec![build("RMB", vec![
+                    "A".to_string(), "".to_string(), "".to_string(), "".to_string(),
+                    f.u32(20, 1.0).map(|id| id.to_string()).unwrap_or_default(),
+                    lat, lat_hemisphere, lon, lon_hemisphere,
+                    optional(f.u32(1, 0.01).map(|d| d / NAUTICAL_MILE_METERS), 2),
+                    optional_angle(f.u16(14, 0.0001)),
+                    optional(f.i16(32, 0.01).map(knots), 1),
+                    if (flags >> 4) & 0x03 == 1 { "A" } else { "V" }.to_string(),
+                    "A".to_string(),
+                ])]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+fn heading_payload(sid: u8, heading: Option<f64>, deviation: Option<f64>, variation: Option<f64>, reference: u8) -> Vec<u8> {
+    Payload::new()
+        .byte(sid)
+        .angle(heading)
+        .i16(deviation, 0.0001)
+        .i16(variation, 0.0001)
+        .byte(0xFC | reference)
+        .finish()
+}
+
+fn depth_payload(sid: u8, depth: f64, offset: Option<f64>, range: Option<f64>) -> Vec<u8> {
+    Payload::new()
+        .byte(sid)
+        .u32(Some(depth), 0.01)
+        .i16(offset, 0.001)
+        .u8(range.map(|r| (r / 10.0).round().clamp(0.0, 252.0) as u8))
+        .finish()
+}
+
+fn position_payload(latitude: Option<f64>, longitude: Option<f64>) -> Option<Vec<u8>> {
+    match (latitude, longitude) {
+        (Some(_), Some(_)) => Some(Payload::new().i32(latitude, 1e-7).i32(longitude, 1e-7).finish()),
+        _ => None,
+    }
+}
+
+fn cog_sog_payload(sid: u8, reference: u8, cog: Option<f64>, sog: Option<f64>) -> Vec<u8> {
+    Payload::new()
+        .byte(sid)
+        .byte(0xFC | reference)
+        .angle(cog)
+        .u16(sog, 0.01)
+        .byte(0xFF)
+        .byte(0xFF)
+        .finish()
+}
+
+fn wind_payload(sid: u8, speed: Option<f64>, angle: Option<f64>, reference: u8) -> Vec<u8> {
+    Payload::new()
+        .byte(sid)
+        .u16(speed, 0.01)
+        .angle(angle)
+        .byte(0xF8 | reference)
+        .byte(0xFF)
+        .byte(0xFF)
+        .finish()
+}
+
+fn temperature_payload(sid: u8, instance: u8, source: u8, kelvin: f64) -> Vec<u8> {
+    Payload::new()
+        .byte(sid)
+        .byte(instance)
+        .byte(source)
+        .u16(Some(kelvin), 0.01)
+        .u16(None, 0.01)
+        .byte(0xFF)
+        .finish()
+}
+
+fn rudder_payload(instance: u8, position: f64) -> Vec<u8> {
+    Payload::new()
+        .byte(instance)
+        .byte(0xF8)
+        .i16(None, 0.0001)
+        .i16(Some(position), 0.0001)
+        .byte(0xFF)
+        .byte(0xFF)
+        .finish()
+}
+
+// Positive cross track error means steer left, matching the Signal K convention.
+fn xte_payload(sid: u8, meters: f64, steer: Option<char>) -> Vec<u8> {
+    let meters = if steer == Some('R') { -meters.abs() } else { meters.abs() };
+    Payload::new()
+        .byte(sid)
+        .byte(0x30)
+        .i32(Some(meters), 0.01)
+        .finish()
+}
+
+struct NavigationData {
+    distance: Option<f64>,
+    reference: u8,
+    perpendicular_crossed: bool,
+    arrival_circle_entered: bool,
+    bearing_origin: Option<f64>,
+    bearing_position: Option<f64>,
+    destination_id: Option<u32>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    closing_velocity: Option<f64>,
+}
+
+fn navigation_payload(sid: u8, n: NavigationData) -> Vec<u8> {
+    let flags = n.reference
+        | (n.perpendicular_crossed as u8) << 2
+        | (n.arrival_circle_entered as u8) << 4
+        | 1 << 6;
+    Payload::new()
+        .byte(sid)
+        .u32(n.distance, 0.01)
+        .byte(flags)
+        .u32(None, 0.0001)
+        .u16(None, 1.0)
+        .angle(n.bearing_origin)
+        .angle(n.bearing_position)
+        .u32(None, 1.0)
+        .u32(n.destination_id.map(|id| id as f64), 1.0)
+        .i32(n.latitude, 1e-7)
+        .i32(n.longitude, 1e-7)
+        .i16(n.closing_velocity, 0.01)
+        .finish()
+}
+
@@ -0,0 +1,268 @@
+use crate::*;
+use crate::units::*;
+
+pub const ST_DEPTH: u8 = 0x00;
+pub const ST_APPARENT_WIND_ANGLE: u8 = 0x10;
+pub const ST_APPARENT_WIND_SPEED: u8 = 0x11;
+pub const ST_SPEED: u8 = 0x20;
+pub const ST_WATER_TEMPERATURE: u8 = 0x23;
+pub const ST_SPEED_SENSOR: u8 = 0x26;
+pub const ST_WATER_TEMPERATURE_PRECISE: u8 = 0x27;
+pub const ST_LATITUDE: u8 = 0x50;
+pub const ST_LONGITUDE: u8 = 0x51;
+pub const ST_POSITION: u8 = 0x58;
+pub const ST_AUTOPILOT_STATUS: u8 = 0x84;
+pub const ST_HEADING_RUDDER: u8 = 0x9C;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutopilotMode {
+    Standby,
+    Auto,
+    Wind,
+    Track,
+}
+
+// Values are kept in the units SeaTalk sends them in: feet, knots, degrees and Celsius.
+// Positions are decimal degrees, negative south and west.  Rudder angles are positive to
+// starboard.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SeaTalkDatagram {
+    Depth {
+        feet: f64,
+        anchor_alarm: bool,
+        metric_display: bool,
+        transducer_defective: bool,
+        deep_alarm: bool,
+        shallow_alarm: bool,
+    },
+    ApparentWindAngle(f64),
+    ApparentWindSpeed { knots: f64, metric_display: bool },
+    SpeedThroughWater(f64),
+    WaterTemperature { celsius: f64, sensor_defective: bool },
+    Latitude(f64),
+    Longitude(f64),
+    Position { latitude: f64, longitude: f64 },
+    HeadingAndRudder { heading: f64, turning_right: bool, rudder: f64 },
+    AutopilotStatus {
+        heading: f64,
+        turning_right: bool,
+        course: f64,
+        mode: AutopilotMode,
+        off_course_alarm: bool,
+        wind_shift_alarm: bool,
+        rudder: f64,
+    },
+}
+
+fn word(datagram: &[u8], offset: usize) -> u16 {
+    datagram[offset] as u16 | (datagram[offset + 1] as u16) << 8
+}
+
+// Headings are spread over three fields: the quadrant in U, two-degree steps in VW and
+// the odd degrees in the upper bits of U.
+fn heading(u: u8, vw: u8) -> f64 {
+    let odd = match u & 0x0C {
+        0x00 => 0,
+        0x0C => 2,
+        _ => 1,
+    };
+    ((u & 0x03) as u32 * 90 + (vw & 0x3F) as u32 * 2 + odd) as f64
+}
+
+// The second byte's low nibble counts the bytes after the first three, so a datagram is
+// rejected if it is shorter than it says it is.  Unsupported commands give None.
+pub fn decode(datagram: &[u8]) -> Option<SeaTalkDatagram> {
+    let attribute = *datagram.get(1)?;
+    if datagram.len() < 3 + (attribute & 0x0F) as usize {
+        return None;
+    }
+    let u = attribute >> 4;
+    match datagram[0] {
+        ST_DEPTH if attribute & 0x0F >= 2 => {
+            let (y, z) = (datagram[2] >> 4, datagram[2] & 0x0F);
+            Some(SeaTalkDatagram::Depth {
+                feet: word(datagram, 3) as f64 / 10.0,
+                anchor_alarm: y & 0x08 != 0,
+                metric_display: y & 0x04 != 0,
+                transducer_defective: z & 0x04 != 0,
+                deep_alarm: z & 0x02 != 0,
+                shallow_alarm: z & 0x01 != 0,
+            })
+        }
+        ST_APPARENT_WIND_ANGLE if attribute & 0x0F >= 1 => {
+            let angle = ((datagram[2] as u16) << 8 | datagram[3] as u16) as f64 / 2.0;
+            Some(SeaTalkDatagram::ApparentWindAngle(angle))
+        }
+        ST_APPARENT_WIND_SPEED if attribute & 0x0F >= 1 => Some(SeaTalkDatagram::ApparentWindSpeed {
+            knots: (datagram[2] & 0x7F) as f64 + (datagram[3] & 0x0F) as f64 / 10.0,
+            metric_display: datagram[2] & 0x80 != 0,
+        }),
+        ST_SPEED if attribute & 0x0F >= 1 => Some(SeaTalkDatagram::SpeedThroughWater(word(datagram, 2) as f64 / 10.0)),
+        ST_SPEED_SENSOR if attribute & 0x0F >= 4 => {
+            Some(SeaTalkDatagram::SpeedThroughWater(word(datagram, 2) as f64 / 100.0))
+        }
+        ST_WATER_TEMPERATURE => Some(SeaTalkDatagram::WaterTemperature {
+            celsius: datagram[2] as f64,
+            sensor_defective: u & 0x04 != 0,
+        }),
+        ST_WATER_TEMPERATURE_PRECISE if attribute & 0x0F >= 1 => Some(SeaTalkDatagram::WaterTemperature {
+            celsius: (word(datagram, 2) as f64 - 100.0) / 10.0,
+            sensor_defective: false,
+        }),
+        ST_LATITUDE | ST_LONGITUDE if attribute & 0x0F >= 2 => {
+            let minutes = word(datagram, 3);
+            let degrees = datagram[2] as f64 + (minutes & 0x7FFF) as f64 / 6000.0;
+            // The top bit is south for latitude and east for longitude.
+            if datagram[0] == ST_LATITUDE {
+                Some(SeaTalkDatagram::Latitude(if minutes & 0x8000 != 0 { -degrees } else { degrees }))
+            } else {
+                Some(SeaTalkDatagram::Longitude(if minutes & 0x8000 != 0 { degrees } else { -degrees }))
+            }
+        }
+        ST_POSITION if attribute & 0x0F >= 5 => {
+            let latitude = datagram[2] as f64 + ((datagram[3] as u16) << 8 | datagram[4] as u16) as f64 / 60000.0;
+            let longitude = datagram[5] as f64 + ((datagram[6] as u16) << 8 | datagram[7] as u16) as f64 / 60000.0;
+            Some(SeaTalkDatagram::Position {
+                latitude: if u & 0x01 != 0 { -latitude } else { latitude },
+                longitude: if u & 0x02 != 0 { longitude } else { -longitude },
+            })
+        }
+        ST_HEADING_RUDDER if attribute & 0x0F >= 1 => Some(SeaTalkDatagram::HeadingAndRudder {
+            heading: heading(u, datagram[2]),
+            turning_right: u & 0x08 != 0,
+            rudder: datagram[3] as i8 as f64,
+        }),
+        ST_AUTOPILOT_STATUS if attribute & 0x0F >= 6 => {
+            let z = datagram[4] & 0x0F;
+            let mode = if z & 0x08 != 0 {
+                AutopilotMode::Track
+            } else if z & 0x04 != 0 {
+                AutopilotMode::Wind
+            } else if z & 0x02 != 0 {
+                AutopilotMode::Auto
+            } else {
+                AutopilotMode::Standby
+            };
+            Some(SeaTalkDatagram::AutopilotStatus {
+                heading: heading(u, datagram[2]),
+                turning_right: u & 0x08 != 0,
+                course: (datagram[2] >> 6) as f64 * 90.0 + datagram[3] as f64 / 2.0,
+                mode,
+                off_course_alarm: datagram[5] & 0x04 != 0,
+                wind_shift_alarm: datagram[5] & 0x08 != 0,
+                rudder: datagram[6] as i8 as f64,
+            })
+        }
+        _ => None,
+    }
+}
+
+// SeaTalk sends wind angle and speed, and latitude and longitude, in separate datagrams.
+// The translator remembers the last of each so that MWV and RMC can be built once both
+// halves have been seen.
+pub struct SeaTalkTranslator {
+    talker: String,
+    wind_angle: Option<f64>,
+    wind_speed: Option<f64>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+}
+
+impl Default for SeaTalkTranslator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SeaTalkTranslator {
+    pub fn new() -> Self {
+        Self {
+            talker: "II".to_string(),
+            wind_angle: None,
+            wind_speed: None,
+            latitude: None,
+            longitude: None,
+        }
+    }
+
+    pub fn with_talker(mut self, talker: &str) -> Self {
+        self.talker = talker.to_string();
+        self
+    }
+
+    // Decodes a datagram and translates it in one step.
+    pub fn push(&mut self, datagram: &[u8]) -> Vec<NmeaSentence> {
+        match decode(datagram) {
+            Some(d) => self.to_sentences(&d).iter().map(NmeaSentence::from).collect(),
+            None => vec![],
+        }
+    }
+
+    pub fn to_sentences(&mut self, datagram: &SeaTalkDatagram) -> Vec<NmeaBaseSentence> {
+        let sender = format!("${}", self.talker);
+        let build = |message_type: &str, fields: Vec<String>| NmeaBaseSentence::build(&sender, message_type, &fields);
+        let hdg = |heading: f64| build("HDG", vec![
+            format_number(heading, 1), "".to_string(), "".to_string(), "".to_string(), "".to_string(),
+        ]);
+        let rsa = |rudder: f64| build("RSA", vec![format_number(rudder, 1), "A".to_string(), "".to_string(), "V".to_string()]);
+        match *datagram {
+            SeaTalkDatagram::Depth { feet, transducer_defective: false, .. } => {
+                vec![build("DPT", vec![format_number(feet * FEET_TO_METERS, 2), "".to_string()])]
+            }
+            SeaTalkDatagram::Depth { .. } => vec![],
+            SeaTalkDatagram::ApparentWindAngle(angle) => {
+                self.wind_angle = Some(angle);
+                self.wind().map(|fields| vec![build("MWV", fields)]).unwrap_or_default()
+            }
+            SeaTalkDatagram::ApparentWindSpeed { knots, .. } => {
+                self.wind_speed = Some(knots);
+                self.wind().map(|fields| vec![build("MWV", fields)]).unwrap_or_default()
+            }
+            SeaTalkDatagram::SpeedThroughWater(knots) => vec![build("VHW", vec![
+                "".to_string(), "T".to_string(), "".to_string(), "M".to_string(),
+                format_number(knots, 2), "N".to_string(),
+                format_number(knots * KNOTS_TO_MPS * 3.6, 2), "K".to_string(),
+            ])],
+            SeaTalkDatagram::WaterTemperature { celsius, sensor_defective: false } => {
+                vec![build("MTW", vec![format_number(celsius, 1), "C".to_string()])]
+            }
+            SeaTalkDatagram::WaterTemperature { .. } => vec![],
+            SeaTalkDatagram::Latitude(latitude) => {
+                self.latitude = Some(latitude);
+                self.position().map(|fields| vec![build("RMC", fields)]).unwrap_or_default()
+            }
+            SeaTalkDatagram::Longitude(longitude) => {
+                self.longitude = Some(longitude);
+                self.position().map(|fields| vec![build("RMC", fields)]).unwrap_or_default()
+            }
+            SeaTalkDatagram::Position { latitude, longitude } => {
+                self.latitude = Some(latitude);
+                self.longitude = Some(longitude);
+                self.position().map(|fields| vec![build("RMC", fields)]).unwrap_or_default()
+            }
+            SeaTalkDatagram::HeadingAndRudder { heading, rudder, .. } => vec![hdg(heading), rsa(rudder)],
+            SeaTalkDatagram::AutopilotStatus { heading, rudder, .. } => vec![hdg(heading), rsa(rudder)],
+        }
+    }
+
+    fn wind(&self) -> Option<Vec<String>> {
+        let (angle, speed) = (self.wind_angle?, self.wind_speed?);
+        Some(vec![
+            format_number(angle, 1), "R".to_string(),
+            format_number(speed, 1), "N".to_string(),
+            "A".to_string(),
+        ])
+    }
+
+    // SeaTalk carries no fix time, so RMC goes out with empty time and date fields.
+    fn position(&self) -> Option<Vec<String>> {
+        let (lat, lat_hemisphere) = format_coordinate(self.latitude?, 2, 'N', 'S');
+        let (lon, lon_hemisphere) = format_coordinate(self.longitude?, 3, 'E', 'W');
+        Some(vec![
+            "".to_string(), "A".to_string(),
+            lat, lat_hemisphere, lon, lon_hemisphere,
+            "".to_string(), "".to_string(), "".to_string(), "".to_string(), "".to_string(),
+            "A".to_string(),
+        ])
+    }
+}
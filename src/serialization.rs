@@ -0,0 +1,92 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "chrono")]
+use chrono::{NaiveTime, Timelike};
+use num_traits::Signed;
+use crate::NmeaBaseSentence;
+
+// Helpers for turning deserialized or built values back into sentence fields.
+
+pub(crate) fn set(fields: &mut Vec<String>, index: usize, value: String) {
+    reserve(fields, index);
+    fields[index] = value;
+}
+
+// Missing values still take up their place in the sentence.
+pub(crate) fn reserve(fields: &mut Vec<String>, index: usize) {
+    if fields.len() <= index {
+        fields.resize(index + 1, String::new());
+    }
+}
+
+#[cfg(feature = "chrono")]
+pub(crate) fn time(t: &NaiveTime) -> String {
+    let millis = t.nanosecond() / 1_000_000;
+    if millis == 0 {
+        t.format("%H%M%S").to_string()
+    } else {
+        format!("{}.{:03}", t.format("%H%M%S"), millis)
+    }
+}
+
+// Coordinate accessors return a signed ddmm.mmmm value and drop the hemisphere letter, so
+// the letter is recovered from the sign and from whether the field is a latitude.
+pub(crate) fn coordinate<T: Signed + PartialOrd + ToString>(value: T, name: &str) -> (String, String) {
+    let hemisphere = match (name.ends_with("latitude"), value < T::zero()) {
+        (true, false) => "N",
+        (true, true) => "S",
+        (false, false) => "E",
+        (false, true) => "W",
+    };
+    (value.abs().to_string(), hemisphere.to_string())
+}
+
+// The message type is recovered from the name of the data type, e.g. GgaData gives GGA.
+pub(crate) fn build(sender: &str, data_type: &str, fields: &[String]) -> NmeaBaseSentence {
+    let message_type = data_type.trim_end_matches("Data").to_uppercase();
+    NmeaBaseSentence::build(sender, &message_type, fields)
+}
+
+// NMEA reserves some characters for framing, so text fields carry them as ^ and two hex
+// digits, e.g. ^2C for a comma.  Characters outside ASCII go out as their UTF-8 bytes.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '$' | '*' | ',' | '!' | '\\' | '^' | '~' | '\x7f' | '\0'..='\x1f' => {
+                escaped.push_str(&format!("^{:02X}", c as u8));
+            }
+            c if !c.is_ascii() => {
+                let mut buffer = [0u8; 4];
+                c.encode_utf8(&mut buffer).bytes().for_each(|b| escaped.push_str(&format!("^{:02X}", b)));
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// The inverse of escape.  A ^ without two hex digits after it is kept as it is.  Escaped
+// bytes that are not UTF-8 are read as Latin-1, which is what older equipment sends.
+pub(crate) fn unescape(text: &str) -> String {
+    if !text.contains('^') {
+        return text.to_string();
+    }
+    let bytes = text.as_bytes();
+    let hex = |b: u8| (b as char).to_digit(16);
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if let [b'^', high, low, ..] = bytes[i..] {
+            if let (Some(high), Some(low)) = (hex(high), hex(low)) {
+                unescaped.push((high << 4 | low) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        unescaped.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8(unescaped).unwrap_or_else(|e| e.into_bytes().iter().map(|b| *b as char).collect())
+}
@@ -0,0 +1,708 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Datelike, SecondsFormat, Utc};
+use crate::*;
+use crate::units::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignalKValue {
+    Number(f64),
+    Text(String),
+    Position { latitude: f64, longitude: f64 },
+    Attitude { roll: Option<f64>, pitch: Option<f64>, yaw: Option<f64> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalKPathValue {
+    pub path: String,
+    pub value: SignalKValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalKUpdate {
+    pub label: String,
+    pub talker: String,
+    pub sentence: String,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub values: Vec<SignalKPathValue>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalKDelta {
+    pub context: String,
+    pub updates: Vec<SignalKUpdate>,
+}
+
+impl SignalKValue {
+    fn to_json(&self) -> String {
+        match self {
+            SignalKValue::Number(n) => json_number(*n),
+            SignalKValue::Text(t) => json_string(t),
+            SignalKValue::Position { latitude, longitude } =>
+                format!("{{\"latitude\":{},\"longitude\":{}}}", json_number(*latitude), json_number(*longitude)),
+            SignalKValue::Attitude { roll, pitch, yaw } => {
+                let parts = [("roll", roll), ("pitch", pitch), ("yaw", yaw)]
+                    .iter()
+                    .filter_map(|(name, v)| v.map(|v| format!("\"{}\":{}", name, json_number(v))))
+                    .collect::<Vec<String>>();
+                format!("{{{}}}", parts.join(","))
+            }
+        }
+    }
+
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            SignalKValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+impl SignalKUpdate {
+    // The Signal K "$source" reference, e.g. "nmea0183.II".
+    pub fn source_ref(&self) -> String {
+        format!("{}.{}", self.label, self.talker)
+    }
+
+    pub fn to_json(&self) -> String {
+        let values = self.values.iter()
+            .map(|v| format!("{{\"path\":{},\"value\":{}}}", json_string(&v.path), v.value.to_json()))
+            .collect::<Vec<String>>();
+        let timestamp = match self.timestamp {
+            Some(t) => format!(",\"timestamp\":{}", json_string(&t.to_rfc3339_opts(SecondsFormat::Millis, true))),
+            None => "".to_string(),
+        };
+        format!(
+            "{{\"source\":{{\"label\":{},\"type\":\"NMEA0183\",\"talker\":{},\"sentence\":{}}},\"$source\":{}{},\"values\":[{}]}}",
+            json_string(&self.label),
+            json_string(&self.talker),
+            json_string(&self.sentence),
+            json_string(&self.source_ref()),
+            timestamp,
+            values.join(","),
+        )
+    }
+}
+
+impl SignalKDelta {
+    pub fn to_json(&self) -> String {
+        let updates = self.updates.iter().map(|u| u.to_json()).collect::<Vec<String>>();
+        format!("{{\"context\":{},\"updates\":[{}]}}", json_string(&self.context), updates.join(","))
+    }
+}
+
+fn json_number(n: f64) -> String {
+    if n.is_finite() { n.to_string() } else { "null".to_string() }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn value(path: &str, value: SignalKValue) -> SignalKPathValue {
+    SignalKPathValue { path: path.to_string(), value }
+}
+
+fn number(path: &str, n: f64) -> SignalKPathValue {
+    value(path, SignalKValue::Number(n))
+}
+
+fn position(latitude: Option<ValueWithUnit<f32>>, longitude: Option<ValueWithUnit<f32>>) -> Option<SignalKPathValue> {
+    match (latitude, longitude) {
+        (Some(latitude), Some(longitude)) => Some(value("navigation.position", SignalKValue::Position {
+            latitude: decimal_degrees(&latitude),
+            longitude: decimal_degrees(&longitude),
+        })),
+        _ => None,
+    }
+}
+
+// Positive cross track error means the vessel is right of track and must steer left.
+fn cross_track_error(xte: Option<ValueWithUnit<f32>>, steer: Option<char>) -> Option<SignalKPathValue> {
+    let meters = distance_meters(&xte?)?;
+    let meters = if steer == Some('R') { -meters } else { meters };
+    Some(number("navigation.courseRhumbline.crossTrackError", meters))
+}
+
+fn apb_values(d: &ApbData) -> Vec<SignalKPathValue> {
+    let mut values = Vec::new();
+    values.extend(cross_track_error(d.xte(), d.steer_direction()));
+    for (bearing, true_path, magnetic_path) in [
+        (d.bearing_origin_to_destination(),
+         "navigation.courseRhumbline.bearingOriginToDestinationTrue",
+         "navigation.courseRhumbline.bearingOriginToDestinationMagnetic"),
+        (d.bearing_present_to_destination(),
+         "navigation.courseRhumbline.nextPoint.bearingTrue",
+         "navigation.courseRhumbline.nextPoint.bearingMagnetic"),
+        (d.heading_to_steer_to_waypoint(),
+         "steering.autopilot.target.headingTrue",
+         "steering.autopilot.target.headingMagnetic"),
+    ] {
+        if let Some(b) = bearing {
+            let path = if b.unit == 'M' { magnetic_path } else { true_path };
+            values.push(number(path, radians(b.value)));
+        }
+    }
+    if let Some(id) = d.destination_id() {
+        values.push(value("navigation.courseRhumbline.nextPoint.ID", SignalKValue::Text(id)));
+    }
+    values
+}
+
+fn dbt_values(d: &DbtData) -> Vec<SignalKPathValue> {
+    let depth = d.depth_meters()
+        .or_else(|| d.depth_feet())
+        .or_else(|| d.depth_fathoms())
+        .and_then(|v| distance_meters(&v));
+    depth.map(|m| vec![number("environment.depth.belowTransducer", m)]).unwrap_or_default()
+}
+
+fn dpt_values(d: &DptData) -> Vec<SignalKPathValue> {
+    let mut values = Vec::new();
+    if let Some(depth) = d.water_depth_meters() {
+        let depth = depth as f64;
+        values.push(number("environment.depth.belowTransducer", depth));
+        if let Some(offset) = d.transducer_offset() {
+            let offset = offset as f64;
+            if offset >= 0.0 {
+                values.push(number("environment.depth.surfaceToTransducer", offset));
+                values.push(number("environment.depth.belowSurface", depth + offset));
+            } else {
+                values.push(number("environment.depth.transducerToKeel", -offset));
+                values.push(number("environment.depth.belowKeel", depth + offset));
+            }
+        }
+    }
+    values
+}
+
+fn gga_values(d: &GgaData) -> Vec<SignalKPathValue> {
+    let mut values = Vec::new();
+    values.extend(position(d.latitude(), d.longitude()));
+    if let Some(q) = d.quality_indicator() {
+        let quality = match q {
+            0 => "no GPS",
+            1 => "GNSS Fix",
+            2 => "DGNSS fix",
+            3 => "Precise GNSS",
+            4 => "RTK fixed integer",
+            5 => "RTK float",
+            6 => "Estimated (DR) mode",
+            7 => "Manual input",
+            8 => "Simulator mode",
+            _ => "Error",
+        };
+        values.push(value("navigation.gnss.methodQuality", SignalKValue::Text(quality.to_string())));
+    }
+    if let Some(n) = d.satellites_in_use_count() {
+        values.push(number("navigation.gnss.satellites", n as f64));
+    }
+    if let Some(h) = d.hdop() {
+        values.push(number("navigation.gnss.horizontalDilution", h as f64));
+    }
+    if let Some(a) = d.antenna_altitude().and_then(|a| distance_meters(&a)) {
+        values.push(number("navigation.gnss.antennaAltitude", a));
+    }
+    if let Some(g) = d.geoidal_separation().and_then(|g| distance_meters(&g)) {
+        values.push(number("navigation.gnss.geoidalSeparation", g));
+    }
+    if let Some(a) = d.age_differential_gps_data() {
+        values.push(number("navigation.gnss.differentialAge", a as f64));
+    }
+    if let Some(r) = d.differential_station_id() {
+        values.push(number("navigation.gnss.differentialReference", r as f64));
+    }
+    values
+}
+
+fn gll_values(d: &GllData) -> Vec<SignalKPathValue> {
+    if d.status() == Some('V') {
+        return vec![];
+    }
+    position(d.latitude(), d.longitude()).into_iter().collect()
+}
+
+fn hdg_values(d: &HdgData) -> Vec<SignalKPathValue> {
+    let mut values = Vec::new();
+    if let Some(heading) = d.sensor_heading_degrees() {
+        let deviation = d.deviation().map(|v| signed_angle(&v));
+        values.push(number("navigation.headingMagnetic", radians(heading) + deviation.unwrap_or(0.0)));
+        if let Some(deviation) = deviation {
+            values.push(number("navigation.magneticDeviation", deviation));
+        }
+    }
+    if let Some(variation) = d.variation() {
+        values.push(number("navigation.magneticVariation", signed_angle(&variation)));
+    }
+    values
+}
+
+fn hdm_values(d: &HdmData) -> Vec<SignalKPathValue> {
+    d.heading_magnetic().map(|h| vec![number("navigation.headingMagnetic", radians(h.value))]).unwrap_or_default()
+}
+
+fn hdt_values(d: &HdtData) -> Vec<SignalKPathValue> {
+    d.heading_true().map(|h| vec![number("navigation.headingTrue", radians(h.value))]).unwrap_or_default()
+}
+
+fn mda_values(d: &MdaData) -> Vec<SignalKPathValue> {
+    let mut values = Vec::new();
+    if let Some(p) = d.barometric_pressure_bars().or_else(|| d.barometric_pressure_mercury()).and_then(|p| pascal(&p)) {
+        values.push(number("environment.outside.pressure", p));
+    }
+    if let Some(t) = d.air_temperature_c().and_then(|t| kelvin(&t)) {
+        values.push(number("environment.outside.temperature", t));
+    }
+    if let Some(t) = d.water_temperature_c().and_then(|t| kelvin(&t)) {
+        values.push(number("environment.water.temperature", t));
+    }
+    if let Some(h) = d.relative_humidity() {
+        values.push(number("environment.outside.relativeHumidity", h as f64 / 100.0));
+    }
+    if let Some(t) = d.dew_point_c().and_then(|t| kelvin(&t)) {
+        values.push(number("environment.outside.dewPointTemperature", t));
+    }
+    if let Some(w) = d.wind_direction_true() {
+        values.push(number("environment.wind.directionTrue", radians(w.value)));
+    }
+    if let Some(w) = d.wind_direction_magnetic() {
+        values.push(number("environment.wind.directionMagnetic", radians(w.value)));
+    }
+    if let Some(s) = d.wind_speed_mps().or_else(|| d.wind_speed_knots()).and_then(|s| speed_mps(&s)) {
+        values.push(number("environment.wind.speedTrue", s));
+    }
+    values
+}
+
+fn mtw_values(d: &MtwData) -> Vec<SignalKPathValue> {
+    d.temperature().and_then(|t| kelvin(&t))
+        .map(|t| vec![number("environment.water.temperature", t)])
+        .unwrap_or_default()
+}
+
+fn mwd_values(d: &MwdData) -> Vec<SignalKPathValue> {
+    let mut values = Vec::new();
+    if let Some(w) = d.wind_direction_true() {
+        values.push(number("environment.wind.directionTrue", radians(w.value)));
+    }
+    if let Some(w) = d.wind_direction_magnetic() {
+        values.push(number("environment.wind.directionMagnetic", radians(w.value)));
+    }
+    if let Some(s) = d.wind_speed_mps().or_else(|| d.wind_speed_knots()).and_then(|s| speed_mps(&s)) {
+        values.push(number("environment.wind.speedTrue", s));
+    }
+    values
+}
+
+fn mwv_values(d: &MwvData) -> Vec<SignalKPathValue> {
+    if d.status() != Some('A') {
+        return vec![];
+    }
+    let (angle_path, speed_path) = match d.wind_angle().map(|a| a.unit) {
+        Some('R') => ("environment.wind.angleApparent", "environment.wind.speedApparent"),
+        Some('T') => ("environment.wind.angleTrueWater", "environment.wind.speedTrue"),
+        _ => return vec![],
+    };
+    let mut values = Vec::new();
+    if let Some(a) = d.wind_angle() {
+        values.push(number(angle_path, relative_radians(a.value)));
+    }
+    if let Some(s) = d.wind_speed().and_then(|s| speed_mps(&s)) {
+        values.push(number(speed_path, s));
+    }
+    values
+}
+
+fn rmb_values(d: &RmbData) -> Vec<SignalKPathValue> {
+    let mut values = Vec::new();
+    if d.status() == Some('V') {
+        return values;
+    }
+    if let Some(xte) = d.xte() {
+        let meters = xte as f64 * NAUTICAL_MILE_METERS;
+        let meters = if d.direction_to_steer() == Some('R') { -meters } else { meters };
+        values.push(number("navigation.courseRhumbline.crossTrackError", meters));
+    }
+    if let (Some(latitude), Some(longitude)) = (d.destination_latitude(), d.destination_longitude()) {
+        values.push(value("navigation.courseRhumbline.nextPoint.position", SignalKValue::Position {
+            latitude: decimal_degrees(&latitude),
+            longitude: decimal_degrees(&longitude),
+        }));
+    }
+    if let Some(r) = d.destination_range() {
+        values.push(number("navigation.courseRhumbline.nextPoint.distance", r as f64 * NAUTICAL_MILE_METERS));
+    }
+    if let Some(b) = d.destination_bearing() {
+        values.push(number("navigation.courseRhumbline.nextPoint.bearingTrue", radians(b)));
+    }
+    if let Some(v) = d.vmg_knots() {
+        values.push(number("navigation.courseRhumbline.nextPoint.velocityMadeGood", v as f64 * KNOTS_TO_MPS));
+    }
+    if let Some(id) = d.destination_waypoint_id() {
+        values.push(value("navigation.courseRhumbline.nextPoint.ID", SignalKValue::Text(id)));
+    }
+    values
+}
+
+fn rmc_values(d: &RmcData) -> Vec<SignalKPathValue> {
+    let mut values = Vec::new();
+    if d.status() == Some('V') {
+        return values;
+    }
+    values.extend(position(d.latitude(), d.longitude()));
+    if let Some(c) = d.track_made_good_true() {
+        values.push(number("navigation.courseOverGroundTrue", radians(c)));
+    }
+    if let Some(s) = d.sog_knots() {
+        values.push(number("navigation.speedOverGround", s as f64 * KNOTS_TO_MPS));
+    }
+    if let Some(v) = d.variation() {
+        values.push(number("navigation.magneticVariation", signed_angle(&v)));
+    }
+    if let Some(t) = datetime(d.date(), d.time()) {
+        values.push(value("navigation.datetime", SignalKValue::Text(t.to_rfc3339_opts(SecondsFormat::Millis, true))));
+    }
+    values
+}
+
+fn rot_values(d: &RotData) -> Vec<SignalKPathValue> {
+    if d.valid() != Some('A') {
+        return vec![];
+    }
+    // Rate of turn is degrees per minute.
+    d.rate().map(|r| vec![number("navigation.rateOfTurn", radians(r) / 60.0)]).unwrap_or_default()
+}
+
+fn rsa_values(d: &RsaData) -> Vec<SignalKPathValue> {
+    if d.starboard_status() != Some('A') {
+        return vec![];
+    }
+    d.starboard_angle().map(|a| vec![number("steering.rudderAngle", radians(a))]).unwrap_or_default()
+}
+
+fn vhw_values(d: &VhwData) -> Vec<SignalKPathValue> {
+    let mut values = Vec::new();
+    if let Some(h) = d.heading_true() {
+        values.push(number("navigation.headingTrue", radians(h.value)));
+    }
+    if let Some(h) = d.heading_magnetic() {
+        values.push(number("navigation.headingMagnetic", radians(h.value)));
+    }
+    if let Some(s) = d.stw_knots().or_else(|| d.stw_kph()).and_then(|s| speed_mps(&s)) {
+        values.push(number("navigation.speedThroughWater", s));
+    }
+    values
+}
+
+fn vlw_values(d: &VlwData) -> Vec<SignalKPathValue> {
+    let mut values = Vec::new();
+    if let Some(l) = d.water_distance_total().and_then(|l| distance_meters(&l)) {
+        values.push(number("navigation.log", l));
+    }
+    if let Some(l) = d.water_distance_since_reset().and_then(|l| distance_meters(&l)) {
+        values.push(number("navigation.trip.log", l));
+    }
+    values
+}
+
+fn vtg_values(d: &VtgData) -> Vec<SignalKPathValue> {
+    let mut values = Vec::new();
+    if d.mode() == Some('N') {
+        return values;
+    }
+    if let Some(c) = d.cog_true() {
+        values.push(number("navigation.courseOverGroundTrue", radians(c.value)));
+    }
+    if let Some(c) = d.cog_magnetic() {
+        values.push(number("navigation.courseOverGroundMagnetic", radians(c.value)));
+    }
+    if let Some(s) = d.sog_knots().or_else(|| d.sog_kph()).and_then(|s| speed_mps(&s)) {
+        values.push(number("navigation.speedOverGround", s));
+    }
+    values
+}
+
+fn xdr_values(d: &XdrData) -> Vec<SignalKPathValue> {
+    let mut values = Vec::new();
+    let (mut roll, mut pitch, mut yaw) = (None, None, None);
+    for m in d.measurements().unwrap_or_default() {
+        let name = m.name.to_uppercase();
+        match (m.transducer_type, name.as_str()) {
+            ('A', "HEEL") | ('A', "ROLL") => roll = Some(radians(m.data.value)),
+            ('A', "TRIM") | ('A', "PITCH") | ('A', "PTCH") => pitch = Some(radians(m.data.value)),
+            ('A', "YAW") => yaw = Some(radians(m.data.value)),
+            ('A', "RUDDER") => values.push(number("steering.rudderAngle", radians(m.data.value))),
+            ('C', "AIRTEMP") | ('C', "ENV_OUTAIR_T") | ('C', "ENV_OUTSIDE_T") => {
+                values.extend(kelvin(&m.data).map(|t| number("environment.outside.temperature", t)))
+            }
+            ('C', "WATERTEMP") | ('C', "ENV_WATER_T") => {
+                values.extend(kelvin(&m.data).map(|t| number("environment.water.temperature", t)))
+            }
+            ('P', "BARO") => values.extend(pascal(&m.data).map(|p| number("environment.outside.pressure", p))),
+            ('H', _) => values.push(number("environment.outside.relativeHumidity", m.data.value as f64 / 100.0)),
+            _ => {}
+        }
+    }
+    if roll.is_some() || pitch.is_some() || yaw.is_some() {
+        values.push(value("navigation.attitude", SignalKValue::Attitude { roll, pitch, yaw }));
+    }
+    values
+}
+
+fn xte_values(d: &XteData) -> Vec<SignalKPathValue> {
+    if d.status() == Some('V') {
+        return vec![];
+    }
+    cross_track_error(d.xte(), d.direction_to_steer()).into_iter().collect()
+}
+
+fn zda_values(d: &ZdaData) -> Vec<SignalKPathValue> {
+    d.datetime_utc()
+        .map(|t| vec![value("navigation.datetime", SignalKValue::Text(t.to_rfc3339_opts(SecondsFormat::Millis, true)))])
+        .unwrap_or_default()
+}
+
+fn update<T: NmeaBaseTrait>(label: &str, data: &T, timestamp: Option<DateTime<Utc>>, values: Vec<SignalKPathValue>) -> SignalKUpdate {
+    SignalKUpdate {
+        label: label.to_string(),
+        talker: data.talker().to_string(),
+        sentence: data.message().clone(),
+        timestamp,
+        values,
+    }
+}
+
+pub struct SignalKConverter {
+    context: String,
+    label: String,
+}
+
+impl Default for SignalKConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SignalKConverter {
+    pub fn new() -> Self {
+        Self {
+            context: "vessels.self".to_string(),
+            label: "nmea0183".to_string(),
+        }
+    }
+
+    pub fn with_context(mut self, context: &str) -> Self {
+        self.context = context.to_string();
+        self
+    }
+
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = label.to_string();
+        self
+    }
+
+    // None if the sentence type is not mapped or carries no usable values.
+    pub fn delta(&self, sentence: &NmeaSentence) -> Option<SignalKDelta> {
+        let label = self.label.as_str();
+        let update = match sentence {
+            NmeaSentence::APB(d) => update(label, d, None, apb_values(d)),
+            NmeaSentence::DBT(d) => update(label, d, None, dbt_values(d)),
+            NmeaSentence::DPT(d) => update(label, d, None, dpt_values(d)),
+            NmeaSentence::GGA(d) => update(label, d, None, gga_values(d)),
+            NmeaSentence::GLL(d) => update(label, d, None, gll_values(d)),
+            NmeaSentence::HDG(d) => update(label, d, None, hdg_values(d)),
+            NmeaSentence::HDM(d) => update(label, d, None, hdm_values(d)),
+            NmeaSentence::HDT(d) => update(label, d, None, hdt_values(d)),
+            NmeaSentence::MDA(d) => update(label, d, None, mda_values(d)),
+            NmeaSentence::MTW(d) => update(label, d, None, mtw_values(d)),
+            NmeaSentence::MWD(d) => update(label, d, None, mwd_values(d)),
+            NmeaSentence::MWV(d) => update(label, d, None, mwv_values(d)),
+            NmeaSentence::RMB(d) => update(label, d, None, rmb_values(d)),
+            NmeaSentence::RMC(d) => update(label, d, datetime(d.date(), d.time()), rmc_values(d)),
+            NmeaSentence::ROT(d) => update(label, d, None, rot_values(d)),
+            NmeaSentence::RSA(d) => update(label, d, None, rsa_values(d)),
+            NmeaSentence::VHW(d) => update(label, d, None, vhw_values(d)),
+            NmeaSentence::VLW(d) => update(label, d, None, vlw_values(d)),
+            NmeaSentence::VTG(d) => update(label, d, None, vtg_values(d)),
+            NmeaSentence::XDR(d) => update(label, d, None, xdr_values(d)),
+            NmeaSentence::XTE(d) => update(label, d, None, xte_values(d)),
+            NmeaSentence::ZDA(d) => update(label, d, d.datetime_utc(), zda_values(d)),
+            _ => return None,
+        };
+        if update.values.is_empty() {
+            return None;
+        }
+        Some(SignalKDelta {
+            context: self.context.clone(),
+            updates: vec![update],
+        })
+    }
+
+    // The reverse direction: builds the NMEA sentences that carry the given Signal K values.
+    // Values that travel together (wind angle and speed, course and speed) are combined into
+    // one sentence; paths without an NMEA 0183 equivalent are ignored.
+    pub fn to_sentences(&self, talker: &str, values: &[SignalKPathValue]) -> Vec<NmeaBaseSentence> {
+        let map: HashMap<&str, &SignalKValue> = values.iter().map(|v| (v.path.as_str(), &v.value)).collect();
+        let n = |path: &str| map.get(path).and_then(|v| v.as_number());
+        let sender = format!("${}", talker);
+        let mut sentences = Vec::new();
+        let mut build = |message_type: &str, fields: Vec<String>| {
+            sentences.push(NmeaBaseSentence::build(&sender, message_type, &fields));
+        };
+
+        if let Some(heading) = n("navigation.headingMagnetic") {
+            let (variation, direction) = match n("navigation.magneticVariation") {
+                Some(v) => (format_number(v.to_degrees().abs(), 1), if v < 0.0 { "W" } else { "E" }.to_string()),
+                None => ("".to_string(), "".to_string()),
+            };
+            build("HDG", vec![format_angle(heading), "".to_string(), "".to_string(), variation, direction]);
+        }
+        if let Some(heading) = n("navigation.headingTrue") {
+            build("HDT", vec![format_angle(heading), "T".to_string()]);
+        }
+        if let Some(speed) = n("navigation.speedThroughWater") {
+            build("VHW", vec![
+                n("navigation.headingTrue").map(format_angle).unwrap_or_default(), "T".to_string(),
+                n("navigation.headingMagnetic").map(format_angle).unwrap_or_default(), "M".to_string(),
+                format_number(speed / KNOTS_TO_MPS, 2), "N".to_string(),
+                format_number(speed * 3.6, 2), "K".to_string(),
+            ]);
+        }
+        for (angle_path, speed_path, reference) in [
+            ("environment.wind.angleApparent", "environment.wind.speedApparent", "R"),
+            ("environment.wind.angleTrueWater", "environment.wind.speedTrue", "T"),
+        ] {
+            if let Some(angle) = n(angle_path) {
+                build("MWV", vec![
+                    format_angle(angle), reference.to_string(),
+                    n(speed_path).map(|s| format_number(s / KNOTS_TO_MPS, 1)).unwrap_or_default(), "N".to_string(),
+                    "A".to_string(),
+                ]);
+            }
+        }
+        if let Some(direction) = n("environment.wind.directionTrue") {
+            let speed = n("environment.wind.speedTrue");
+            build("MWD", vec![
+                format_angle(direction), "T".to_string(),
+                n("environment.wind.directionMagnetic").map(format_angle).unwrap_or_default(), "M".to_string(),
+                speed.map(|s| format_number(s / KNOTS_TO_MPS, 1)).unwrap_or_default(), "N".to_string(),
+                speed.map(|s| format_number(s, 1)).unwrap_or_default(), "M".to_string(),
+            ]);
+        }
+        if let Some(depth) = n("environment.depth.belowTransducer") {
+            let offset = n("environment.depth.surfaceToTransducer")
+                .or_else(|| n("environment.depth.transducerToKeel").map(|k| -k));
+            build("DPT", vec![format_number(depth, 1), offset.map(|o| format_number(o, 1)).unwrap_or_default()]);
+        }
+        if let Some(temperature) = n("environment.water.temperature") {
+            build("MTW", vec![format_number(temperature - KELVIN_OFFSET, 1), "C".to_string()]);
+        }
+        let pressure = n("environment.outside.pressure");
+        let air_temperature = n("environment.outside.temperature");
+        let humidity = n("environment.outside.relativeHumidity");
+        if pressure.is_some() || air_temperature.is_some() || humidity.is_some() {
+            build("MDA", vec![
+                pressure.map(|p| format_number(p / INCH_MERCURY_TO_PASCAL, 2)).unwrap_or_default(), "I".to_string(),
+                pressure.map(|p| format_number(p / BAR_TO_PASCAL, 4)).unwrap_or_default(), "B".to_string(),
+                air_temperature.map(|t| format_number(t - KELVIN_OFFSET, 1)).unwrap_or_default(), "C".to_string(),
+                "".to_string(), "C".to_string(),
+                humidity.map(|h| format_number(h * 100.0, 1)).unwrap_or_default(),
+                "".to_string(),
+                n("environment.outside.dewPointTemperature").map(|t| format_number(t - KELVIN_OFFSET, 1)).unwrap_or_default(), "C".to_string(),
+                "".to_string(), "T".to_string(),
+                "".to_string(), "M".to_string(),
+                "".to_string(), "N".to_string(),
+                "".to_string(), "M".to_string(),
+            ]);
+        }
+        let time = match map.get("navigation.datetime") {
+            Some(SignalKValue::Text(t)) => DateTime::parse_from_rfc3339(t).ok().map(|t| t.with_timezone(&Utc)),
+            _ => None,
+        };
+        if let Some(SignalKValue::Position { latitude, longitude }) = map.get("navigation.position") {
+            let (lat, lat_hemisphere) = format_coordinate(*latitude, 2, 'N', 'S');
+            let (lon, lon_hemisphere) = format_coordinate(*longitude, 3, 'E', 'W');
+            build("GLL", vec![
+                lat.clone(), lat_hemisphere.clone(), lon.clone(), lon_hemisphere.clone(),
+                time.map(|t| format_time(&t)).unwrap_or_default(), "A".to_string(), "A".to_string(),
+            ]);
+            if let Some(t) = time {
+                let variation = n("navigation.magneticVariation");
+                build("RMC", vec![
+                    format_time(&t), "A".to_string(), lat, lat_hemisphere, lon, lon_hemisphere,
+                    n("navigation.speedOverGround").map(|s| format_number(s / KNOTS_TO_MPS, 1)).unwrap_or_default(),
+                    n("navigation.courseOverGroundTrue").map(format_angle).unwrap_or_default(),
+                    format!("{:02}{:02}{:02}", t.day(), t.month(), t.year() % 100),
+                    variation.map(|v| format_number(v.to_degrees().abs(), 1)).unwrap_or_default(),
+                    variation.map(|v| if v < 0.0 { "W" } else { "E" }.to_string()).unwrap_or_default(),
+                    "A".to_string(),
+                ]);
+            }
+        }
+        let cog = n("navigation.courseOverGroundTrue");
+        let sog = n("navigation.speedOverGround");
+        if cog.is_some() || sog.is_some() {
+            build("VTG", vec![
+                cog.map(format_angle).unwrap_or_default(), "T".to_string(),
+                n("navigation.courseOverGroundMagnetic").map(format_angle).unwrap_or_default(), "M".to_string(),
+                sog.map(|s| format_number(s / KNOTS_TO_MPS, 1)).unwrap_or_default(), "N".to_string(),
+                sog.map(|s| format_number(s * 3.6, 1)).unwrap_or_default(), "K".to_string(),
+                "A".to_string(),
+            ]);
+        }
+        if let Some(rudder) = n("steering.rudderAngle") {
+            build("RSA", vec![format_number(rudder.to_degrees(), 1), "A".to_string(), "".to_string(), "".to_string()]);
+        }
+        if let Some(rate) = n("navigation.rateOfTurn") {
+            build("ROT", vec![format_number(rate.to_degrees() * 60.0, 1), "A".to_string()]);
+        }
+        if let Some(xte) = n("navigation.courseRhumbline.crossTrackError") {
+            build("XTE", vec![
+                "A".to_string(), "A".to_string(),
+                format_number(xte.abs() / NAUTICAL_MILE_METERS, 3),
+                if xte < 0.0 { "R" } else { "L" }.to_string(),
+                "N".to_string(), "A".to_string(),
+            ]);
+        }
+        let log = n("navigation.log");
+        let trip = n("navigation.trip.log");
+        if log.is_some() || trip.is_some() {
+            build("VLW", vec![
+                log.map(|l| format_number(l / NAUTICAL_MILE_METERS, 1)).unwrap_or_default(), "N".to_string(),
+                trip.map(|l| format_number(l / NAUTICAL_MILE_METERS, 1)).unwrap_or_default(), "N".to_string(),
+            ]);
+        }
+        if let Some(SignalKValue::Attitude { roll, pitch, yaw }) = map.get("navigation.attitude") {
+            let mut fields = Vec::new();
+            for (name, angle) in [("ROLL", roll), ("PITCH", pitch), ("YAW", yaw)] {
+                if let Some(a) = angle {
+                    fields.extend(["A".to_string(), format_number(a.to_degrees(), 1), "D".to_string(), name.to_string()]);
+                }
+            }
+            if !fields.is_empty() {
+                build("XDR", fields);
+            }
+        }
+        if let Some(t) = time {
+            build("ZDA", vec![
+                format_time(&t),
+                format!("{:02}", t.day()), format!("{:02}", t.month()), t.year().to_string(),
+                "00".to_string(), "00".to_string(),
+            ]);
+        }
+        sentences
+    }
+}
+
@@ -0,0 +1,420 @@
+use crate::*;
+
+// What a DOR, FIR or WAT sentence reports on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportType {
+    SectionStatus,
+    Event,
+    Fault,
+}
+
+impl ReportType {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'S' => Some(ReportType::SectionStatus),
+            'E' => Some(ReportType::Event),
+            'F' => Some(ReportType::Fault),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoorPanel {
+    Watertight,
+    SemiWatertight,
+    FireDoor,
+    HullDoor,
+    Other,
+}
+
+impl DoorPanel {
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "WT" => Some(DoorPanel::Watertight),
+            "WS" => Some(DoorPanel::SemiWatertight),
+            "FD" => Some(DoorPanel::FireDoor),
+            "HD" => Some(DoorPanel::HullDoor),
+            "OT" => Some(DoorPanel::Other),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoorStatus {
+    Open,
+    Closed,
+    Secured,
+    // A watertight door set free to be opened locally.
+    Free,
+    Fault,
+}
+
+impl DoorStatus {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'O' => Some(DoorStatus::Open),
+            'C' => Some(DoorStatus::Closed),
+            'S' => Some(DoorStatus::Secured),
+            'F' => Some(DoorStatus::Free),
+            'X' => Some(DoorStatus::Fault),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatertightSetting {
+    // Doors may be open.
+    Harbour,
+    // Doors are ordered closed.
+    Sea,
+}
+
+impl WatertightSetting {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'O' => Some(WatertightSetting::Harbour),
+            'C' => Some(WatertightSetting::Sea),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FireDetector {
+    Generic,
+    Heat,
+    Smoke,
+    ManualCallPoint,
+    Gas,
+    Oxygen,
+    HydrogenSulphide,
+    Hydrocarbon,
+    SprinklerFlow,
+    SprinklerValve,
+    Co2Release,
+    Other,
+}
+
+impl FireDetector {
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "FD" => Some(FireDetector::Generic),
+            "FH" => Some(FireDetector::Heat),
+            "FS" => Some(FireDetector::Smoke),
+            "FM" => Some(FireDetector::ManualCallPoint),
+            "GD" => Some(FireDetector::Gas),
+            "GO" => Some(FireDetector::Oxygen),
+            "GS" => Some(FireDetector::HydrogenSulphide),
+            "GH" => Some(FireDetector::Hydrocarbon),
+            "SF" => Some(FireDetector::SprinklerFlow),
+            "SV" => Some(FireDetector::SprinklerValve),
+            "CO" => Some(FireDetector::Co2Release),
+            "OT" => Some(FireDetector::Other),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FireCondition {
+    Activated,
+    NotActivated,
+    Fault,
+}
+
+impl FireCondition {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'A' => Some(FireCondition::Activated),
+            'V' => Some(FireCondition::NotActivated),
+            'X' => Some(FireCondition::Fault),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaterSystem {
+    WaterLevel,
+    HighBilge,
+    LowBilge,
+    Other,
+}
+
+impl WaterSystem {
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "WL" => Some(WaterSystem::WaterLevel),
+            "HB" => Some(WaterSystem::HighBilge),
+            "LB" => Some(WaterSystem::LowBilge),
+            "OT" => Some(WaterSystem::Other),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaterCondition {
+    Normal,
+    High,
+    VeryHigh,
+    Low,
+    VeryLow,
+    Other,
+}
+
+impl WaterCondition {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'N' => Some(WaterCondition::Normal),
+            'H' => Some(WaterCondition::High),
+            'J' => Some(WaterCondition::VeryHigh),
+            'L' => Some(WaterCondition::Low),
+            'K' => Some(WaterCondition::VeryLow),
+            'X' => Some(WaterCondition::Other),
+            _ => None,
+        }
+    }
+}
+
+impl<B: FieldSource> DorData<B> {
+    pub fn report_type(&self) -> Option<ReportType> {
+        ReportType::from_char(self.message_type()?)
+    }
+
+    pub fn panel(&self) -> Option<DoorPanel> {
+        DoorPanel::from_code(&self.panel_type()?)
+    }
+
+    pub fn door_status(&self) -> Option<DoorStatus> {
+        DoorStatus::from_char(self.status()?)
+    }
+
+    pub fn watertight_setting(&self) -> Option<WatertightSetting> {
+        WatertightSetting::from_char(self.switch_setting()?)
+    }
+}
+
+impl<B: FieldSource> FirData<B> {
+    pub fn report_type(&self) -> Option<ReportType> {
+        ReportType::from_char(self.message_type()?)
+    }
+
+    pub fn detector(&self) -> Option<FireDetector> {
+        FireDetector::from_code(&self.detection_type()?)
+    }
+
+    pub fn fire_condition(&self) -> Option<FireCondition> {
+        FireCondition::from_char(self.condition()?)
+    }
+
+    pub fn acknowledged(&self) -> Option<bool> {
+        self.acknowledgement().map(|a| a == 'A')
+    }
+}
+
+impl<B: FieldSource> WatData<B> {
+    pub fn report_type(&self) -> Option<ReportType> {
+        ReportType::from_char(self.message_type()?)
+    }
+
+    pub fn water_system(&self) -> Option<WaterSystem> {
+        WaterSystem::from_code(&self.system_type()?)
+    }
+
+    pub fn water_condition(&self) -> Option<WaterCondition> {
+        WaterCondition::from_char(self.condition()?)
+    }
+
+    pub fn overridden(&self) -> Option<bool> {
+        self.override_setting().map(|o| o == 'O')
+    }
+}
+
+// The true heading of one HMR sensor.  A magnetic sensor is corrected by its deviation and
+// the variation, east being added.
+fn true_heading(heading: Option<f32>, status: Option<char>, kind: Option<char>, deviation: Option<f32>, variation: Option<ValueWithUnit<f32>>) -> Option<f32> {
+    if status == Some('V') {
+        return None;
+    }
+    let heading = heading?;
+    let heading = match kind {
+        Some('M') => {
+            let variation = variation.map_or(0.0, |v| if v.unit() == 'W' { -v.value() } else { v.value() });
+            heading + deviation.unwrap_or(0.0) + variation
+        }
+        _ => heading,
+    };
+    Some((heading % 360.0 + 360.0) % 360.0)
+}
+
+impl<B: FieldSource> HmrData<B> {
+    pub fn sensor_1_true_heading(&self) -> Option<f32> {
+        true_heading(self.sensor_1_heading(), self.sensor_1_status(), self.sensor_1_type(), self.sensor_1_deviation(), self.sensor_1_variation())
+    }
+
+    pub fn sensor_2_true_heading(&self) -> Option<f32> {
+        true_heading(self.sensor_2_heading(), self.sensor_2_status(), self.sensor_2_type(), self.sensor_2_deviation(), self.sensor_2_variation())
+    }
+
+    // The difference between the sensors' true headings, 0 to 180 degrees, worked out
+    // rather than taken from actual_difference.
+    pub fn difference(&self) -> Option<f32> {
+        let difference = (self.sensor_1_true_heading()? - self.sensor_2_true_heading()?).abs();
+        Some(if difference > 180.0 { 360.0 - difference } else { difference })
+    }
+
+    // Whether the difference is over the set one.  The warning flag is used when the
+    // headings cannot be compared.
+    pub fn limit_exceeded(&self) -> Option<bool> {
+        match (self.difference(), self.set_difference()) {
+            (Some(difference), Some(limit)) => Some(difference > limit),
+            _ => self.warning().map(|w| w == 'V'),
+        }
+    }
+}
+
+// Where a door, detector or detection point is: its panel or system type, the first and
+// second division, and its number.  The number is None for a section status.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    pub system: String,
+    pub division: String,
+    pub subdivision: Option<u16>,
+    pub number: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SystemEvent {
+    Door(Location, DoorStatus),
+    WatertightSetting(WatertightSetting),
+    Fire(Location, FireCondition),
+    Water(Location, WaterCondition),
+    // A DOR, FIR or WAT reporting a fault in the system itself; the sentence type and
+    // description.
+    Fault(MessageType, Option<String>),
+    // The heading sensors have gone over, or come back within, the set difference.
+    HeadingDifference(bool),
+}
+
+// The state of the doors, fire detectors and water level alarms on board, and of the
+// heading monitor, from DOR, FIR, WAT, HMS and HMR.  A section status is kept under the
+// section, without a number.  update() returns what changed.
+#[derive(Debug, Clone, Default)]
+pub struct ShipSystems {
+    doors: BTreeMap<Location, DoorStatus>,
+    fires: BTreeMap<Location, FireCondition>,
+    water: BTreeMap<Location, WaterCondition>,
+    pub watertight_setting: Option<WatertightSetting>,
+    pub heading_difference: Option<f32>,
+    pub heading_limit: Option<f32>,
+    pub heading_limit_exceeded: Option<bool>,
+}
+
+impl ShipSystems {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn doors(&self) -> impl Iterator<Item = (&Location, &DoorStatus)> {
+        self.doors.iter()
+    }
+
+    pub fn fires(&self) -> impl Iterator<Item = (&Location, &FireCondition)> {
+        self.fires.iter()
+    }
+
+    pub fn water(&self) -> impl Iterator<Item = (&Location, &WaterCondition)> {
+        self.water.iter()
+    }
+
+    pub fn open_doors(&self) -> impl Iterator<Item = &Location> {
+        self.doors.iter().filter(|(_, s)| matches!(s, DoorStatus::Open | DoorStatus::Free)).map(|(l, _)| l)
+    }
+
+    pub fn active_fires(&self) -> impl Iterator<Item = &Location> {
+        self.fires.iter().filter(|(_, c)| **c == FireCondition::Activated).map(|(l, _)| l)
+    }
+
+    pub fn water_alarms(&self) -> impl Iterator<Item = &Location> {
+        self.water.iter().filter(|(_, c)| **c != WaterCondition::Normal).map(|(l, _)| l)
+    }
+
+    pub fn update<B: FieldSource>(&mut self, sentence: &NmeaSentence<B>) -> Vec<SystemEvent> {
+        let mut events = vec![];
+        match sentence {
+            NmeaSentence::DOR(dor) => {
+                if let Some(setting) = dor.watertight_setting() {
+                    if self.watertight_setting != Some(setting) {
+                        events.push(SystemEvent::WatertightSetting(setting));
+                    }
+                    self.watertight_setting = Some(setting);
+                }
+                let location = location(dor.report_type(), dor.panel_type(), dor.division(), dor.subdivision(), dor.door_number());
+                match (dor.report_type(), location, dor.door_status()) {
+                    (Some(ReportType::Fault), _, _) => events.push(SystemEvent::Fault(MessageType::DOR, dor.description())),
+                    (_, Some(location), Some(status)) => {
+                        events.extend(record(&mut self.doors, location, status).map(|l| SystemEvent::Door(l, status)));
+                    }
+                    _ => {}
+                }
+            }
+            NmeaSentence::FIR(fir) => {
+                let location = location(fir.report_type(), fir.detection_type(), fir.zone(), fir.detector_loop(), fir.detector_number());
+                match (fir.report_type(), location, fir.fire_condition()) {
+                    (Some(ReportType::Fault), _, _) => events.push(SystemEvent::Fault(MessageType::FIR, fir.description())),
+                    (_, Some(location), Some(condition)) => {
+                        events.extend(record(&mut self.fires, location, condition).map(|l| SystemEvent::Fire(l, condition)));
+                    }
+                    _ => {}
+                }
+            }
+            NmeaSentence::WAT(wat) => {
+                let location = location(wat.report_type(), wat.system_type(), wat.location(), wat.sublocation(), wat.point_number());
+                match (wat.report_type(), location, wat.water_condition()) {
+                    (Some(ReportType::Fault), _, _) => events.push(SystemEvent::Fault(MessageType::WAT, wat.description())),
+                    (_, Some(location), Some(condition)) => {
+                        events.extend(record(&mut self.water, location, condition).map(|l| SystemEvent::Water(l, condition)));
+                    }
+                    _ => {}
+                }
+            }
+            NmeaSentence::HMS(hms) => {
+                self.heading_limit = hms.maximum_difference().or(self.heading_limit);
+            }
+            NmeaSentence::HMR(hmr) => {
+                self.heading_limit = hmr.set_difference().or(self.heading_limit);
+                self.heading_difference = hmr.difference().or(hmr.actual_difference());
+                let exceeded = match (self.heading_difference, self.heading_limit) {
+                    (Some(difference), Some(limit)) => Some(difference > limit),
+                    _ => hmr.limit_exceeded(),
+                };
+                if let Some(exceeded) = exceeded {
+                    if self.heading_limit_exceeded.unwrap_or(false) != exceeded {
+                        events.push(SystemEvent::HeadingDifference(exceeded));
+                    }
+                    self.heading_limit_exceeded = Some(exceeded);
+                }
+            }
+            _ => {}
+        }
+        events
+    }
+}
+
+fn location(report: Option<ReportType>, system: Option<String>, division: Option<String>, subdivision: Option<u16>, number: Option<u16>) -> Option<Location> {
+    Some(Location {
+        system: system?,
+        division: division.unwrap_or_default(),
+        subdivision,
+        number: if report == Some(ReportType::SectionStatus) { None } else { number },
+    })
+}
+
+// Stores the state, giving back the location if it changed.
+fn record<T: Copy + PartialEq>(states: &mut BTreeMap<Location, T>, location: Location, state: T) -> Option<Location> {
+    (states.insert(location.clone(), state) != Some(state)).then_some(location)
+}
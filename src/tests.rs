@@ -1,5 +1,6 @@
 use super::*;
 use std::io::{BufRead, BufReader};
+use crate::csv::CsvRecord;
 use std::{assert_eq, fs};
 
 fn create_test_val_1() -> NmeaBaseSentence {
@@ -42,6 +43,7 @@ fn test_string() {
 }
 
 #[test]
+#[allow(clippy::excessive_precision)]
 fn test_f32() {
     let testval = create_test_val_1();
     let n = testval.get::<f32>(1);
@@ -128,6 +130,1209 @@ fn big_test() {
             assert_ne!(sentence.nfields(), 0);
         }
     } else {
-        assert!(false)
+        panic!("Could not open test.log")
     }
 }
+
+// The seconds start at the fifth character of hhmmss; reading from the sixth lost their
+// tens digit, so 17 seconds came back as 7.
+#[test]
+fn test_time_seconds_tens_digit() {
+    let base = NmeaBaseSentence::from(&"$GPZDA,173617,235959.50,000010.25,1736*4C".to_string());
+    assert_eq!(base.get_time(0), NaiveTime::from_hms_opt(17, 36, 17));
+    assert_eq!(base.get_time(1), NaiveTime::from_hms_milli_opt(23, 59, 59, 500));
+    assert_eq!(base.get_time(2), NaiveTime::from_hms_milli_opt(0, 0, 10, 250));
+    assert_eq!(base.get_time(3), None);
+}
+
+#[test]
+fn test_borrowed_matches_owned() {
+    let f = fs::File::open("test.log").unwrap();
+    for line in BufReader::new(f).lines() {
+        let line = line.unwrap();
+        let borrowed = NmeaSentenceRef::from(line.as_str());
+        let owned = NmeaBaseSentence::from(&line);
+        assert_eq!(borrowed.sender(), owned.sender());
+        assert_eq!(borrowed.message_type(), owned.message_type());
+        assert_eq!(borrowed.checksum(), owned.checksum());
+        assert_eq!(borrowed.nfields(), owned.nfields());
+        for i in 0..owned.nfields() {
+            assert_eq!(borrowed.get_field(i), owned._fields.get(i).map(|f| f.as_str()));
+        }
+    }
+}
+
+#[test]
+fn test_borrowed_views() {
+    let line = "$GPGGA,173617,4844.8683,N,12313.7709,W,2,11,1.00,2,M,-17.0,M,,*52";
+    let d = GgaData::from(NmeaSentenceRef::from(line));
+    assert_eq!(d.time(), NaiveTime::from_hms_opt(17, 36, 17));
+    assert_eq!(d.hdop(), Some(1.0));
+    assert_eq!(d.to_string(), line);
+    let owned: GgaData = d.into_owned();
+    assert_eq!(owned.satellites_in_use_count(), Some(11));
+
+    match NmeaSentence::from("$IIHDG,108.4,,,15.7,E*1C") {
+        NmeaSentence::HDG(hdg) => assert_eq!(hdg.variation().unwrap().value, 15.7),
+        _ => panic!("Expected HDG"),
+    }
+    let invalid = NmeaSentence::from("$IIHDG,108.4,,,15.7,E*1D");
+    assert!(matches!(invalid.into_owned(), NmeaSentence::ERROR(_)));
+    assert_eq!(NmeaSentenceRef::from("$IIHDG,108.4,,,15.7,E*1D").nfields(), 0);
+}
+
+#[test]
+fn test_time_without_fraction() {
+    let s = "$GPGGA,173617,4844.8683,N,12313.7709,W,2,11,1.00,2,M,-17.0,M,,*52".to_string();
+    let d = GgaData::from(&s);
+    assert_eq!(d.time(), NaiveTime::from_hms_opt(17, 36, 17));
+}
+
+#[test]
+fn test_time_with_fraction() {
+    let d = GgaData::from(NmeaSentenceRef::from("$GPGGA,173617.37,,,,,,,,,,,,,*79"));
+    assert_eq!(d.time(), NaiveTime::from_hms_milli_opt(17, 36, 17, 370));
+    let d = GgaData::from(NmeaSentenceRef::from("$GPGGA,173617.3x,,,,,,,,,,,,,*36"));
+    assert_eq!(d.time(), None);
+}
+
+#[test]
+fn test_csv_headers() {
+    assert_eq!(
+        MwvData::headers(),
+        vec!["mwv_wind_angle_value", "mwv_wind_angle_unit", "mwv_wind_speed_value", "mwv_wind_speed_unit", "mwv_status"]
+    );
+    let d = MwvData::from(&"$WIMWV,46.3,R,2.1,N,A*11".to_string());
+    assert_eq!(d.cells(), vec!["46.3", "R", "2.1", "N", "A"]);
+}
+
+#[test]
+fn test_csv_tables_by_type() {
+    let f = fs::File::open("test.log").unwrap();
+    let tables = csv::CsvExporter::new().tables_by_type(BufReader::new(f)).unwrap();
+    let hdg = &tables["hdg"];
+    assert_eq!(hdg.headers()[..3], ["line", "talker", "hdg_sensor_heading_degrees"]);
+    assert_eq!(hdg.rows()[0][1], "II");
+    assert_eq!(hdg.rows()[0][2], "108.3");
+    let mut out = Vec::new();
+    tables["xdr"].write(&mut out, '\t').unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.starts_with("line\ttalker\txdr_transducer_type\txdr_value\txdr_unit\txdr_name\n"));
+}
+
+#[test]
+fn test_csv_resampled() {
+    let log = "$GPZDA,173618,21,05,2023,07,00*40\n\
+               $IIHDG,108.3,,,15.7,E*1B\n\
+               $GPZDA,173621,21,05,2023,07,00*4A\n\
+               $IIHDG,108.4,,,15.7,E*1C\n";
+    let table = csv::CsvExporter::new().resampled(log.as_bytes()).unwrap();
+    let heading = table.headers().iter().position(|h| h == "hdg_sensor_heading_degrees").unwrap();
+    let rows = table.rows();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0][0], "2023-05-21T17:36:18");
+    assert_eq!(rows[2][0], "2023-05-21T17:36:20");
+    assert_eq!(rows[2][heading], "108.3");
+    assert_eq!(rows[3][heading], "108.4");
+}
+
+#[test]
+fn test_signalk_delta() {
+    let converter = signalk::SignalKConverter::new();
+    let hdg = NmeaSentence::from(&"$IIHDG,108.3,,,15.7,E*1B".to_string());
+    let delta = converter.delta(&hdg).unwrap();
+    let update = &delta.updates[0];
+    assert_eq!(update.source_ref(), "nmea0183.II");
+    assert_eq!(update.values[0].path, "navigation.headingMagnetic");
+    let heading = update.values[0].value.as_number().unwrap();
+    assert!((heading - 108.3f64.to_radians()).abs() < 1e-6);
+    assert!(delta.to_json().starts_with(
+        "{\"context\":\"vessels.self\",\"updates\":[{\"source\":{\"label\":\"nmea0183\",\"type\":\"NMEA0183\",\"talker\":\"II\",\"sentence\":\"HDG\"},\"$source\":\"nmea0183.II\",\"values\":[{\"path\":\"navigation.headingMagnetic\",\"value\":1.89"
+    ));
+
+    let mwv = NmeaSentence::from(&"$WIMWV,346.3,R,2.1,N,A*22".to_string());
+    let values = &converter.delta(&mwv).unwrap().updates[0].values;
+    assert_eq!(values[0].path, "environment.wind.angleApparent");
+    assert!((values[0].value.as_number().unwrap() + 13.7f64.to_radians()).abs() < 1e-5);
+    assert!((values[1].value.as_number().unwrap() - 2.1 * 1852.0 / 3600.0).abs() < 1e-5);
+
+    let invalid = NmeaSentence::from(&"$WIMWV,,T,,N,V*32".to_string());
+    assert!(converter.delta(&invalid).is_none());
+}
+
+#[test]
+fn test_signalk_round_trip() {
+    let converter = signalk::SignalKConverter::new();
+    let rmc = NmeaSentence::from(&"$YDRMC,173619.00,A,4844.8692,N,12313.7747,W,0.0,221.9,210523,15.7,E,A,C*7D".to_string());
+    let delta = converter.delta(&rmc).unwrap();
+    assert_eq!(delta.updates[0].timestamp.unwrap().to_string(), "2023-05-21 17:36:19 UTC");
+    let sentences = converter.to_sentences("YD", &delta.updates[0].values)
+        .iter().map(|s| s.to_string()).collect::<Vec<String>>();
+    let gll = GllData::from(sentences.iter().find(|s| s.starts_with("$YDGLL")).unwrap());
+    assert!((gll.latitude().unwrap().value() - 4844.869).abs() < 0.001);
+    assert_eq!(gll.longitude().unwrap().unit(), 'W');
+    assert_eq!(gll.time(), NaiveTime::from_hms_opt(17, 36, 19));
+    let rmc = sentences.iter().find(|s| s.starts_with("$YDRMC")).unwrap();
+    let rmc = RmcData::from(rmc);
+    assert_eq!(rmc.date(), NaiveDate::from_ymd_opt(2023, 5, 21));
+    assert_eq!(rmc.track_made_good_true(), Some(221.9));
+    assert_eq!(rmc.variation().unwrap().unit(), 'E');
+
+    let dpt = NmeaSentence::from(&"$SDDPT,5.2,-0.3*7E".to_string());
+    let values = &converter.delta(&dpt).unwrap().updates[0].values;
+    let sentences = converter.to_sentences("SD", values);
+    assert_eq!(sentences[0].to_string(), "$SDDPT,5.2,-0.3*7E");
+
+    let attitude = |roll| signalk::SignalKPathValue {
+        path: "navigation.attitude".to_string(),
+        value: signalk::SignalKValue::Attitude { roll, pitch: None, yaw: None },
+    };
+    assert!(converter.to_sentences("II", &[attitude(None)]).is_empty());
+    let xdr = converter.to_sentences("II", &[attitude(Some(0.1))]);
+    assert!(xdr[0].to_string().starts_with("$IIXDR,A,5.7,D,ROLL*"));
+}
+
+#[test]
+fn test_n2k_heading() {
+    let mut converter = n2k::N2kConverter::new().with_source(35);
+    let hdg = NmeaSentence::from(&"$IIHDG,108.3,,,15.7,E*1B".to_string());
+    let messages = converter.to_pgns(&hdg);
+    assert_eq!(messages.len(), 1);
+    let m = &messages[0];
+    assert_eq!((m.pgn, m.priority, m.source, m.destination), (127250, 2, 35, 255));
+    // 108.3 degrees is 1.8902 rad, deviation is not available, variation 15.7 E is 0.2740 rad.
+    assert_eq!(m.data, vec![0x00, 0xD6, 0x49, 0xFF, 0x7F, 0xB4, 0x0A, 0xFD]);
+    let sentences = converter.to_sentences(m);
+    assert_eq!(sentences[0].to_string(), "$YDHDG,108.3,,,15.7,E*06");
+}
+
+#[test]
+fn test_n2k_round_trip() {
+    let mut converter = n2k::N2kConverter::new().with_talker("WI");
+    let mwv = NmeaSentence::from(&"$WIMWV,46.3,R,2.1,N,A*11".to_string());
+    let messages = converter.to_pgns(&mwv);
+    assert_eq!(messages[0].pgn, n2k::PGN_WIND_DATA);
+    assert_eq!(converter.to_sentences(&messages[0])[0].to_string(), "$WIMWV,46.3,R,2.1,N,A*11");
+
+    let gga = NmeaSentence::from(&"$GPGGA,173617,4844.8683,N,12313.7709,W,2,11,1.00,2,M,-17.0,M,,*52".to_string());
+    let messages = converter.to_pgns(&gga);
+    assert_eq!(messages.iter().map(|m| m.pgn).collect::<Vec<u32>>(), vec![129025, 129029]);
+    assert_eq!(messages[1].data.len(), 43);
+    let back = GgaData::from(&converter.to_sentences(&messages[1])[0].to_string());
+    assert_eq!(back.time(), NaiveTime::from_hms_opt(17, 36, 17));
+    assert_eq!(back.quality_indicator(), Some(2));
+    assert_eq!(back.satellites_in_use_count(), Some(11));
+    assert_eq!(back.geoidal_separation().unwrap().value(), -17.0);
+    assert!((back.longitude().unwrap().value() - 12313.771).abs() < 0.001);
+
+    let dpt = NmeaSentence::from(&"$SDDPT,5.2,-0.3*7E".to_string());
+    let messages = converter.to_pgns(&dpt);
+    let back = DptData::from(&converter.to_sentences(&messages[0])[0].to_string());
+    assert_eq!(back.water_depth_meters(), Some(5.2));
+    assert_eq!(back.transducer_offset(), Some(-0.3));
+
+    let xte = NmeaSentence::from(&"$GPXTE,A,A,0.050,R,N,A*28".to_string());
+    let messages = converter.to_pgns(&xte);
+    assert_eq!(messages[0].data[2..6], (-9260i32).to_le_bytes());
+
+    // Values past the top of a field are sent as the largest valid value, not as unavailable.
+    let vhw = NmeaSentence::from(&"$IIVHW,,T,,M,2000.0,N,,K*49".to_string());
+    let messages = converter.to_pgns(&vhw);
+    assert_eq!(messages[0].data[1..3], [0xFC, 0xFF]);
+    let back = VhwData::from(&converter.to_sentences(&messages[0])[0].to_string());
+    assert_eq!(back.stw_kph().map(|s| s.value()), Some(2359.15));
+    let gga = NmeaSentence::from(&"$GPGGA,173617,4844.8683,N,12313.7709,W,2,253,1.00,2,M,-17.0,M,,*66".to_string());
+    let messages = converter.to_pgns(&gga);
+    let back = GgaData::from(&converter.to_sentences(&messages[1])[0].to_string());
+    assert_eq!(back.satellites_in_use_count(), Some(252));
+}
+
+#[test]
+fn test_gateway_yd_raw() {
+    let frame = gateway::parse_yd_raw("17:33:21.107 R 09F11223 00 D6 49 FF 7F B4 0A FD").unwrap();
+    assert_eq!((frame.priority(), frame.pgn(), frame.source(), frame.destination()), (2, 127250, 0x23, 255));
+    assert_eq!(frame.timestamp, NaiveTime::from_hms_milli_opt(17, 33, 21, 107));
+
+    let request = gateway::parse_yd_raw("17:33:21.110 T 18EA1223 14 F0 01").unwrap();
+    assert_eq!((request.pgn(), request.destination(), request.direction), (59904, 0x12, 'T'));
+
+    assert!(gateway::parse_yd_raw("17:33:21.110 X 18EA1223 14").is_none());
+    assert!(gateway::parse_yd_raw("17:33:21.110 R 18EA1223 14 F0 01 02 03 04 05 06 07").is_none());
+}
+
+#[test]
+fn test_gateway_fast_packet() {
+    let gga = NmeaSentence::from(&"$GPGGA,173617,4844.8683,N,12313.7709,W,2,11,1.00,2,M,-17.0,M,,*52".to_string());
+    let payload = n2k::N2kConverter::new().to_pgns(&gga).remove(1).data;
+    let mut lines = vec![format!("17:36:17.000 R 0DF80523 40 {:02X} {}", payload.len(),
+        payload[..6].iter().map(|b| format!("{:02X}", b)).collect::<Vec<String>>().join(" "))];
+    for (i, chunk) in payload[6..].chunks(7).enumerate() {
+        lines.push(format!("17:36:17.001 R 0DF80523 {:02X} {}", 0x40 + i + 1,
+            chunk.iter().map(|b| format!("{:02X}", b)).collect::<Vec<String>>().join(" ")));
+    }
+    lines.insert(2, "$IIHDG,108.3,,,15.7,E*1B".to_string());
+
+    let log = lines.join("\n");
+    let messages = gateway::GatewayReader::new().messages(log.as_bytes())
+        .map(|m| m.unwrap())
+        .collect::<Vec<gateway::GatewayMessage>>();
+    assert_eq!(messages.len(), 2);
+    assert!(matches!(messages[0], gateway::GatewayMessage::Nmea0183(NmeaSentence::HDG(_))));
+    match &messages[1] {
+        gateway::GatewayMessage::N2k(m) => {
+            assert_eq!((m.pgn, m.priority, m.source), (129029, 3, 0x23));
+            assert_eq!(m.data, payload);
+        }
+        _ => panic!("expected an N2K message"),
+    }
+}
+
+#[test]
+fn test_gateway_actisense() {
+    let m = gateway::parse_actisense("A173321.107 23FF7 1F513 012F3070002F30709F").unwrap();
+    assert_eq!((m.source, m.destination, m.priority, m.pgn), (0x23, 0xFF, 7, 128275));
+    assert_eq!(m.data, vec![0x01, 0x2F, 0x30, 0x70, 0x00, 0x2F, 0x30, 0x70, 0x9F]);
+    assert_eq!(m.timestamp, NaiveTime::from_hms_milli_opt(17, 33, 21, 107));
+    assert!(gateway::parse_actisense("A173321.107 23FF7 1F513 012").is_none());
+}
+
+#[test]
+fn test_seatalk_decode() {
+    use seatalk::{decode, AutopilotMode, SeaTalkDatagram};
+    assert_eq!(decode(&[0x00, 0x02, 0x04, 0x94, 0x00]), Some(SeaTalkDatagram::Depth {
+        feet: 14.8, anchor_alarm: false, metric_display: false,
+        transducer_defective: true, deep_alarm: false, shallow_alarm: false,
+    }));
+    assert_eq!(decode(&[0x00, 0x02, 0x00, 0x94]), None);
+    assert_eq!(decode(&[0x10, 0x01, 0x00, 0x5A]), Some(SeaTalkDatagram::ApparentWindAngle(45.0)));
+    assert_eq!(decode(&[0x26, 0x04, 0x6A, 0x02, 0x00, 0x00, 0x04]), Some(SeaTalkDatagram::SpeedThroughWater(6.18)));
+    assert_eq!(decode(&[0x27, 0x01, 0x2C, 0x01]), Some(SeaTalkDatagram::WaterTemperature { celsius: 20.0, sensor_defective: false }));
+    assert_eq!(decode(&[0x9C, 0x61, 0x1C, 0xFB]), Some(SeaTalkDatagram::HeadingAndRudder {
+        heading: 237.0, turning_right: false, rudder: -5.0,
+    }));
+    assert_eq!(decode(&[0x84, 0x26, 0x5C, 0x5A, 0x02, 0x00, 0x03, 0x00, 0x00]), Some(SeaTalkDatagram::AutopilotStatus {
+        heading: 236.0, turning_right: false, course: 135.0, mode: AutopilotMode::Auto,
+        off_course_alarm: false, wind_shift_alarm: false, rudder: 3.0,
+    }));
+    match decode(&[0x58, 0x05, 0x30, 0xAF, 0x44, 0x7B, 0x35, 0xCB]) {
+        Some(SeaTalkDatagram::Position { latitude, longitude }) => {
+            assert!((latitude - (48.0 + 44.868 / 60.0)).abs() < 1e-9);
+            assert!((longitude + (123.0 + 13.771 / 60.0)).abs() < 1e-9);
+        }
+        d => panic!("unexpected {:?}", d),
+    }
+}
+
+#[test]
+fn test_seatalk_translate() {
+    let mut translator = seatalk::SeaTalkTranslator::new();
+    let sentences = translator.push(&[0x00, 0x02, 0x00, 0x94, 0x00]);
+    match &sentences[..] {
+        [NmeaSentence::DPT(d)] => assert_eq!(d.water_depth_meters(), Some(4.51)),
+        s => panic!("unexpected {:?}", s),
+    }
+
+    assert!(translator.push(&[0x10, 0x01, 0x00, 0x5A]).is_empty());
+    match &translator.push(&[0x11, 0x01, 0x0C, 0x05])[..] {
+        [NmeaSentence::MWV(d)] => {
+            let (angle, speed) = (d.wind_angle().unwrap(), d.wind_speed().unwrap());
+            assert_eq!((angle.value(), angle.unit()), (45.0, 'R'));
+            assert_eq!((speed.value(), speed.unit()), (12.5, 'N'));
+        }
+        s => panic!("unexpected {:?}", s),
+    }
+
+    match &translator.push(&[0x9C, 0x21, 0x1C, 0xFB])[..] {
+        [NmeaSentence::HDG(h), NmeaSentence::RSA(r)] => {
+            assert_eq!(h.sensor_heading_degrees(), Some(236.0));
+            assert_eq!(r.starboard_angle(), Some(-5.0));
+        }
+        s => panic!("unexpected {:?}", s),
+    }
+
+    match &translator.push(&[0x27, 0x01, 0x2C, 0x01])[..] {
+        [NmeaSentence::MTW(d)] => {
+            let temperature = d.temperature().unwrap();
+            assert_eq!((temperature.value(), temperature.unit()), (20.0, 'C'));
+        }
+        s => panic!("unexpected {:?}", s),
+    }
+
+    assert!(translator.push(&[0x50, 0x02, 0x30, 0x87, 0x11]).is_empty());
+    let rmc = translator.to_sentences(&seatalk::decode(&[0x51, 0x02, 0x7B, 0x61, 0x05]).unwrap());
+    assert_eq!(rmc[0].to_string(), NmeaBaseSentence::build("$II", "RMC", &[
+        "", "A", "4844.8700", "N", "12313.7700", "W", "", "", "", "", "", "A",
+    ].map(String::from)).to_string());
+}
+
+#[test]
+fn test_seatalk_short_datagrams() {
+    let mut translator = seatalk::SeaTalkTranslator::new();
+    for id in [0x10, 0x11, 0x20, 0x27, 0x9C] {
+        assert_eq!(seatalk::decode(&[id, 0x00, 0x00]), None);
+        assert!(translator.push(&[id, 0x00, 0x00]).is_empty());
+    }
+    for id in 0..=255u8 {
+        for attribute in 0..=0x0F {
+            seatalk::decode(&[id, attribute, 0x00]);
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_fields_by_name() {
+    let gga = NmeaSentence::from(&"$GPGGA,173617,4844.8683,N,12313.7709,W,2,11,1.00,2,M,-17.0,M,,*52".to_string());
+    let json = serde_json::to_value(&gga).unwrap();
+    assert_eq!(json["GGA"]["sender"], "$GP");
+    assert_eq!(json["GGA"]["time"], "17:36:17");
+    assert_eq!(json["GGA"]["latitude"]["value"].as_f64(), Some(4844.8683));
+    assert_eq!(json["GGA"]["latitude"]["unit"], "N");
+    assert_eq!(json["GGA"]["satellites_in_use_count"], 11);
+    assert!(json["GGA"]["differential_station_id"].is_null());
+
+    let back: NmeaSentence = serde_json::from_value(json).unwrap();
+    match back {
+        NmeaSentence::GGA(d) => {
+            // Values come back unchanged; only trailing zeros such as those of 1.00 are dropped.
+            assert_eq!(d.to_string(), "$GPGGA,173617,4844.8683,N,12313.7709,W,2,11,1,2,M,-17,M,,*62");
+            let original = GgaData::from(&"$GPGGA,173617,4844.8683,N,12313.7709,W,2,11,1.00,2,M,-17.0,M,,*52".to_string());
+            assert!((0..original.base.field_count()).all(|i| original.base.get::<f64>(i) == d.base.get::<f64>(i)));
+            assert_eq!(d.hdop(), Some(1.0));
+        }
+        s => panic!("unexpected {:?}", s),
+    }
+
+    let xdr = NmeaSentence::from(&"$IIXDR,C,,C,AIRTEMP,A,0.1,D,HEEL,A,1.3,D,TRIM,P,1.016,B,BARO,A,,D,RUDDER*2C".to_string());
+    let json = serde_json::to_value(&xdr).unwrap();
+    assert_eq!(json["XDR"]["measurements"][2]["name"], "BARO");
+    assert_eq!(json["XDR"]["measurements"][2]["data"]["unit"], "B");
+
+    let tut = NmeaSentence::from(&"$IITUT,01,01,0,U,48656C6C6F*63".to_string());
+    let json = serde_json::to_value(&tut).unwrap();
+    assert_eq!(json["TUT"]["fields"][4], "48656C6C6F");
+    let back: NmeaSentence = serde_json::from_value(json).unwrap();
+    match back {
+        NmeaSentence::TUT(d) => assert_eq!(d.to_string(), "$IITUT,01,01,0,U,48656C6C6F*63"),
+        s => panic!("unexpected {:?}", s),
+    }
+
+    // Escapes are decoded for serde and put back when deserializing.
+    let txt = NmeaSentence::from(&"$GPTXT,01,01,02,u-blox ag^2C www.u-blox.com*72".to_string());
+    let json = serde_json::to_value(&txt).unwrap();
+    assert_eq!(json["TXT"]["text"], "u-blox ag, www.u-blox.com");
+    let back: NmeaSentence = serde_json::from_value(json).unwrap();
+    assert_eq!(back.to_string(), "$GPTXT,1,1,2,u-blox ag^2C www.u-blox.com*42");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_round_trip() {
+    let file = fs::File::open("test.log").unwrap();
+    for line in BufReader::new(file).lines() {
+        let sentence = NmeaSentence::from(&line.unwrap());
+        let json = serde_json::to_value(&sentence).unwrap();
+        let packed = rmp_serde::to_vec(&sentence).unwrap();
+        let unpacked: NmeaSentence = rmp_serde::from_slice(&packed).unwrap();
+        assert_eq!(serde_json::to_value(&unpacked).unwrap(), json);
+    }
+}
+
+
+#[test]
+fn test_field_table() {
+    assert_eq!(GgaData::FIELD_COUNT, 14);
+    let quality = GgaData::FIELDS.iter().find(|f| f.name == "quality_indicator").unwrap();
+    assert_eq!((quality.kind, quality.index, quality.required), (FieldKind::Number, 5, true));
+    assert!(quality.description.starts_with("Fix quality: 0 = no fix"));
+    let latitude = &GgaData::FIELDS[1];
+    assert_eq!((latitude.name, latitude.type_name, latitude.unit_index), ("latitude", "f32", Some(2)));
+    assert!(RsaData::FIELDS.iter().find(|f| f.name == "angle").unwrap().alias);
+    assert_eq!(GsvData::FIELDS[3].kind, FieldKind::Group);
+
+    let tfi = TfiData::from(&"$IITFI,0,1,2*44".to_string());
+    assert_eq!((tfi.sensor_1(), tfi.sensor_2(), tfi.sensor_3()), (Some('0'), Some('1'), Some('2')));
+    let vpw = VpwData::from(&"$IIVPW,-1.5,N,-0.8,M*5E".to_string());
+    assert_eq!(vpw.speed_mps().unwrap().value(), -0.8);
+    let rsa = RsaData::from(&"$IIRSA,2.5,A,-1.0,V*7C".to_string());
+    assert_eq!((rsa.angle().unwrap(), rsa.port_angle().unwrap(), rsa.port_status()), (2.5, -1.0, Some('V')));
+}
+
+#[test]
+fn test_validate() {
+    assert_eq!(RsaData::from(&"$IIRSA,2.5,A,-1.0,V*7C".to_string()).validate(), Ok(()));
+    assert_eq!(
+        RsaData::from(&"$IIRSA,2.5,,,*69".to_string()).validate(),
+        Err(FieldError::MissingField("starboard_status"))
+    );
+    assert_eq!(
+        RsaData::from(&"$IIRSA,*6C".to_string()).validate(),
+        Err(FieldError::TooFewFields { expected: 2, found: 1 })
+    );
+    assert_eq!(
+        HdtData::from(&"$IIHDT,123.4,T,1*3B".to_string()).validate(),
+        Err(FieldError::TooManyFields { expected: 2, found: 3 })
+    );
+    // Trailing empty fields are not counted, and groups may repeat.
+    assert_eq!(DptData::from(&"$YDDPT,10.68,-1.82,140.0,*6F".to_string()).validate(), Ok(()));
+    let gsv = "$GPGSV,3,1,11,03,03,111,00,04,15,270,00,06,01,010,00,13,06,292,00*74";
+    assert_eq!(GsvData::from(NmeaSentenceRef::from(gsv)).validate(), Ok(()));
+}
+
+#[test]
+fn test_builder() {
+    let mwv = MwvData::builder("$WI").wind_angle(46.3, 'R').wind_speed(2.1, 'N').status('A').build();
+    assert_eq!(mwv.to_string(), "$WIMWV,46.3,R,2.1,N,A*11");
+    // Fields that are not set are left empty.
+    let rmc = RmcData::builder("$GP").status('V').build();
+    assert_eq!(rmc.to_string(), "$GPRMC,,V,,,,,,,,,,,*31");
+    assert_eq!(rmc.validate(), Ok(()));
+    let tlb = TlbData::builder("$RA").targets(&[(1, "ALPHA".to_string()), (2, "BRAVO".to_string())]).build();
+    assert_eq!(tlb.targets().unwrap()[1].1, "BRAVO");
+}
+
+#[test]
+fn test_field_values() {
+    let mwv = MwvData::from(&"$WIMWV,46.3,R,2.1,N,A*11".to_string());
+    let printed: Vec<String> = mwv.fields().map(|(name, value)| format!("mwv.{} = {}", name, value)).collect();
+    assert_eq!(printed, vec!["mwv.wind_angle = 46.3 R", "mwv.wind_speed = 2.1 N", "mwv.status = A"]);
+
+    let line = "$GPGGA,173617,4844.8683,N,12313.7709,W,2,11,1.00,2,M,-17.0,M,,*52";
+    let gga = NmeaSentence::from(line);
+    assert_eq!(gga.schema().len(), 10);
+    let values: Vec<(&str, Value)> = gga.fields().collect();
+    assert_eq!(values[0], ("time", Value::Time(NaiveTime::from_hms_opt(17, 36, 17).unwrap())));
+    assert_eq!(values[2], ("longitude", Value::WithUnit(12313.7709, 'W')));
+    assert_eq!(values[5], ("hdop", Value::Number(1.0)));
+    assert_eq!(values[8], ("age_differential_gps_data", Value::Missing));
+
+    let gsv = NmeaSentence::from("$GPGSV,3,1,11,03,03,111,00,04,15,270,00,06,01,010,00,13,06,292,00*74");
+    let (name, value) = gsv.fields().last().unwrap();
+    assert_eq!((name, value), ("satellite_info", Value::Text("03,03,111,00,04,15,270,00,06,01,010,00,13,06,292,00")));
+
+    // Aliases are left out, and sentences without accessors have no fields.
+    let rsa = RsaData::from(&"$IIRSA,2.5,A,-1.0,V*7C".to_string());
+    assert_eq!(rsa.fields().map(|(name, _)| name).collect::<Vec<_>>(), vec!["starboard_angle", "starboard_status", "port_angle", "port_status"]);
+    assert_eq!(NmeaSentence::from("$IITUT,01,01,0,U,48656C6C6F*63").fields().count(), 0);
+}
+
+#[test]
+fn test_common_accessors() {
+    let line = "$IIHDG,108.4,,,15.7,E*1C".to_string();
+    let hdg = NmeaSentence::from(&line);
+    assert_eq!(hdg.message_type(), MessageType::HDG);
+    assert_eq!(hdg.message_type().to_string(), "HDG");
+    assert_eq!(MessageType::from("HDG"), MessageType::HDG);
+    assert_eq!(hdg.talker(), "II");
+    assert_eq!(hdg.raw(), line);
+    assert_eq!(hdg.to_string(), line);
+    assert!(!hdg.is_error());
+    assert_eq!(hdg.base().unwrap().get::<f32>(0), Some(108.4));
+
+    // The trait works on any variant, so routing code needs no match.
+    fn route<T: NmeaBaseTrait>(sentence: &T) -> String {
+        format!("{}/{}/{}", sentence.talker(), sentence.message(), sentence.nfields())
+    }
+    assert_eq!(route(&hdg), "II/HDG/5");
+    assert_eq!(route(&HdgData::from(&line)), "II/HDG/5");
+
+    let bad = "$IIHDG,108.4,,,15.7,E*1D".to_string();
+    let error = NmeaSentence::from(&bad);
+    assert!(error.is_error());
+    assert_eq!(error.message_type(), MessageType::Unknown);
+    assert!(error.base().is_none());
+    assert_eq!((error.talker(), error.raw()), ("", bad.as_str()));
+    assert_eq!(route(&error), "//0");
+
+    let borrowed = NmeaSentence::from(line.as_str());
+    assert_eq!((borrowed.message_type(), borrowed.talker(), borrowed.raw()), (MessageType::HDG, "II", line.as_str()));
+}
+
+#[derive(Debug)]
+struct ZzzSentence {
+    base: NmeaBaseSentence,
+}
+
+impl From<NmeaBaseSentence> for ZzzSentence {
+    fn from(base: NmeaBaseSentence) -> Self {
+        Self { base }
+    }
+}
+
+impl CustomSentence for ZzzSentence {
+    fn base(&self) -> &NmeaBaseSentence {
+        &self.base
+    }
+
+    fn schema(&self) -> &'static [FieldInfo] {
+        &[FieldInfo {
+            name: "temperature",
+            kind: FieldKind::Number,
+            type_name: "f32",
+            index: 0,
+            unit_index: Some(1),
+            required: false,
+            alias: false,
+            range: None,
+            description: "Temperature.",
+        }]
+    }
+}
+
+#[test]
+fn test_registry() {
+    let zzz = "$MYZZZ,102.3,C*23";
+    let hdg = "$IIHDG,108.4,,,15.7,E*1C";
+
+    // The default registry parses exactly as NmeaSentence::from does.
+    let registry = SentenceRegistry::default();
+    assert!(registry.parse(zzz).is_error());
+    assert_eq!(registry.parse(hdg).message_type(), MessageType::HDG);
+    assert!(matches!(registry.parse(hdg), NmeaSentence::HDG(_)));
+
+    let registry = SentenceRegistry::new().with_sentence::<ZzzSentence>("ZZZ");
+    let sentence = registry.parse(zzz);
+    let NmeaSentence::Custom(custom) = &sentence else { panic!("expected a custom sentence") };
+    assert_eq!(custom.downcast_ref::<ZzzSentence>().unwrap().base.get::<f32>(0), Some(102.3));
+    assert_eq!((sentence.talker(), sentence.raw(), sentence.message_type()), ("MY", zzz, MessageType::Unknown));
+    assert_eq!(sentence.fields().collect::<Vec<_>>(), vec![("temperature", Value::WithUnit(102.3, 'C'))]);
+    assert_eq!(sentence.nfields(), 2);
+    assert!(matches!(registry.parse(hdg), NmeaSentence::HDG(_)));
+
+    // Built-in sentences can be overridden, and a full address takes precedence over a type.
+    let registry = SentenceRegistry::new()
+        .with_sentence::<ZzzSentence>("HDG")
+        .with_parser("RMZ", |_| panic!("the address should be preferred"))
+        .with_sentence::<ZzzSentence>("PGRMZ");
+    let sentence = registry.parse(hdg);
+    assert!(matches!(sentence, NmeaSentence::Custom(_)));
+    assert_eq!(sentence.message_type(), MessageType::HDG);
+    assert!(matches!(registry.parse("$PGRMZ,1494,f,3*23"), NmeaSentence::Custom(_)));
+    assert!(registry.parse("$PGRMZ,1494,f,3*24").is_error());
+}
+
+#[test]
+fn test_parse_options() {
+    let hdg = "$IIHDG,108.4,,,15.7,E*1C";
+
+    // The default options parse exactly as NmeaSentence::from does.
+    let parsed = ParseOptions::default().parse(hdg);
+    assert!(parsed.is_clean());
+    assert!(matches!(parsed.sentence, NmeaSentence::HDG(_)));
+    let parsed = ParseOptions::default().parse("$IIHDG,108.4,,,15.7,E");
+    assert!(parsed.sentence.is_error() && parsed.warnings.is_empty());
+    assert!(ParseOptions::default().parse("$IIHDG,108.4,,,15.7,E*1D").sentence.is_error());
+    assert!(ParseOptions::default().parse("$IIHDG,108.4,,,15.7,E*1c").is_clean());
+
+    let lenient = ParseOptions::lenient();
+    let parsed = lenient.parse("$IIHDG,108.4,,,15.7,E");
+    assert_eq!(parsed.warnings, vec![ParseWarning::MissingChecksum]);
+    assert_eq!(parsed.sentence.base().unwrap().get::<f32>(3), Some(15.7));
+    assert_eq!(parsed.sentence.nfields(), 5);
+    let parsed = lenient.parse("  $IIHDG,108.4,,,15.7,E*1D \r");
+    assert_eq!(parsed.warnings, vec![ParseWarning::Trimmed, ParseWarning::ChecksumMismatch { expected: 0x1C, found: 0x1D }]);
+    assert_eq!(parsed.sentence.raw(), "$IIHDG,108.4,,,15.7,E*1D");
+    assert!(matches!(parsed.sentence, NmeaSentence::HDG(_)));
+    assert!(lenient.parse("$IIHDG,108.4,*,,15.7,E").sentence.is_error());
+
+    // The rudder angle from our log is out of range, and the beacon frequency below is not a
+    // beacon frequency.
+    let parsed = lenient.parse("$YDRSA,-138.1,A,,V*42");
+    assert_eq!(parsed.warnings, vec![ParseWarning::Field(FieldError::OutOfRange("starboard_angle"))]);
+    assert_eq!(parsed.warnings[0].to_string(), "field starboard_angle is out of range");
+    assert!(matches!(parsed.sentence, NmeaSentence::RSA(_)));
+    let parsed = lenient.parse("$GPMSK,250.0,A,100,A,2*44");
+    assert_eq!(parsed.warnings, vec![ParseWarning::Field(FieldError::OutOfRange("beacon_frequency"))]);
+    assert!(ParseOptions::default().parse("$YDRSA,-138.1,A,,V*42").is_clean());
+
+    let strict = ParseOptions::strict();
+    assert!(strict.parse(hdg).is_clean());
+    let long = format!("$GPTXT,01,01,02,{}", "X".repeat(70));
+    let long = format!("{}*{:02X}", long, long[1..].bytes().fold(0, |acc, b| acc ^ b));
+    assert!(NmeaSentence::from(&long).base().is_some());
+    let parsed = strict.parse(&long);
+    assert!(parsed.sentence.is_error());
+    if let NmeaSentence::ERROR(error) = parsed.sentence {
+        assert_eq!(error.error, "Sentence too long");
+    }
+    let parsed = strict.parse("$IIHDT,123.4,T,1*3B");
+    assert_eq!(parsed.warnings, vec![ParseWarning::Field(FieldError::TooManyFields { expected: 2, found: 3 })]);
+
+    // The borrowed parser reports warnings as it goes.
+    let mut warnings = vec![];
+    let sentence = lenient.parse_ref("$YDRSA,-138.1,A,,V", |w| warnings.push(w));
+    assert_eq!(sentence.raw(), "$YDRSA,-138.1,A,,V");
+    assert_eq!(warnings, vec![ParseWarning::MissingChecksum, ParseWarning::Field(FieldError::OutOfRange("starboard_angle"))]);
+
+    // Nothing in the log draws a warning under the strict options, apart from the rudder.
+    let file = fs::File::open("test.log").unwrap();
+    for line in BufReader::new(file).lines().map(|l| l.unwrap()) {
+        let parsed = strict.parse(&line);
+        assert!(parsed.warnings.is_empty() || line.starts_with("$YDRSA"), "{}: {:?}", line, parsed.warnings);
+    }
+}
+
+// Runs a sentence through everything that reads it.  None of it may panic.
+fn exercise(bytes: &[u8]) {
+    let sentence = NmeaSentence::from(bytes);
+    let _ = (sentence.to_string(), sentence.talker(), sentence.message_type(), sentence.schema());
+    sentence.fields().for_each(|(_, value)| drop(value.to_string()));
+    if let Some(base) = sentence.base() {
+        for i in 0..base.field_count() + 1 {
+            let _ = (base.get::<f64>(i), base.get_hex::<u32>(i), base.get_time(i), base.get_date(i), base.get_coordinate(i, i + 1));
+        }
+    }
+    let sentence = sentence.into_owned();
+    let _ = signalk::SignalKConverter::new().delta(&sentence);
+    if let Ok(line) = std::str::from_utf8(bytes) {
+        let _ = ParseOptions::lenient().parse(line);
+        let _ = ParseOptions::strict().parse_ref(line, |_| ());
+        let _ = NmeaBaseSentence::from(&line.to_string());
+    }
+}
+
+fn log_lines() -> Vec<String> {
+    let file = fs::File::open("test.log").unwrap();
+    BufReader::new(file).lines().map(|l| l.unwrap()).collect()
+}
+
+#[test]
+fn test_never_panics() {
+    for line in log_lines() {
+        exercise(line.as_bytes());
+    }
+    let cases: [&[u8]; 10] = [
+        b"", b"$", b"*", b"$GP,*00", b"$,,,*00", b"$GPGGA,1\xc3\xa9*00", "$GPRMC,é,A*00".as_bytes(),
+        b"$GPZDA,1,,,*79", b"$GPRMC,0,A,,,,,,,0,,*26", b"$GPRMC,123519.\xff,A*00",
+    ];
+    for case in cases {
+        exercise(case);
+    }
+    assert_eq!(NmeaSentenceRef::parse("$GPGGA,é*00".as_bytes()).unwrap_err(), "Non-ASCII character");
+    if let NmeaSentence::ERROR(error) = NmeaSentence::from(&b"$GPRMC,,V,\xff*31"[..]) {
+        assert_eq!((error.error.as_str(), error.message.as_str()), ("Non-ASCII character", "$GPRMC,,V,\u{fffd}*31"));
+    } else {
+        panic!("expected an error");
+    }
+    let sentence = NmeaSentence::from("$GPRMC,0,A,,,,,,,0,,*26");
+    let NmeaSentence::RMC(rmc) = sentence else { panic!("expected RMC") };
+    assert_eq!((rmc.time(), rmc.date()), (None, None));
+}
+
+proptest::proptest! {
+    #![proptest_config(proptest::test_runner::Config::with_cases(5000))]
+
+    #[test]
+    fn prop_random_bytes(bytes in proptest::collection::vec(proptest::num::u8::ANY, 0..100)) {
+        exercise(&bytes);
+    }
+
+    // Mutating real sentences gets past the checksum far more often than random bytes do.
+    #[test]
+    fn prop_mutated_lines(
+        line in proptest::sample::select(log_lines()),
+        edits in proptest::collection::vec((proptest::num::usize::ANY, proptest::num::u8::ANY, 0..3u8), 1..4),
+    ) {
+        let mut bytes = line.into_bytes();
+        for (position, byte, edit) in edits {
+            let position = position % (bytes.len() + 1);
+            match edit {
+                0 => bytes.insert(position, byte),
+                1 if position < bytes.len() => { bytes.remove(position); }
+                _ if position < bytes.len() => bytes[position] = byte,
+                _ => {}
+            }
+        }
+        exercise(&bytes);
+        // Fix up the checksum so the fields themselves get read.
+        if let Some(star) = bytes.iter().rposition(|b| *b == b'*') {
+            let checksum = bytes[1.min(star)..star].iter().fold(0, |acc, b| acc ^ b);
+            bytes.truncate(star + 1);
+            bytes.extend_from_slice(format!("{:02X}", checksum).as_bytes());
+            exercise(&bytes);
+        }
+    }
+}
+
+#[test]
+fn test_txt() {
+    let txt = TxtData::from(&"$GPTXT,01,01,02,ANTENNA OK*36".to_string());
+    assert_eq!((txt.total(), txt.number(), txt.text_identifier()), (Some(1), Some(1), Some(2)));
+    assert_eq!(txt.text(), Some("ANTENNA OK".to_string()));
+    assert!(txt.validate().is_ok());
+
+    // ^HH escapes are decoded in any text field, and a stray ^ is left alone.
+    let txt = TxtData::from(&"$GPTXT,01,01,02,u-blox ag^2C www.u-blox.com*72".to_string());
+    assert_eq!(txt.text(), Some("u-blox ag, www.u-blox.com".to_string()));
+    assert_eq!(txt.base.get::<String>(3), Some("u-blox ag^2C www.u-blox.com".to_string()));
+    assert_eq!(txt.fields().last(), Some(("text", Value::Text("u-blox ag^2C www.u-blox.com"))));
+    let txt = TxtData::from(&"$GPTXT,02,02,25,OPEN ^5E_^5E*23".to_string());
+    assert_eq!(txt.text(), Some("OPEN ^_^".to_string()));
+    let base = NmeaBaseSentence::build("$GP", "TXT", &["1".to_string(), "1".to_string(), "1".to_string(), "^zz ^2 ^b0C".to_string()]);
+    assert_eq!(base.get_text(3), Some("^zz ^2 °C".to_string()));
+
+    // The builder escapes what the text cannot carry as it is.
+    let txt = TxtData::builder("$GP").total(1).number(1).text_identifier(2).text("25°C, *ok*").build();
+    assert_eq!(txt.base.field(3), Some("25^C2^B0C^2C ^2Aok^2A"));
+    assert_eq!(txt.text(), Some("25°C, *ok*".to_string()));
+    assert_eq!(TxtData::from(&txt.to_string()).text(), txt.text());
+
+    let mut assembler = TxtAssembler::new();
+    let message = assembler.push(&TxtData::from(&"$GPTXT,01,01,02,ANTENNA OK*36".to_string())).unwrap();
+    assert_eq!(message, TextMessage { talker: "GP".to_string(), identifier: 2, text: "ANTENNA OK".to_string() });
+    assert_eq!(assembler.push(&TxtData::from(&"$GPTXT,02,01,25,ANTENNA *34".to_string())), None);
+    let message = assembler.push(&TxtData::from(&"$GPTXT,02,02,25,OPEN ^5E_^5E*23".to_string())).unwrap();
+    assert_eq!((message.identifier, message.text.as_str()), (25, "ANTENNA OPEN ^_^"));
+
+    // A second part on its own, or under another identifier, is dropped.
+    assert_eq!(assembler.push(&TxtData::from(&"$GPTXT,02,02,25,OPEN ^5E_^5E*23".to_string())), None);
+    assert_eq!(assembler.push(&TxtData::from(&"$GPTXT,02,01,25,ANTENNA *34".to_string())), None);
+    assert_eq!(assembler.push(&TxtData::from(&"$GPTXT,02,02,07,OPEN*5C".to_string())), None);
+    assert!(assembler.push(&TxtData::from(&"$GPTXT,02,02,25,OPEN ^5E_^5E*23".to_string())).is_some());
+
+    // Totals and numbers outside 1 to 99 are refused rather than counted past.
+    let part = |total: u8, number: u8| TxtData::from(&TxtData::builder("$GP").total(total).number(number).text_identifier(1).text("X").build().to_string());
+    assert!((1..=255).all(|n| assembler.push(&part(255, n)).is_none()));
+    assert!(assembler.push(&part(0, 0)).is_none() && assembler.push(&part(3, 4)).is_none());
+    assert!((1..99).all(|n| assembler.push(&part(99, n)).is_none()));
+    assert_eq!(assembler.push(&part(99, 99)).map(|m| m.text.len()), Some(99));
+}
+
+#[test]
+fn test_autopilot() {
+    use crate::autopilot::{AutopilotState, SteeringMode};
+
+    let htc = HtcData::builder("$II")
+        .override_status('V')
+        .steering_mode(SteeringMode::Heading)
+        .turn_mode('N')
+        .commanded_rudder(-5.0)
+        .commanded_rudder_limit(20.0)
+        .commanded_heading_to_steer(123.5)
+        .heading_reference('M')
+        .build();
+    assert_eq!(htc.to_string(), "$IIHTC,V,5,L,H,N,20,,,,123.5,,,M*3E");
+    assert!(htc.validate().is_ok());
+    assert_eq!((htc.steering_mode(), htc.commanded_rudder()), (Some(SteeringMode::Heading), Some(-5.0)));
+
+    let htd = HtdData::from(&"$IIHTD,V,5,L,H,N,20,10,,,123.5,,,M,A,V,A,110.0*40".to_string());
+    assert_eq!((htd.commanded_off_heading_limit(), htd.commanded_track(), htd.heading_reference()), (Some(10.0), None, Some('M')));
+    assert_eq!((htd.rudder_status(), htd.off_heading_status(), htd.off_track_status()), (Some('A'), Some('V'), Some('A')));
+    assert_eq!(htd.vessel_heading(), Some(110.0));
+
+    let mut state = AutopilotState::new();
+    assert_eq!(state.heading_error(), None);
+    state.update(&NmeaSentence::from(&htc.to_string()));
+    state.update(&NmeaSentence::from("$IIRSA,-2.5,A,,V*53"));
+    state.update(&NmeaSentence::from("$IIHDG,108.4,,,15.7,E*1C"));
+    assert_eq!((state.override_active, state.steering_mode), (Some(false), Some(SteeringMode::Heading)));
+    assert_eq!((state.commanded_heading, state.heading_reference, state.heading()), (Some(123.5), Some('M'), Some(108.4)));
+    assert!((state.heading_true.unwrap() - 124.1).abs() < 0.001);
+    assert!((state.heading_error().unwrap() - 15.1).abs() < 0.001);
+    assert_eq!(state.rudder_error(), Some(-2.5));
+
+    state.update(&NmeaSentence::from("$IIHTD,V,5,L,H,N,20,10,,,123.5,,,M,A,V,A,110.0*40"));
+    assert_eq!((state.off_heading, state.rudder_limit_reached), (Some(true), Some(false)));
+    assert_eq!(state.heading_error(), Some(13.5));
+
+    // A true heading command is compared with the true heading, the short way round.
+    state.update(&NmeaSentence::from("$IIHSC,40.0,T,,M*5B"));
+    assert_eq!((state.commanded_heading, state.heading()), (Some(40.0), state.heading_true));
+    assert!((state.heading_error().unwrap() + 84.1).abs() < 0.001);
+    state.heading_true = Some(350.0);
+    assert_eq!(state.heading_error(), Some(50.0));
+}
+
+#[test]
+fn test_arpa() {
+    use crate::arpa::{TargetEvent, TargetStatus, TargetTable};
+    use std::time::{Duration, Instant};
+
+    let t0 = Instant::now();
+    let mut table = TargetTable::new().with_max_age(Duration::from_secs(30)).with_danger_limits(0.5, 10.0);
+
+    // A relative bearing means nothing until own ship's heading is known.
+    let events = table.update(&NmeaSentence::from("$RATTM,01,2.5,45.0,R,10.0,180.0,T,1.5,5.0,N,,Q,,,A*24"), t0);
+    assert_eq!(events, vec![TargetEvent::New(1), TargetEvent::StatusChanged(1, TargetStatus::Query)]);
+    assert_eq!((table.get(1).unwrap().bearing_true, table.get(1).unwrap().course_true), (None, Some(180.0)));
+
+    table.update(&NmeaSentence::from("$RAHDT,90.0,T*08"), t0);
+    assert_eq!(table.update(&NmeaSentence::from("$RATLB,01,ALPHA,02,BRAVO*56"), t0), vec![TargetEvent::New(2)]);
+    let events = table.update(&NmeaSentence::from("$RATTM,01,2.5,45.0,R,10.0,180.0,T,0.3,5.0,N,SHIP,T,,123456.00,A*0D"), t0 + Duration::from_secs(20));
+    assert_eq!(events, vec![TargetEvent::StatusChanged(1, TargetStatus::Tracking), TargetEvent::Dangerous(1)]);
+    table.update(&NmeaSentence::from("$RATLL,01,4916.45,N,12311.12,W,SHIP,123456.00,T,*34"), t0 + Duration::from_secs(20));
+
+    let target = table.get(1).unwrap();
+    assert_eq!((target.distance, target.unit, target.bearing_true, target.speed), (Some(2.5), Some('N'), Some(135.0), Some(10.0)));
+    assert_eq!((target.cpa, target.tcpa, target.dangerous), (Some(0.3), Some(5.0), true));
+    assert_eq!((target.latitude, target.longitude), (Some(4916.45), Some(-12311.12)));
+    assert_eq!((target.name.as_deref(), target.label.as_deref()), (Some("SHIP"), Some("ALPHA")));
+
+    // Still dangerous, so no new event; OSD takes over the heading.
+    table.update(&NmeaSentence::from("$RAOSD,275.0,A,270.0,T,6.0,B,,,N*53"), t0 + Duration::from_secs(21));
+    assert_eq!(table.update(&NmeaSentence::from("$RATTM,01,2.5,45.0,R,10.0,180.0,T,0.3,5.0,N,SHIP,T,,123456.00,A*0D"), t0 + Duration::from_secs(21)), vec![]);
+    assert_eq!((table.own_heading(), table.get(1).unwrap().bearing_true), (Some(275.0), Some(320.0)));
+
+    assert_eq!(table.expire(t0 + Duration::from_secs(40)), vec![TargetEvent::Expired(2)]);
+    assert_eq!(table.targets().map(|t| t.number).collect::<Vec<_>>(), vec![1]);
+}
+
+#[test]
+fn test_ais_binary() {
+    use crate::ais::{Acknowledgement, AisTransmitter, BinaryPayload, SentMessage};
+
+    let mut payload = BinaryPayload::new();
+    payload.push(1, 6);
+    payload.push(40, 6);
+    assert_eq!(payload.armor(), ("1`".to_string(), 0));
+    payload.push(3, 2);
+    assert_eq!(payload.armor(), ("1`h".to_string(), 4));
+    assert_eq!(BinaryPayload::dearmor("1`h", 4), Some(payload.clone()));
+    assert_eq!(BinaryPayload::dearmor("1`x", 4), None);
+    assert_eq!((payload.len(), payload.get(6, 6), payload.get(12, 2), payload.get(12, 3)), (14, Some(40), Some(3), None));
+
+    let mut text = BinaryPayload::from_bytes(&[0x12, 0x34]);
+    text.push_text("Keep clear!");
+    assert_eq!((text.get(0, 16), text.text(16, 11)), (Some(0x1234), Some("KEEP CLEAR!".to_string())));
+
+    let mut transmitter = AisTransmitter::new("!AI");
+    let abm = transmitter.addressed(2655619, 2, 6, &payload).unwrap();
+    assert_eq!(abm.len(), 1);
+    assert_eq!(abm[0].to_string(), "!AIABM,1,1,0,002655619,2,6,1`h,4*45");
+    assert!(abm[0].validate().is_ok());
+    if let NmeaSentence::ABM(abm) = NmeaSentence::from(&abm[0].to_string()) {
+        assert_eq!((abm.mmsi(), abm.message_id(), abm.payload()), (Some("002655619".to_string()), Some(6), Some(payload.clone())));
+    } else {
+        panic!("ABM did not parse");
+    }
+    assert_eq!(transmitter.addressed(2655619, 2, 6, &payload).unwrap()[0].sequential_id(), Some(1));
+
+    // A long broadcast is split, with the fill bits in the last sentence only.
+    let mut long = BinaryPayload::new();
+    (0..100).for_each(|i| long.push(i, 7));
+    let bbm = transmitter.broadcast(0, 8, &long).unwrap();
+    assert_eq!(bbm.len(), 3);
+    assert!(bbm.iter().all(|s| s.to_string().len() + 2 <= 82 && s.total() == Some(3) && s.sequential_id() == Some(0)));
+    assert_eq!(bbm.iter().map(|s| s.fill_bits()).collect::<Vec<_>>(), vec![Some(0), Some(0), Some(2)]);
+    let data: String = bbm.iter().map(|s| s.data().unwrap()).collect();
+    assert_eq!(BinaryPayload::dearmor(&data, 2), Some(long));
+
+    let abk = |line: &str| AbkData::from(&line.to_string());
+    assert_eq!(transmitter.pending().len(), 3);
+    assert_eq!(
+        transmitter.acknowledge(&abk("$AIABK,002655619,B,6,1,0*23")),
+        Some((SentMessage { mmsi: Some(2655619), message_id: 6, sequential_id: 1 }, Acknowledgement::Received))
+    );
+    assert_eq!(transmitter.acknowledge(&abk("$AIABK,366999999,A,6,1,0*29")), None);
+    assert_eq!(transmitter.acknowledge(&abk("$AIABK,,A,8,0,3*16")).map(|(_, a)| a), Some(Acknowledgement::Broadcast));
+    assert_eq!(transmitter.pending(), &[SentMessage { mmsi: Some(2655619), message_id: 6, sequential_id: 0 }]);
+
+    // Message 8 carries at most 952 bits, and no message more than nine sentences.  A
+    // refused message does not use up a sequential identifier.
+    let bits = |n: usize| {
+        let mut payload = BinaryPayload::new();
+        (0..n).for_each(|i| payload.push(i as u64 & 1, 1));
+        payload
+    };
+    assert!(transmitter.broadcast(0, 8, &bits(952)).is_some());
+    assert!(transmitter.broadcast(0, 8, &bits(953)).is_none());
+    let bbm = transmitter.broadcast(0, 70, &bits(9 * 57 * 6)).unwrap();
+    assert!(bbm.len() == 9 && bbm.iter().all(|s| s.to_string().len() + 2 <= 82 && s.total() == Some(9)));
+    assert!(transmitter.broadcast(0, 70, &bits(9 * 57 * 6 + 1)).is_none());
+    assert_eq!(transmitter.broadcast(0, 8, &bits(8)).unwrap()[0].sequential_id(), Some(3));
+}
+
+#[test]
+fn test_ais_static_data() {
+    use crate::ais::{NavigationalStatus, RegionalFlags, ShipType};
+
+    let ssd = SsdData::from(&"$AISSD,PD2345,SEA BREEZE,20,8,3,4,0,AI*25".to_string());
+    assert_eq!((ssd.call_sign(), ssd.name()), (Some("PD2345".to_string()), Some("SEA BREEZE".to_string())));
+    assert_eq!((ssd.length(), ssd.beam(), ssd.dte(), ssd.source_identifier()), (Some(28), Some(7), Some(0), Some("AI".to_string())));
+    assert!(ssd.validate_ais().is_ok());
+    let ssd = SsdData::from(&"$AISSD,PD2345,Sea breeze,600,8,3,4,0,AI*11".to_string());
+    assert_eq!(ssd.validate_ais(), Err(FieldError::OutOfRange("bow_distance")));
+    assert_eq!((ssd.length(), ssd.beam()), (None, Some(7)));
+    let ssd = SsdData::from(&"$AISSD,CALL,NAME,60000,60000,200,200,0,AI*71".to_string());
+    assert_eq!((ssd.length(), ssd.beam()), (None, None));
+    let ssd = SsdData::builder("$AI").call_sign("PD2345").name("Sea breeze").build();
+    assert_eq!(ssd.validate_ais(), Err(FieldError::InvalidCharacter("name")));
+    let ssd = SsdData::builder("$AI").call_sign("PD2345").name("THE LONGEST NAME AFLOAT").build();
+    assert_eq!(ssd.validate_ais(), Err(FieldError::TooLong("name")));
+
+    let vsd = VsdData::from(&"$AIVSD,70,8.4,12,ROTTERDAM,093000.00,24,11,5,0*26".to_string());
+    assert_eq!((vsd.ship_type(), vsd.draught(), vsd.persons_on_board()), (Some(ShipType::Cargo(0)), Some(8.4), Some(12)));
+    assert_eq!((vsd.destination(), vsd.eta_day(), vsd.eta_month()), (Some("ROTTERDAM".to_string()), Some(24), Some(11)));
+    assert_eq!((vsd.navigational_status(), vsd.regional_flags()), (Some(NavigationalStatus::Moored), Some(RegionalFlags(0))));
+    assert!(vsd.validate_ais().is_ok());
+    let vsd = VsdData::from(&"$AIVSD,36,30.0,,,,,,16,*7A".to_string());
+    assert_eq!(vsd.validate_ais(), Err(FieldError::OutOfRange("draught")));
+    assert_eq!(vsd.navigational_status(), None);
+
+    let vsd = VsdData::builder("$AI")
+        .ship_type(ShipType::Sailing)
+        .draught(2.1)
+        .destination("HARLINGEN")
+        .navigational_status(NavigationalStatus::UnderWaySailing)
+        .regional_flags(RegionalFlags::default().with(1))
+        .build();
+    assert_eq!(vsd.to_string(), "$AIVSD,36,2.1,,HARLINGEN,,,,8,2*1B");
+    assert!(vsd.regional_flags().unwrap().is_set(1));
+    assert_eq!(RegionalFlags::default().with(3).with(7), RegionalFlags(8));
+    assert_eq!(RegionalFlags::default().with(4), RegionalFlags(0));
+    assert_eq!((0..=255).map(|t| u8::from(ShipType::from(t))).collect::<Vec<_>>(), (0..=255).collect::<Vec<_>>());
+
+    let air = AirData::builder("$AI").interrogate(2655619, 5).build();
+    assert_eq!(air.to_string(), "$AIAIR,002655619,5,,,,,,*5D");
+    let aca = AcaData::builder("$AI")
+        .sequence_number(1)
+        .ne_latitude(5230.0)
+        .ne_longitude(500.0)
+        .sw_latitude(5200.0)
+        .sw_longitude(430.0)
+        .transition_zone_size(5.0)
+        .channel_a(2087)
+        .channel_b(2088)
+        .power_level(1)
+        .build();
+    assert!(aca.validate().is_ok());
+    assert_eq!((aca.channel_a(), aca.channel_b(), aca.sw_longitude()), (Some(2087), Some(2088), Some(430.0)));
+}
+
+#[test]
+fn test_engine_monitor() {
+    use crate::engine::{EngineEvent, EngineMonitor, TelegraphOrder};
+    use std::time::{Duration, Instant};
+
+    let t0 = Instant::now();
+    let at = |seconds: u64| t0 + Duration::from_secs(seconds);
+    let mut monitor = EngineMonitor::new().with_max_rpm(1900.0).with_max_coolant_temperature(90.0);
+
+    assert_eq!(monitor.update(&NmeaSentence::from("$ERRPM,E,1,1800,12.5,A*50"), at(0)), vec![]);
+    assert_eq!(monitor.update(&NmeaSentence::from("$ERRPM,E,1,2000,,A*43"), at(30)), vec![EngineEvent::Overspeed(1)]);
+    assert_eq!(monitor.update(&NmeaSentence::from("$ERRPM,E,1,0,,A*71"), at(60)), vec![EngineEvent::Normal(1)]);
+    monitor.update(&NmeaSentence::from("$ERRPM,S,1,600,50.0,A*7A"), at(60));
+    // Stopped time and gaps in the stream do not count.
+    monitor.update(&NmeaSentence::from("$ERRPM,E,1,1800,12.5,A*50"), at(120));
+    monitor.update(&NmeaSentence::from("$ERRPM,E,1,1800,12.5,A*50"), at(1000));
+
+    let engine = monitor.get(1).unwrap();
+    assert_eq!((engine.rpm, engine.shaft_rpm, engine.pitch), (Some(1800.0), Some(600.0), Some(12.5)));
+    assert_eq!(engine.running_time(), Duration::from_secs(60));
+    assert!((engine.hours() - 1.0 / 60.0).abs() < 1e-9);
+    assert_eq!(engine.average_rpm(), Some(1900.0));
+
+    let events = monitor.update(&NmeaSentence::from("$ERXDR,C,95.5,C,ENGINE#1,P,3.2,B,ENGINE#1,C,20.0,C,AIRTEMP*39"), at(1000));
+    assert_eq!(events, vec![EngineEvent::Overheat(1)]);
+    monitor.update(&NmeaSentence::from("$ERXDR,U,14.1,V,ALT#1,R,0.005,l,FUEL#1*16"), at(1000));
+    monitor.update(&NmeaSentence::from("$ERXDR,U,14.1,V,ALT#1,R,0.005,l,FUEL#1*16"), at(1060));
+    monitor.update(&NmeaSentence::from("$ERETL,120000.00,O,03,30,B,1*5B"), at(1060));
+    monitor.update(&NmeaSentence::from("$ERETL,120001.00,A,03,30,C,1*55"), at(1061));
+
+    let engine = monitor.get(1).unwrap();
+    assert_eq!((engine.coolant_temperature.map(|t| t.round()), engine.oil_pressure.map(|p| p.round())), (Some(96.0), Some(320_000.0)));
+    assert_eq!((engine.alternator_voltage.map(|v| (v * 10.0).round()), engine.overheat), (Some(141.0), true));
+    assert!((engine.fuel_rate.unwrap() - 18.0).abs() < 1e-3);
+    assert!((engine.fuel_used() - 0.3).abs() < 1e-4);
+    assert_eq!((engine.telegraph_order, engine.telegraph_answer), (Some(TelegraphOrder::AheadHalf), Some(TelegraphOrder::AheadHalf)));
+    assert_eq!(monitor.engines().count(), 1);
+
+    let etl = EtlData::from(&"$ERETL,120000.00,O,03,30,B,1*5B".to_string());
+    assert_eq!((etl.message_type(), etl.sub_telegraph_position(), etl.operating_location()), (Some('O'), Some(30), Some('B')));
+    assert!(etl.validate().is_ok());
+}
+
+#[test]
+fn test_ship_systems() {
+    use crate::systems::*;
+
+    let dor = DorData::from(&"$IIDOR,E,120000.00,WT,03,002,011,O,O,ENGINE ROOM AFT*5D".to_string());
+    assert_eq!((dor.report_type(), dor.panel(), dor.door_status(), dor.watertight_setting()), (Some(ReportType::Event), Some(DoorPanel::Watertight), Some(DoorStatus::Open), Some(WatertightSetting::Harbour)));
+    assert_eq!((dor.division(), dor.subdivision(), dor.door_number()), (Some("03".to_string()), Some(2), Some(11)));
+    assert!(dor.validate().is_ok());
+    let fir = FirData::from(&"$FRFIR,E,120000.00,FS,05,001,012,A,V,GALLEY*12".to_string());
+    assert_eq!((fir.detector(), fir.zone(), fir.detector_loop(), fir.detector_number()), (Some(FireDetector::Smoke), Some("05".to_string()), Some(1), Some(12)));
+    assert_eq!((fir.fire_condition(), fir.acknowledged(), fir.description()), (Some(FireCondition::Activated), Some(false), Some("GALLEY".to_string())));
+    let wat = WatData::from(&"$IIWAT,E,120000.00,WL,01,001,003,H,N,HOLD 1*06".to_string());
+    assert_eq!((wat.water_system(), wat.water_condition(), wat.overridden()), (Some(WaterSystem::WaterLevel), Some(WaterCondition::High), Some(false)));
+
+    let hmr = HmrData::from(&"$IIHMR,GYRO1,COMP1,5.0,3.0,A,101.5,A,T,,,,95.0,A,M,-1.5,2.0,W*72".to_string());
+    assert_eq!((hmr.sensor_1_true_heading(), hmr.sensor_2_true_heading()), (Some(101.5), Some(91.5)));
+    assert_eq!((hmr.difference(), hmr.actual_difference(), hmr.limit_exceeded()), (Some(10.0), Some(3.0), Some(true)));
+
+    let mut systems = ShipSystems::new();
+    let door = Location { system: "WT".to_string(), division: "03".to_string(), subdivision: Some(2), number: Some(11) };
+    let events = systems.update(&NmeaSentence::from("$IIDOR,E,120000.00,WT,03,002,011,O,O,ENGINE ROOM AFT*5D"));
+    assert_eq!(events, vec![SystemEvent::WatertightSetting(WatertightSetting::Harbour), SystemEvent::Door(door.clone(), DoorStatus::Open)]);
+    assert_eq!(systems.update(&NmeaSentence::from("$IIDOR,E,120000.00,WT,03,002,011,O,O,ENGINE ROOM AFT*5D")), vec![]);
+    assert_eq!(systems.open_doors().collect::<Vec<_>>(), vec![&door]);
+    let events = systems.update(&NmeaSentence::from("$IIDOR,E,120100.00,WT,03,002,011,C,C,ENGINE ROOM AFT*5C"));
+    assert_eq!(events, vec![SystemEvent::WatertightSetting(WatertightSetting::Sea), SystemEvent::Door(door, DoorStatus::Closed)]);
+    assert_eq!(systems.open_doors().count(), 0);
+    let events = systems.update(&NmeaSentence::from("$IIDOR,F,120200.00,WT,,,,,,LOOP FAULT*69"));
+    assert_eq!(events, vec![SystemEvent::Fault(MessageType::DOR, Some("LOOP FAULT".to_string()))]);
+
+    systems.update(&NmeaSentence::from("$FRFIR,E,120000.00,FS,05,001,012,A,V,GALLEY*12"));
+    let events = systems.update(&NmeaSentence::from("$FRFIR,S,120000.00,FS,05,001,001,A,V,*1C"));
+    let section = Location { system: "FS".to_string(), division: "05".to_string(), subdivision: Some(1), number: None };
+    assert_eq!(events, vec![SystemEvent::Fire(section, FireCondition::Activated)]);
+    assert_eq!(systems.active_fires().count(), 2);
+    systems.update(&NmeaSentence::from("$IIWAT,E,120000.00,WL,01,001,003,H,N,HOLD 1*06"));
+    assert_eq!(systems.water_alarms().map(|l| l.number).collect::<Vec<_>>(), vec![Some(3)]);
+
+    systems.update(&NmeaSentence::from("$IIHMS,GYRO1,COMP1,5.0*43"));
+    assert_eq!(systems.heading_limit, Some(5.0));
+    assert_eq!(systems.update(&NmeaSentence::from("$IIHMR,GYRO1,COMP1,5.0,3.0,A,101.5,A,T,,,,95.0,A,M,-1.5,2.0,W*72")), vec![SystemEvent::HeadingDifference(true)]);
+    assert_eq!(systems.update(&NmeaSentence::from("$IIHMR,GYRO1,COMP1,,,A,101.5,A,T,,,,101.0,A,M,0.5,,*1F")), vec![SystemEvent::HeadingDifference(false)]);
+    assert_eq!(systems.heading_difference, Some(0.0));
+}
+
+#[test]
+fn test_current_profile() {
+    use crate::current::{CurrentProfiles, SetAndDrift};
+
+    let cur = CurData::from(&"$VWCUR,A,1,2,10.0,30.0,R,0.8,10.0,90.0,T,B*43".to_string());
+    assert_eq!((cur.data_set(), cur.layer(), cur.depth(), cur.direction_true()), (Some(1), Some(2), Some(10.0), Some(120.0)));
+    assert_eq!((cur.speed(), cur.reference_layer_depth(), cur.speed_reference()), (Some(0.8), Some(10.0), Some('B')));
+    assert!(cur.validate().is_ok());
+
+    let mut profiles = CurrentProfiles::new();
+    for line in [
+        "$VWCUR,A,1,2,10.0,30.0,R,0.8,10.0,90.0,T,B*43",
+        "$VWCUR,A,1,1,2.0,45.0,T,1.2,10.0,90.0,T,B*7C",
+        "$VWCUR,A,1,1,2.0,50.0,T,1.3,10.0,90.0,T,B*79",
+        "$VWCUR,V,2,1,2.0,50.0,T,1.3,10.0,90.0,T,B*6D",
+    ] {
+        if let NmeaSentence::CUR(cur) = NmeaSentence::from(line) {
+            profiles.push(&cur);
+        }
+    }
+    assert_eq!(profiles.profiles().count(), 1);
+    let profile = profiles.get(1).unwrap();
+    assert_eq!(profile.layers.iter().map(|l| l.layer).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(profile.surface().map(|l| (l.direction, l.speed)), Some((Some(50.0), Some(1.3))));
+    assert_eq!(profile.at_depth(8.0).map(|l| l.layer), Some(2));
+
+    let mut calculator = SetAndDrift::new();
+    assert_eq!(calculator.current(), None);
+    calculator.update(&NmeaSentence::from("$GPRMC,120000.00,A,5230.000,N,00430.000,E,5.0,36.87,191026,,,A*5C"));
+    calculator.update(&NmeaSentence::from("$IIVHW,0.0,T,,M,4.0,N,7.4,K*7C"));
+    let current = calculator.current().unwrap();
+    assert!((current.set - 90.0).abs() < 0.01 && (current.drift - 3.0).abs() < 0.001);
+    assert_eq!(calculator.to_vdr("$II").unwrap().to_string(), "$IIVDR,90,T,,,3,N*60");
+    let cur = calculator.to_cur("$II", 0).unwrap();
+    assert_eq!((cur.direction_true(), cur.speed(), cur.speed_reference()), (Some(90.0), Some(3.0), Some('P')));
+}
+
+#[test]
+fn test_time_source() {
+    use crate::clock::ClockSource;
+    use chrono::{Duration, FixedOffset, TimeZone};
+
+    let zda = ZdaData::from(&"$GPZDA,120000.00,19,10,2026,-02,30*46".to_string());
+    let utc = Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap();
+    assert_eq!(zda.datetime_utc(), Some(utc));
+    assert_eq!(zda.zone_offset(ZoneConvention::Description), FixedOffset::east_opt(9000));
+    assert_eq!(zda.local_datetime().unwrap().to_rfc3339(), "2026-10-19T14:30:00+02:30");
+    assert_eq!(zda.local_datetime_with(ZoneConvention::Offset).unwrap().to_rfc3339(), "2026-10-19T09:30:00-02:30");
+    let zda = ZdaData::from(&"$GPZDA,234500.00,31,12,2026,-00,30*4F".to_string());
+    assert_eq!(zda.local_datetime().unwrap().to_rfc3339(), "2027-01-01T00:15:00+00:30");
+    let zda = ZdaData::from(&"$GPZDA,120000.00,19,10,2026,05,30*6C".to_string());
+    assert_eq!(zda.zone_offset(ZoneConvention::Offset), FixedOffset::east_opt(19800));
+
+    let zdl = ZdlData::from(&"$IIZDL,123015.5,4.2,W*1E".to_string());
+    assert_eq!(zdl.duration_to_point(), Some(std::time::Duration::from_millis(45_015_500)));
+    assert_eq!((zdl.distance_to_point(), zdl.point_type()), (Some(4.2), Some('W')));
+
+    let mut clock = ClockSource::new().with_latency(Duration::milliseconds(100));
+    assert_eq!(clock.offset(), None);
+    let sentence = NmeaSentence::from("$GPZDA,120001.00,19,10,2026,00,00*6B");
+    assert_eq!(clock.update(&sentence, utc + Duration::milliseconds(1300)), Some(Duration::milliseconds(-200)));
+    let sentence = NmeaSentence::from("$GPZDA,120002.00,19,10,2026,00,00*68");
+    clock.update(&sentence, utc + Duration::milliseconds(2100));
+    assert_eq!((clock.offset(), clock.jitter(), clock.samples()), (Some(Duration::milliseconds(-100)), Some(Duration::milliseconds(200)), 2));
+    assert_eq!(clock.now(utc), Some(utc - Duration::milliseconds(100)));
+    clock.update(&sentence, utc - Duration::seconds(60));
+    assert_eq!((clock.offset(), clock.samples()), (Some(Duration::milliseconds(62100)), 1));
+}
+
+#[test]
+fn test_gnss_constellations() {
+    use crate::gnss::{GnssSystem, IntegrityStatus, PositionMode};
+
+    let gns = GnsData::from(&"$GNGNS,112257.00,3844.24011,N,00908.43828,W,AAN,10,0.9,31.4,52.5,,,S*4C".to_string());
+    assert!(gns.validate().is_ok());
+    assert_eq!(gns.mode(), Some("AAN".to_string()));
+    assert_eq!(gns.modes().collect::<Vec<_>>(), vec![
+        (GnssSystem::Gps, Some(PositionMode::Autonomous)),
+        (GnssSystem::Glonass, Some(PositionMode::Autonomous)),
+        (GnssSystem::Galileo, Some(PositionMode::NoFix)),
+    ]);
+    assert_eq!((gns.mode_of(GnssSystem::Galileo), gns.mode_of(GnssSystem::BeiDou)), (Some(PositionMode::NoFix), None));
+    assert_eq!(gns.integrity(), Some(IntegrityStatus::Safe));
+
+    let gsa = GsaData::from(&"$GNGSA,A,3,80,71,73,79,69,,,,,,,,1.83,1.09,1.47,2*09".to_string());
+    assert_eq!((gsa.system(), gsa.satellite_ids().count()), (Some(GnssSystem::Glonass), 5));
+    let gsa = GsaData::from(&"$GNGSA,A,3,05,07,,,,,,,,,,,2.0,1.0,1.7*2B".to_string());
+    assert_eq!(gsa.system(), Some(GnssSystem::Gps));
+
+    let gsv = GsvData::from(&"$GAGSV,1,1,02,04,40,120,35,11,50,210,41,7*77".to_string());
+    assert_eq!((gsv.system(), gsv.signal_id(), gsv.satellite_info().map(|s| s.len())), (Some(GnssSystem::Galileo), Some(7), Some(2)));
+    let gsv = GsvData::from(&"$GPGSV,3,1,11,03,03,111,00,04,15,270,00,06,01,010,00,13,06,292,00*74".to_string());
+    assert_eq!(gsv.signal_id(), None);
+    assert_eq!((GnssSystem::from_system_id(6), GnssSystem::NavIc.system_id(), GnssSystem::from_system_id(7)), (Some(GnssSystem::NavIc), 6, None));
+}
+
+#[test]
+fn test_nmea_version() {
+    use crate::version::{NmeaVersion, VersionProfile};
+
+    let rmc = RmcData::from(&"$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W,D*02".to_string());
+    assert_eq!((rmc.version(), rmc.mode(), rmc.nav_status()), (Some(NmeaVersion::V2_3), Some('D'), None));
+    let rmc = RmcData::from(&"$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A".to_string());
+    assert_eq!((rmc.version(), rmc.mode()), (Some(NmeaVersion::V2_0), None));
+    let rmc = RmcData::from(&"$YDRMC,173619.00,A,4844.8692,N,12313.7747,W,0.0,221.9,210523,15.7,E,A,C*7D".to_string());
+    assert_eq!((rmc.version(), rmc.mode()), (Some(NmeaVersion::V4_10), Some('A')));
+    assert_eq!(rmc.integrity(), Some(crate::gnss::IntegrityStatus::Caution));
+
+    // The 1.5 layout of VTG has no unit letters; the accessors supply them.
+    let values = |vtg: &VtgData| {
+        [vtg.cog_true(), vtg.cog_magnetic(), vtg.sog_knots(), vtg.sog_kph()].map(|v| v.map(|v| (v.value(), v.unit())))
+    };
+    let vtg = VtgData::from(&"$GPVTG,054.7,034.4,005.5,010.2*54".to_string());
+    assert_eq!((vtg.version(), vtg.mode()), (Some(NmeaVersion::V1_5), None));
+    let old = values(&vtg);
+    assert_eq!(old, [Some((54.7, 'T')), Some((34.4, 'M')), Some((5.5, 'N')), Some((10.2, 'K'))]);
+    assert_eq!(vtg.fields().collect::<Vec<_>>(), vec![
+        ("cog_true", Value::Number(54.7)),
+        ("cog_magnetic", Value::Number(34.4)),
+        ("sog_knots", Value::Number(5.5)),
+        ("sog_kph", Value::Number(10.2)),
+    ]);
+    assert_eq!(NmeaSentence::from(&vtg.to_string()).fields().collect::<Vec<_>>(), vtg.fields().collect::<Vec<_>>());
+    assert!(vtg.validate().is_ok());
+    let vtg = VtgData::from(&"$GPVTG,054.7,T,034.4,M,005.5,N,010.2,K,A*25".to_string());
+    assert_eq!((vtg.version(), values(&vtg)), (Some(NmeaVersion::V2_3), old));
+    #[cfg(feature = "serde")]
+    {
+        let vtg = VtgData::from(&"$GPVTG,054.7,034.4,005.5,010.2*54".to_string());
+        let back: VtgData = serde_json::from_value(serde_json::to_value(&vtg).unwrap()).unwrap();
+        assert_eq!(values(&back), old);
+    }
+    let hdt = HdtData::from(&"$HEHDT,274.07,T*19".to_string());
+    assert_eq!(hdt.version(), None);
+    assert_eq!(NmeaVersion::V4_11.field_count(MessageType::RMC), Some(13));
+    assert_eq!(NmeaVersion::V1_5.field_count(MessageType::RMC), None);
+
+    let mut profile = VersionProfile::new();
+    for line in [
+        "$GPGLL,4916.45,N,12311.12,W,225444,A*31",
+        "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W,D*02",
+        "$GNGNS,112257.00,3844.24011,N,00908.43828,W,AANNNA,10,0.9,31.4,52.5,,,S*0D",
+        "$GPVTG,054.7,034.4,005.5,010.2*54",
+    ] {
+        profile.learn(&NmeaSentence::from(line));
+    }
+    assert_eq!(profile.sources().collect::<Vec<_>>(), vec![("GN", NmeaVersion::V4_11), ("GP", NmeaVersion::V2_3)]);
+    assert_eq!(profile.get("II"), None);
+}
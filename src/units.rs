@@ -0,0 +1,103 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use crate::ValueWithUnit;
+
+pub(crate) const KNOTS_TO_MPS: f64 = 1852.0 / 3600.0;
+pub(crate) const NAUTICAL_MILE_METERS: f64 = 1852.0;
+pub(crate) const FEET_TO_METERS: f64 = 0.3048;
+pub(crate) const FATHOMS_TO_METERS: f64 = 1.8288;
+pub(crate) const KELVIN_OFFSET: f64 = 273.15;
+pub(crate) const BAR_TO_PASCAL: f64 = 100_000.0;
+pub(crate) const INCH_MERCURY_TO_PASCAL: f64 = 3386.389;
+
+pub(crate) fn radians(degrees: f32) -> f64 {
+    (degrees as f64).to_radians()
+}
+
+// Angles relative to the bow run from -pi to pi.
+pub(crate) fn relative_radians(degrees: f32) -> f64 {
+    let degrees = degrees as f64;
+    let degrees = if degrees > 180.0 { degrees - 360.0 } else { degrees };
+    degrees.to_radians()
+}
+
+pub(crate) fn speed_mps(speed: &ValueWithUnit<f32>) -> Option<f64> {
+    match speed.unit {
+        'N' => Some(speed.value as f64 * KNOTS_TO_MPS),
+        'K' => Some(speed.value as f64 / 3.6),
+        'M' => Some(speed.value as f64),
+        _ => None,
+    }
+}
+
+pub(crate) fn distance_meters(distance: &ValueWithUnit<f32>) -> Option<f64> {
+    match distance.unit {
+        'N' => Some(distance.value as f64 * NAUTICAL_MILE_METERS),
+        'K' => Some(distance.value as f64 * 1000.0),
+        'M' => Some(distance.value as f64),
+        'f' => Some(distance.value as f64 * FEET_TO_METERS),
+        'F' => Some(distance.value as f64 * FATHOMS_TO_METERS),
+        _ => None,
+    }
+}
+
+pub(crate) fn kelvin(temperature: &ValueWithUnit<f32>) -> Option<f64> {
+    match temperature.unit {
+        'C' => Some(temperature.value as f64 + KELVIN_OFFSET),
+        'F' => Some((temperature.value as f64 - 32.0) / 1.8 + KELVIN_OFFSET),
+        'K' => Some(temperature.value as f64),
+        _ => None,
+    }
+}
+
+pub(crate) fn pascal(pressure: &ValueWithUnit<f32>) -> Option<f64> {
+    match pressure.unit {
+        'B' => Some(pressure.value as f64 * BAR_TO_PASCAL),
+        'I' => Some(pressure.value as f64 * INCH_MERCURY_TO_PASCAL),
+        'P' => Some(pressure.value as f64),
+        _ => None,
+    }
+}
+
+// Variation and deviation are east-positive in Signal K.
+pub(crate) fn signed_angle(angle: &ValueWithUnit<f32>) -> f64 {
+    if angle.unit == 'W' { -radians(angle.value) } else { radians(angle.value) }
+}
+
+// NMEA coordinates are ddmm.mmmm; Signal K positions are decimal degrees.
+pub(crate) fn decimal_degrees(coordinate: &ValueWithUnit<f32>) -> f64 {
+    let raw = coordinate.value as f64;
+    let whole = (raw / 100.0).trunc();
+    let decimal = whole + (raw - whole * 100.0) / 60.0;
+    if coordinate.unit == 'S' || coordinate.unit == 'W' { -decimal } else { decimal }
+}
+
+pub(crate) fn datetime(date: Option<NaiveDate>, time: Option<NaiveTime>) -> Option<DateTime<Utc>> {
+    match (date, time) {
+        (Some(d), Some(t)) => Some(DateTime::<Utc>::from_naive_utc_and_offset(NaiveDateTime::new(d, t), Utc)),
+        _ => None,
+    }
+}
+
+pub(crate) fn format_number(n: f64, decimals: usize) -> String {
+    format!("{:.*}", decimals, n)
+}
+
+pub(crate) fn format_angle(radians: f64) -> String {
+    format_number(radians.to_degrees().rem_euclid(360.0), 1)
+}
+
+pub(crate) fn format_time(t: &DateTime<Utc>) -> String {
+    format!("{:02}{:02}{:02}.{:02}", t.hour(), t.minute(), t.second(), t.nanosecond() / 10_000_000)
+}
+
+pub(crate) fn format_coordinate(degrees: f64, width: usize, positive: char, negative: char) -> (String, String) {
+    let hemisphere = if degrees < 0.0 { negative } else { positive };
+    let degrees = degrees.abs();
+    let mut whole = degrees.trunc();
+    let mut minutes = (degrees - whole) * 60.0;
+    if minutes >= 59.99995 {
+        whole += 1.0;
+        minutes = 0.0;
+    }
+    (format!("{:0width$}{:07.4}", whole as u32, minutes, width = width), hemisphere.to_string())
+}
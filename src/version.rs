@@ -0,0 +1,141 @@
+use crate::*;
+use self::NmeaVersion::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NmeaVersion {
+    V1_5,
+    V2_0,
+    V2_3,
+    V4_10,
+    V4_11,
+}
+
+// The field counts of the sentences whose layout changed, by the version that brought the
+// layout in.  NMEA 3.x sends the 2.3 layouts.
+const LAYOUTS: &[(MessageType, &[(NmeaVersion, usize)])] = &[
+    (MessageType::APB, &[(V2_0, 14), (V2_3, 15)]),
+    (MessageType::GLL, &[(V2_0, 6), (V2_3, 7)]),
+    (MessageType::GNS, &[(V2_3, 12), (V4_10, 13)]),
+    (MessageType::GSA, &[(V2_0, 17), (V4_10, 18)]),
+    (MessageType::RMC, &[(V2_0, 11), (V2_3, 12), (V4_10, 13)]),
+    (MessageType::VTG, &[(V1_5, 4), (V2_0, 8), (V2_3, 9)]),
+    (MessageType::XTE, &[(V2_0, 5), (V2_3, 6)]),
+];
+
+impl NmeaVersion {
+    // The number of fields a sentence has in this version, for the sentences in LAYOUTS.
+    pub fn field_count(self, message_type: MessageType) -> Option<usize> {
+        let (_, layouts) = LAYOUTS.iter().find(|(t, _)| *t == message_type)?;
+        layouts.iter().rev().find(|(v, _)| *v <= self).map(|(_, count)| *count)
+    }
+
+    // The earliest version whose layout the sentence has, or None for sentences that have
+    // not changed or a field count no version has.  Fields past the latest layout are taken
+    // to be a device's own additions.
+    pub fn detect<B: FieldSource>(sentence: &NmeaSentence<B>) -> Option<Self> {
+        detect(sentence.message_type(), sentence.base()?)
+    }
+}
+
+pub(crate) fn detect<S: FieldSource + ?Sized>(message_type: MessageType, base: &S) -> Option<NmeaVersion> {
+    let count = base.field_count();
+    match message_type {
+        // NavIC and QZSS have system IDs from 4.11; GNS has six modes from then.
+        MessageType::GSA if base.get::<u8>(17).is_some_and(|id| id >= 5) => Some(V4_11),
+        MessageType::GNS if base.field(5).is_some_and(|m| m.len() == 6) => Some(V4_11),
+        MessageType::GSV => (count > 3 && (count - 3) % 4 == 1).then_some(V4_10),
+        _ => {
+            let (_, layouts) = LAYOUTS.iter().find(|(t, _)| *t == message_type)?;
+            let (latest, most) = *layouts.last()?;
+            if count > most {
+                return Some(latest);
+            }
+            layouts.iter().find(|(_, c)| *c == count).map(|(v, _)| *v)
+        }
+    }
+}
+
+// The field tables of older layouts whose fields sit elsewhere than in FIELDS.
+const OLD_FIELDS: &[(MessageType, NmeaVersion, &[FieldInfo])] = &[
+    (MessageType::VTG, V1_5, &[moved(VtgData::FIELDS[0], 0), moved(VtgData::FIELDS[1], 1), moved(VtgData::FIELDS[2], 2), moved(VtgData::FIELDS[3], 3)]),
+];
+
+const fn moved(field: FieldInfo, index: usize) -> FieldInfo {
+    FieldInfo { index, unit_index: None, ..field }
+}
+
+// The field table for the layout the sentence has.
+pub(crate) fn layout_fields<S: FieldSource + ?Sized>(message_type: MessageType, base: &S, fields: &'static [FieldInfo]) -> &'static [FieldInfo] {
+    if !OLD_FIELDS.iter().any(|(t, _, _)| *t == message_type) {
+        return fields;
+    }
+    let version = detect(message_type, base);
+    OLD_FIELDS.iter().find(|(t, v, _)| *t == message_type && Some(*v) == version).map_or(fields, |(_, _, old)| old)
+}
+
+// VTG before 2.0 sent its four values without the unit letters after them.
+impl<B: FieldSource> VtgData<B> {
+    pub fn cog_true(&self) -> Option<ValueWithUnit<f32>> {
+        self.layout_value("cog_true")
+    }
+
+    pub fn cog_magnetic(&self) -> Option<ValueWithUnit<f32>> {
+        self.layout_value("cog_magnetic")
+    }
+
+    pub fn sog_knots(&self) -> Option<ValueWithUnit<f32>> {
+        self.layout_value("sog_knots")
+    }
+
+    pub fn sog_kph(&self) -> Option<ValueWithUnit<f32>> {
+        self.layout_value("sog_kph")
+    }
+
+    pub(crate) fn layout_value<T: FromStr>(&self, name: &str) -> Option<ValueWithUnit<T>> {
+        let (index, unit) = match name {
+            "cog_true" => (0, 'T'),
+            "cog_magnetic" => (1, 'M'),
+            "sog_knots" => (2, 'N'),
+            "sog_kph" => (3, 'K'),
+            _ => return None,
+        };
+        match self.version() {
+            Some(V1_5) => Some(ValueWithUnit { value: self.base.get(index)?, unit }),
+            _ => self.base.get_pair(index * 2, index * 2 + 1),
+        }
+    }
+}
+
+// The version each source is sending, learned from the stream.  A source is taken to send
+// the latest version any of its sentences has shown, as sentences that did not change say
+// nothing against it.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Default)]
+pub struct VersionProfile {
+    sources: BTreeMap<String, NmeaVersion>,
+}
+
+#[cfg(feature = "alloc")]
+impl VersionProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Returns the version of the sentence's source once it has one.
+    pub fn learn<B: FieldSource>(&mut self, sentence: &NmeaSentence<B>) -> Option<NmeaVersion> {
+        let talker = sentence.talker();
+        if let Some(version) = NmeaVersion::detect(sentence) {
+            let known = self.sources.entry(talker.to_string()).or_insert(version);
+            *known = version.max(*known);
+        }
+        self.get(talker)
+    }
+
+    pub fn get(&self, talker: &str) -> Option<NmeaVersion> {
+        self.sources.get(talker).copied()
+    }
+
+    pub fn sources(&self) -> impl Iterator<Item = (&str, NmeaVersion)> {
+        self.sources.iter().map(|(talker, version)| (talker.as_str(), *version))
+    }
+}